@@ -0,0 +1,44 @@
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use blstrs::*;
+use groupy::CurveProjective;
+
+fn random_coeffs(log_size: u32) -> (EvaluationDomain, Vec<Scalar>) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let domain = EvaluationDomain::new(1 << log_size).unwrap();
+    let coeffs = (0..domain.size())
+        .map(|_| Scalar::random(&mut rng))
+        .collect();
+    (domain, coeffs)
+}
+
+#[bench]
+fn bench_domain_fft_2_16(b: &mut ::test::Bencher) {
+    let (domain, mut coeffs) = random_coeffs(16);
+    b.iter(|| domain.fft(&mut coeffs));
+}
+
+#[bench]
+fn bench_domain_coset_fft_2_16(b: &mut ::test::Bencher) {
+    let (domain, mut coeffs) = random_coeffs(16);
+    b.iter(|| domain.coset_fft(&mut coeffs));
+}
+
+#[cfg(feature = "parallel")]
+#[bench]
+fn bench_domain_par_fft_2_16(b: &mut ::test::Bencher) {
+    let (domain, mut coeffs) = random_coeffs(16);
+    b.iter(|| domain.par_fft(&mut coeffs));
+}
+
+#[bench]
+fn bench_domain_fft_group_g1_2_10(b: &mut ::test::Bencher) {
+    let (domain, coeffs) = random_coeffs(10);
+    let mut points: Vec<G1Projective> = coeffs.iter().map(|c| G1Projective::one() * c).collect();
+    b.iter(|| domain.fft_group(&mut points));
+}
@@ -1,9 +1,9 @@
 mod g1 {
-    use rand_core::SeedableRng;
+    use rand_core::{RngCore, SeedableRng};
     use rand_xorshift::XorShiftRng;
 
     use blstrs::*;
-    use fff::Field;
+    use fff::{Field, PrimeField};
     use groupy::CurveProjective;
 
     #[bench]
@@ -28,6 +28,100 @@ mod g1 {
         });
     }
 
+    #[bench]
+    fn bench_g1_mul_secret(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G1Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| (G1Projective::random(&mut rng), Scalar::random(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_secret(&v[count].1, &mut rng);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g1_mul_vartime(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G1Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| (G1Projective::random(&mut rng), Scalar::random(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_vartime(&v[count].1);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g1_mul_assign_128(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G1Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| {
+                (
+                    G1Projective::random(&mut rng),
+                    Scalar::from_repr(ScalarRepr([rng.next_u64(), rng.next_u64(), 0, 0])).unwrap(),
+                )
+            })
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0 * v[count].1;
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g1_mul_vartime_128(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G1Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| {
+                (
+                    G1Projective::random(&mut rng),
+                    Scalar::from_repr(ScalarRepr([rng.next_u64(), rng.next_u64(), 0, 0])).unwrap(),
+                )
+            })
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_vartime(&v[count].1);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
     #[bench]
     fn bench_g1_add_assign(b: &mut ::test::Bencher) {
         const SAMPLES: usize = 1000;
@@ -81,14 +175,31 @@ mod g1 {
             tmp
         });
     }
+
+    #[bench]
+    fn bench_g1_multi_exp_vartime_1024(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1024;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let bases: Vec<G1Affine> = (0..SAMPLES)
+            .map(|_| G1Projective::random(&mut rng).into_affine())
+            .collect();
+        let scalars: Vec<Scalar> = (0..SAMPLES).map(|_| Scalar::random(&mut rng)).collect();
+
+        b.iter(|| G1Projective::multi_exp_vartime(&bases, &scalars));
+    }
 }
 
 mod g2 {
-    use rand_core::SeedableRng;
+    use rand_core::{RngCore, SeedableRng};
     use rand_xorshift::XorShiftRng;
 
     use blstrs::*;
-    use fff::Field;
+    use fff::{Field, PrimeField};
     use groupy::CurveProjective;
 
     #[bench]
@@ -113,6 +224,100 @@ mod g2 {
         });
     }
 
+    #[bench]
+    fn bench_g2_mul_secret(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G2Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| (G2Projective::random(&mut rng), Scalar::random(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_secret(&v[count].1, &mut rng);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g2_mul_vartime(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G2Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| (G2Projective::random(&mut rng), Scalar::random(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_vartime(&v[count].1);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g2_mul_assign_128(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G2Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| {
+                (
+                    G2Projective::random(&mut rng),
+                    Scalar::from_repr(ScalarRepr([rng.next_u64(), rng.next_u64(), 0, 0])).unwrap(),
+                )
+            })
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0 * v[count].1;
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g2_mul_vartime_128(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let v: Vec<(G2Projective, Scalar)> = (0..SAMPLES)
+            .map(|_| {
+                (
+                    G2Projective::random(&mut rng),
+                    Scalar::from_repr(ScalarRepr([rng.next_u64(), rng.next_u64(), 0, 0])).unwrap(),
+                )
+            })
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_vartime(&v[count].1);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
     #[bench]
     fn bench_g2_add_assign(b: &mut ::test::Bencher) {
         const SAMPLES: usize = 1000;
@@ -166,4 +371,21 @@ mod g2 {
             tmp
         });
     }
+
+    #[bench]
+    fn bench_g2_multi_exp_vartime_1024(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1024;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let bases: Vec<G2Affine> = (0..SAMPLES)
+            .map(|_| G2Projective::random(&mut rng).into_affine())
+            .collect();
+        let scalars: Vec<Scalar> = (0..SAMPLES).map(|_| Scalar::random(&mut rng)).collect();
+
+        b.iter(|| G2Projective::multi_exp_vartime(&bases, &scalars));
+    }
 }
@@ -1,7 +1,9 @@
+mod domain;
 mod ec;
 mod fp;
 mod fp12;
 mod fp2;
+mod polynomial;
 mod scalar;
 
 use rand_core::SeedableRng;
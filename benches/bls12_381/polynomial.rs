@@ -0,0 +1,41 @@
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use blstrs::*;
+
+fn random_points(n: usize) -> (Polynomial, Vec<Scalar>) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let p = Polynomial::random(n - 1, &mut rng);
+    let xs = (0..n).map(|_| Scalar::random(&mut rng)).collect();
+    (p, xs)
+}
+
+#[bench]
+fn bench_polynomial_mul_1024(b: &mut ::test::Bencher) {
+    let (p, xs) = random_points(1024);
+    let q = Polynomial::from_coeffs(xs);
+    b.iter(|| &p * &q);
+}
+
+#[bench]
+fn bench_polynomial_evaluate_2048(b: &mut ::test::Bencher) {
+    let (p, xs) = random_points(2048);
+    b.iter(|| xs.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>());
+}
+
+#[bench]
+fn bench_polynomial_evaluate_many_2048(b: &mut ::test::Bencher) {
+    let (p, xs) = random_points(2048);
+    b.iter(|| p.evaluate_many(&xs));
+}
+
+#[bench]
+fn bench_polynomial_interpolate_1024(b: &mut ::test::Bencher) {
+    let (p, xs) = random_points(1024);
+    let points: Vec<(Scalar, Scalar)> = xs.iter().copied().zip(p.evaluate_many(&xs)).collect();
+    b.iter(|| Polynomial::interpolate(&points).unwrap());
+}
@@ -0,0 +1,938 @@
+//! Aggregation of Groth16 proofs into a proof of logarithmic size, following
+//! SnarkPack (Gailly, Maller and Nitulescu).
+//!
+//! The aggregator commits to the vectors `A`, `B` and `C` of the proofs with pairing
+//! based commitments, and proves with a generalized inner product argument that the
+//! randomized products `Z_AB = prod e(A_i, B_i)^(r^i)` and `Z_C = sum r^i C_i`
+//! are those of the committed vectors. The verifier then checks the Groth16
+//! equation once for the random linear combination of all proofs.
+//!
+//! The commitment keys are powers of two secrets `a` and `b` from a two-tier
+//! reference string, the `v` keys `[a^i]_2` and `[b^i]_2` committing to vectors of
+//! $\mathbb{G}_1$ and the `w` keys `[a^(n + i)]_1` and `[b^(n + i)]_1` committing to
+//! vectors of $\mathbb{G}_2$. The keys folded by the argument are checked at the end
+//! with KZG openings.
+
+use std::io::{self, Read, Write};
+
+use fff::{Field, PrimeField};
+use groupy::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+
+use crate::groth16::{PreparedVerifyingKey, Proof};
+use crate::transcript::Transcript;
+use crate::{
+    multi_pairing, Bls12, Compress, Engine, Error, Fp12, G1Affine, G1Projective, G2Affine,
+    G2Projective, Polynomial, Scalar,
+};
+
+/// Domain separation label of the Fiat-Shamir transcript.
+const TRANSCRIPT_LABEL: &[u8] = b"BLSTRS_SNARKPACK_GROTH16_AGGREGATION";
+
+/// Maximal number of rounds read from an encoded proof, for `2^32` proofs.
+const MAX_ROUNDS: u32 = 32;
+
+/// A two-tier reference string: powers of two secrets `a` and `b` in both groups,
+/// from which keys for any supported number of proofs are derived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericSrs {
+    g_alpha_powers: Vec<G1Affine>,
+    h_alpha_powers: Vec<G2Affine>,
+    g_beta_powers: Vec<G1Affine>,
+    h_beta_powers: Vec<G2Affine>,
+}
+
+impl GenericSrs {
+    /// Creates a reference string from the powers `[a^i]` and `[b^i]`, starting at
+    /// the generators.
+    pub fn new(
+        g_alpha_powers: Vec<G1Affine>,
+        h_alpha_powers: Vec<G2Affine>,
+        g_beta_powers: Vec<G1Affine>,
+        h_beta_powers: Vec<G2Affine>,
+    ) -> Self {
+        GenericSrs {
+            g_alpha_powers,
+            h_alpha_powers,
+            g_beta_powers,
+            h_beta_powers,
+        }
+    }
+
+    /// Generates a reference string for up to `max_proofs` proofs from random
+    /// secrets.
+    ///
+    /// **This is insecure outside of tests**, as the secrets are known to whoever
+    /// controls `rng`.
+    pub fn setup_insecure<R: RngCore>(max_proofs: usize, rng: &mut R) -> Self {
+        let a = Scalar::random(rng);
+        let b = Scalar::random(rng);
+
+        GenericSrs {
+            g_alpha_powers: powers::<G1Projective>(&a, 2 * max_proofs),
+            h_alpha_powers: powers::<G2Projective>(&a, max_proofs),
+            g_beta_powers: powers::<G1Projective>(&b, 2 * max_proofs),
+            h_beta_powers: powers::<G2Projective>(&b, max_proofs),
+        }
+    }
+
+    /// Returns the largest number of proofs supported, which may not be a power of
+    /// two.
+    pub fn max_proofs(&self) -> usize {
+        (self.g_alpha_powers.len().min(self.g_beta_powers.len()) / 2)
+            .min(self.h_alpha_powers.len())
+            .min(self.h_beta_powers.len())
+    }
+
+    /// Derives the keys to aggregate and verify exactly `n` proofs.
+    ///
+    /// Fails with `InvalidAggregationSize` unless `n` is a power of two, at least
+    /// two and supported by this reference string.
+    pub fn specialize(&self, n: usize) -> Result<(ProverSrs, VerifierSrs), Error> {
+        if !n.is_power_of_two() || n < 2 || n > self.max_proofs() {
+            return Err(Error::InvalidAggregationSize);
+        }
+
+        let prover = ProverSrs {
+            n,
+            g_alpha_powers: self.g_alpha_powers[..2 * n].to_vec(),
+            h_alpha_powers: self.h_alpha_powers[..n].to_vec(),
+            g_beta_powers: self.g_beta_powers[..2 * n].to_vec(),
+            h_beta_powers: self.h_beta_powers[..n].to_vec(),
+        };
+        let verifier = VerifierSrs {
+            n,
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_beta: self.h_beta_powers[1],
+        };
+
+        Ok((prover, verifier))
+    }
+}
+
+/// The keys to aggregate `n` proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProverSrs {
+    n: usize,
+    /// `[a^i]_1` for `i < 2 n`, the `w` key being the upper half.
+    g_alpha_powers: Vec<G1Affine>,
+    /// `[a^i]_2` for `i < n`, which is the `v` key.
+    h_alpha_powers: Vec<G2Affine>,
+    g_beta_powers: Vec<G1Affine>,
+    h_beta_powers: Vec<G2Affine>,
+}
+
+impl ProverSrs {
+    /// Returns the number of proofs aggregated with these keys.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+}
+
+/// The keys to verify aggregates of `n` proofs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifierSrs {
+    n: usize,
+    g: G1Affine,
+    h: G2Affine,
+    g_alpha: G1Affine,
+    h_alpha: G2Affine,
+    g_beta: G1Affine,
+    h_beta: G2Affine,
+}
+
+impl VerifierSrs {
+    /// Returns the number of proofs in the aggregates verified with these keys.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+}
+
+/// A commitment to vectors under the keys of `a` and `b`, which is a pair of
+/// elements of $\mathbb{G}_T$.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Commitment {
+    pub t: Fp12,
+    pub u: Fp12,
+}
+
+impl Commitment {
+    /// Commits to `a` and `b` as `prod e(a_i, v_i) e(w_i, b_i)`.
+    fn pair(
+        v: (&[G2Affine], &[G2Affine]),
+        w: (&[G1Affine], &[G1Affine]),
+        a: &[G1Affine],
+        b: &[G2Affine],
+    ) -> Self {
+        let commit = |v: &[G2Affine], w: &[G1Affine]| {
+            let pairs: Vec<_> = a.iter().zip(v).chain(w.iter().zip(b)).collect();
+            multi_pairing(&pairs)
+        };
+
+        Commitment {
+            t: commit(v.0, w.0),
+            u: commit(v.1, w.1),
+        }
+    }
+
+    /// Commits to `c` as `prod e(c_i, v_i)`.
+    fn single(v: (&[G2Affine], &[G2Affine]), c: &[G1Affine]) -> Self {
+        let commit = |v: &[G2Affine]| {
+            let pairs: Vec<_> = c.iter().zip(v).collect();
+            multi_pairing(&pairs)
+        };
+
+        Commitment {
+            t: commit(v.0),
+            u: commit(v.1),
+        }
+    }
+
+    /// Computes `left^x self right^(1 / x)`, the commitment to folded vectors.
+    fn fold(&self, (left, right): &(Commitment, Commitment), x: &Scalar, x_inv: &Scalar) -> Self {
+        Commitment {
+            t: fold_gt(&self.t, &left.t, &right.t, x, x_inv),
+            u: fold_gt(&self.u, &left.u, &right.u, x, x_inv),
+        }
+    }
+
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Commitment {
+            t: Fp12::read_compressed(&mut *reader)?,
+            u: Fp12::read_compressed(&mut *reader)?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.t.write_compressed(&mut *writer)?;
+        self.u.write_compressed(&mut *writer)
+    }
+}
+
+/// The messages of a round of the inner product argument, for the halves `L` and
+/// `R` of the vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GipaRound {
+    /// The commitments to `(A_R, B_L)` and `(A_L, B_R)`.
+    pub comms_ab: (Commitment, Commitment),
+    /// The commitments to `C_R` and `C_L`.
+    pub comms_c: (Commitment, Commitment),
+    /// `prod e(A_R, B_L)` and `prod e(A_L, B_R)`.
+    pub z_ab: (Fp12, Fp12),
+    /// `sum r_L C_R` and `sum r_R C_L`.
+    pub z_c: (G1Affine, G1Affine),
+}
+
+/// An aggregate of Groth16 proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateProof {
+    /// The commitment to the `A` and `B` points of the proofs.
+    pub com_ab: Commitment,
+    /// The commitment to the `C` points of the proofs.
+    pub com_c: Commitment,
+    /// `prod e(A_i, B_i)^(r^i)`.
+    pub ip_ab: Fp12,
+    /// `sum r^i C_i`.
+    pub agg_c: G1Affine,
+    pub rounds: Vec<GipaRound>,
+    pub final_a: G1Affine,
+    pub final_b: G2Affine,
+    pub final_c: G1Affine,
+    /// The folded `v` keys for `a` and `b`.
+    pub final_vkey: (G2Affine, G2Affine),
+    /// The folded `w` keys for `a` and `b`.
+    pub final_wkey: (G1Affine, G1Affine),
+    /// The KZG openings of the folded `v` keys.
+    pub vkey_opening: (G2Affine, G2Affine),
+    /// The KZG openings of the folded `w` keys.
+    pub wkey_opening: (G1Affine, G1Affine),
+}
+
+impl AggregateProof {
+    /// Reads a proof of compressed points and elements of $\mathbb{G}_T$, checking
+    /// that the points are in their subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let com_ab = Commitment::read(&mut reader)?;
+        let com_c = Commitment::read(&mut reader)?;
+        let ip_ab = Fp12::read_compressed(&mut reader)?;
+        let agg_c = read_g1(&mut reader)?;
+
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len);
+        if len > MAX_ROUNDS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "too many aggregation rounds",
+            ));
+        }
+        let rounds = (0..len)
+            .map(|_| {
+                Ok(GipaRound {
+                    comms_ab: (
+                        Commitment::read(&mut reader)?,
+                        Commitment::read(&mut reader)?,
+                    ),
+                    comms_c: (
+                        Commitment::read(&mut reader)?,
+                        Commitment::read(&mut reader)?,
+                    ),
+                    z_ab: (
+                        Fp12::read_compressed(&mut reader)?,
+                        Fp12::read_compressed(&mut reader)?,
+                    ),
+                    z_c: (read_g1(&mut reader)?, read_g1(&mut reader)?),
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(AggregateProof {
+            com_ab,
+            com_c,
+            ip_ab,
+            agg_c,
+            rounds,
+            final_a: read_g1(&mut reader)?,
+            final_b: read_g2(&mut reader)?,
+            final_c: read_g1(&mut reader)?,
+            final_vkey: (read_g2(&mut reader)?, read_g2(&mut reader)?),
+            final_wkey: (read_g1(&mut reader)?, read_g1(&mut reader)?),
+            vkey_opening: (read_g2(&mut reader)?, read_g2(&mut reader)?),
+            wkey_opening: (read_g1(&mut reader)?, read_g1(&mut reader)?),
+        })
+    }
+
+    /// Writes the proof in the encoding read by `read`.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.com_ab.write(&mut writer)?;
+        self.com_c.write(&mut writer)?;
+        self.ip_ab.write_compressed(&mut writer)?;
+        writer.write_all(&self.agg_c.to_compressed())?;
+
+        writer.write_all(&(self.rounds.len() as u32).to_be_bytes())?;
+        for round in &self.rounds {
+            round.comms_ab.0.write(&mut writer)?;
+            round.comms_ab.1.write(&mut writer)?;
+            round.comms_c.0.write(&mut writer)?;
+            round.comms_c.1.write(&mut writer)?;
+            round.z_ab.0.write_compressed(&mut writer)?;
+            round.z_ab.1.write_compressed(&mut writer)?;
+            writer.write_all(&round.z_c.0.to_compressed())?;
+            writer.write_all(&round.z_c.1.to_compressed())?;
+        }
+
+        writer.write_all(&self.final_a.to_compressed())?;
+        writer.write_all(&self.final_b.to_compressed())?;
+        writer.write_all(&self.final_c.to_compressed())?;
+        writer.write_all(&self.final_vkey.0.to_compressed())?;
+        writer.write_all(&self.final_vkey.1.to_compressed())?;
+        writer.write_all(&self.final_wkey.0.to_compressed())?;
+        writer.write_all(&self.final_wkey.1.to_compressed())?;
+        writer.write_all(&self.vkey_opening.0.to_compressed())?;
+        writer.write_all(&self.vkey_opening.1.to_compressed())?;
+        writer.write_all(&self.wkey_opening.0.to_compressed())?;
+        writer.write_all(&self.wkey_opening.1.to_compressed())
+    }
+}
+
+/// Aggregates proofs for the same circuit, with `public_inputs[i]` the inputs of
+/// `proofs[i]`.
+///
+/// Fails with `InvalidAggregationSize` if the number of proofs is not the one of
+/// `srs`, and with `LengthMismatch` if there are not as many input vectors as
+/// proofs.
+pub fn aggregate_proofs(
+    srs: &ProverSrs,
+    proofs: &[Proof],
+    public_inputs: &[&[Scalar]],
+) -> Result<AggregateProof, Error> {
+    let n = srs.n;
+    if proofs.len() != n {
+        return Err(Error::InvalidAggregationSize);
+    }
+    if public_inputs.len() != n {
+        return Err(Error::LengthMismatch);
+    }
+
+    let mut a: Vec<G1Affine> = proofs.iter().map(|proof| proof.a).collect();
+    let b: Vec<G2Affine> = proofs.iter().map(|proof| proof.b).collect();
+    let mut c: Vec<G1Affine> = proofs.iter().map(|proof| proof.c).collect();
+
+    let mut v1 = srs.h_alpha_powers.clone();
+    let mut v2 = srs.h_beta_powers.clone();
+    let com_ab = Commitment::pair(
+        (&v1, &v2),
+        (&srs.g_alpha_powers[n..], &srs.g_beta_powers[n..]),
+        &a,
+        &b,
+    );
+    let com_c = Commitment::single((&v1, &v2), &c);
+
+    let mut transcript = start_transcript(public_inputs);
+    transcript.append_commitment(b"com_ab", &com_ab);
+    transcript.append_commitment(b"com_c", &com_c);
+    let r = transcript.challenge_scalar(b"r");
+    let r_inv = r.inverse().expect("challenges are not zero");
+
+    // Rescale B by r^i and the w keys by r^-i, which leaves the commitment to (A, B)
+    // unchanged.
+    let mut r_powers = powers_of(&r, n);
+    let r_inv_powers = powers_of(&r_inv, n);
+    let mut b = scale::<G2Projective>(&b, &r_powers);
+    let mut w1 = scale::<G1Projective>(&srs.g_alpha_powers[n..], &r_inv_powers);
+    let mut w2 = scale::<G1Projective>(&srs.g_beta_powers[n..], &r_inv_powers);
+
+    let ip_ab = inner_pairing_product(&a, &b);
+    let agg_c = G1Projective::multi_exp_vartime(&c, &r_powers).into_affine();
+    transcript.append_gt(b"ip_ab", &ip_ab);
+    transcript.append_g1(b"agg_c", &agg_c);
+
+    let mut rounds = Vec::new();
+    let mut challenges = Vec::new();
+    while a.len() > 1 {
+        let m = a.len() / 2;
+        let (a_l, a_r) = a.split_at(m);
+        let (b_l, b_r) = b.split_at(m);
+        let (c_l, c_r) = c.split_at(m);
+        let (r_l, r_r) = r_powers.split_at(m);
+        let (v1_l, v1_r) = v1.split_at(m);
+        let (v2_l, v2_r) = v2.split_at(m);
+        let (w1_l, w1_r) = w1.split_at(m);
+        let (w2_l, w2_r) = w2.split_at(m);
+
+        let round = GipaRound {
+            comms_ab: (
+                Commitment::pair((v1_l, v2_l), (w1_r, w2_r), a_r, b_l),
+                Commitment::pair((v1_r, v2_r), (w1_l, w2_l), a_l, b_r),
+            ),
+            comms_c: (
+                Commitment::single((v1_l, v2_l), c_r),
+                Commitment::single((v1_r, v2_r), c_l),
+            ),
+            z_ab: (
+                inner_pairing_product(a_r, b_l),
+                inner_pairing_product(a_l, b_r),
+            ),
+            z_c: (
+                G1Projective::multi_exp_vartime(c_r, r_l).into_affine(),
+                G1Projective::multi_exp_vartime(c_l, r_r).into_affine(),
+            ),
+        };
+        transcript.append_round(&round);
+        let x = transcript.challenge_scalar(b"x");
+        let x_inv = x.inverse().expect("challenges are not zero");
+
+        // A and C are folded with x, B and r with 1 / x, the v keys with 1 / x and
+        // the w keys with x.
+        a = fold::<G1Projective>(a_l, a_r, &x);
+        b = fold::<G2Projective>(b_l, b_r, &x_inv);
+        c = fold::<G1Projective>(c_l, c_r, &x);
+        r_powers = r_l
+            .iter()
+            .zip(r_r)
+            .map(|(l, r)| {
+                let mut t = *r;
+                t.mul_assign(&x_inv);
+                t.add_assign(l);
+                t
+            })
+            .collect();
+        v1 = fold::<G2Projective>(v1_l, v1_r, &x_inv);
+        v2 = fold::<G2Projective>(v2_l, v2_r, &x_inv);
+        w1 = fold::<G1Projective>(w1_l, w1_r, &x);
+        w2 = fold::<G1Projective>(w2_l, w2_r, &x);
+
+        rounds.push(round);
+        challenges.push(x);
+    }
+
+    let final_vkey = (v1[0], v2[0]);
+    let final_wkey = (w1[0], w2[0]);
+    transcript.append_finals(&a[0], &b[0], &c[0], &final_vkey, &final_wkey);
+    let z = transcript.challenge_scalar(b"z");
+
+    // The folded keys are commitments to f_v and to X^n f_w, whose openings at z use
+    // the powers of a and b in the other group.
+    let f_v = Polynomial::from_coeffs(expand(&vkey_factors(&challenges)));
+    let (q_v, _) = f_v.divide_by_linear(&z);
+    let vkey_opening = (
+        G2Projective::multi_exp_vartime(&srs.h_alpha_powers[..q_v.coeffs().len()], q_v.coeffs())
+            .into_affine(),
+        G2Projective::multi_exp_vartime(&srs.h_beta_powers[..q_v.coeffs().len()], q_v.coeffs())
+            .into_affine(),
+    );
+
+    let mut f_w = vec![Scalar::zero(); n];
+    f_w.extend(expand(&wkey_factors(&challenges, &r_inv)));
+    let (q_w, _) = Polynomial::from_coeffs(f_w).divide_by_linear(&z);
+    let wkey_opening = (
+        G1Projective::multi_exp_vartime(&srs.g_alpha_powers[..q_w.coeffs().len()], q_w.coeffs())
+            .into_affine(),
+        G1Projective::multi_exp_vartime(&srs.g_beta_powers[..q_w.coeffs().len()], q_w.coeffs())
+            .into_affine(),
+    );
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        rounds,
+        final_a: a[0],
+        final_b: b[0],
+        final_c: c[0],
+        final_vkey,
+        final_wkey,
+        vkey_opening,
+        wkey_opening,
+    })
+}
+
+/// Verifies an aggregate of proofs for the same circuit, with `public_inputs[i]`
+/// the inputs of the `i`-th proof.
+///
+/// Fails with `InvalidAggregationSize` if the number of input vectors is not the
+/// number of proofs of `srs`, with `LengthMismatch` if an input vector does not
+/// match the key, and with `VerifyFailed` if the aggregate is invalid.
+pub fn verify_aggregate_proof(
+    srs: &VerifierSrs,
+    pvk: &PreparedVerifyingKey,
+    public_inputs: &[&[Scalar]],
+    proof: &AggregateProof,
+) -> Result<(), Error> {
+    let n = srs.n;
+    if public_inputs.len() != n {
+        return Err(Error::InvalidAggregationSize);
+    }
+    if public_inputs
+        .iter()
+        .any(|inputs| inputs.len() + 1 != pvk.ic.len())
+    {
+        return Err(Error::LengthMismatch);
+    }
+    if proof.rounds.len() != n.trailing_zeros() as usize {
+        return Err(Error::VerifyFailed);
+    }
+
+    let mut transcript = start_transcript(public_inputs);
+    transcript.append_commitment(b"com_ab", &proof.com_ab);
+    transcript.append_commitment(b"com_c", &proof.com_c);
+    let r = transcript.challenge_scalar(b"r");
+    let r_inv = r.inverse().expect("challenges are not zero");
+    transcript.append_gt(b"ip_ab", &proof.ip_ab);
+    transcript.append_g1(b"agg_c", &proof.agg_c);
+
+    let mut com_ab = proof.com_ab;
+    let mut com_c = proof.com_c;
+    let mut z_ab = proof.ip_ab;
+    let mut z_c = proof.agg_c.into_projective();
+    let mut challenges = Vec::with_capacity(proof.rounds.len());
+    for round in &proof.rounds {
+        transcript.append_round(round);
+        let x = transcript.challenge_scalar(b"x");
+        let x_inv = x.inverse().expect("challenges are not zero");
+
+        com_ab = com_ab.fold(&round.comms_ab, &x, &x_inv);
+        com_c = com_c.fold(&round.comms_c, &x, &x_inv);
+        z_ab = fold_gt(&z_ab, &round.z_ab.0, &round.z_ab.1, &x, &x_inv);
+        z_c.add_assign(&round.z_c.0.into_projective().mul_vartime(&x));
+        z_c.add_assign(&round.z_c.1.into_projective().mul_vartime(&x_inv));
+
+        challenges.push(x);
+    }
+
+    transcript.append_finals(
+        &proof.final_a,
+        &proof.final_b,
+        &proof.final_c,
+        &proof.final_vkey,
+        &proof.final_wkey,
+    );
+    let z = transcript.challenge_scalar(b"z");
+
+    let (v1, v2) = (&proof.final_vkey.0, &proof.final_vkey.1);
+    let (w1, w2) = (&proof.final_wkey.0, &proof.final_wkey.1);
+    let a = &proof.final_a;
+    let b = &proof.final_b;
+    let c = &proof.final_c;
+
+    // The inner product argument ends with vectors of a single element.
+    let final_com_ab = Commitment::pair((&[*v1], &[*v2]), (&[*w1], &[*w2]), &[*a], &[*b]);
+    let final_com_c = Commitment::single((&[*v1], &[*v2]), &[*c]);
+    let r_final = evaluate(&vkey_factors(&challenges), &r);
+    if com_ab != final_com_ab
+        || com_c != final_com_c
+        || z_ab != multi_pairing(&[(a, b)])
+        || z_c != c.into_projective().mul_vartime(&r_final)
+    {
+        return Err(Error::VerifyFailed);
+    }
+
+    // The folded keys are the commitments to f_v and X^n f_w.
+    let f_v_z = evaluate(&vkey_factors(&challenges), &z);
+    let mut f_w_z = evaluate(&wkey_factors(&challenges, &r_inv), &z);
+    f_w_z.mul_assign(&z.pow([n as u64]));
+    let g_z = srs.g.into_projective().mul_vartime(&z);
+    let h_z = srs.h.into_projective().mul_vartime(&z);
+    let g_f = srs.g.into_projective().mul_vartime(&f_w_z);
+    let h_f = srs.h.into_projective().mul_vartime(&f_v_z);
+    let mut neg_g = srs.g;
+    neg_g.negate();
+    for (g_secret, h_secret, v, w, v_opening, w_opening) in &[
+        (
+            srs.g_alpha,
+            srs.h_alpha,
+            v1,
+            w1,
+            proof.vkey_opening.0,
+            proof.wkey_opening.0,
+        ),
+        (
+            srs.g_beta,
+            srs.h_beta,
+            v2,
+            w2,
+            proof.vkey_opening.1,
+            proof.wkey_opening.1,
+        ),
+    ] {
+        // e([secret - z]_1, opening) = e([1]_1, v - [f_v(z)]_2)
+        let mut g_shift = g_secret.into_projective();
+        g_shift.sub_assign(&g_z);
+        let mut v_shift = v.into_projective();
+        v_shift.sub_assign(&h_f);
+        let v_check = multi_pairing(&[
+            (&g_shift.into_affine(), v_opening),
+            (&neg_g, &v_shift.into_affine()),
+        ]);
+
+        // e(opening, [secret - z]_2) = e(w - [z^n f_w(z)]_1, [1]_2)
+        let mut h_shift = h_secret.into_projective();
+        h_shift.sub_assign(&h_z);
+        let mut w_shift = g_f;
+        w_shift.sub_assign(&w.into_projective());
+        let w_check = multi_pairing(&[
+            (w_opening, &h_shift.into_affine()),
+            (&w_shift.into_affine(), &srs.h),
+        ]);
+
+        if v_check != Fp12::one() || w_check != Fp12::one() {
+            return Err(Error::VerifyFailed);
+        }
+    }
+
+    // ip_ab e(sum r^i acc_i, -gamma) e(agg_c, -delta) = e(alpha, beta)^(sum r^i)
+    let r_powers = powers_of(&r, n);
+    let mut coeffs = vec![Scalar::zero(); pvk.ic.len()];
+    for (r, inputs) in r_powers.iter().zip(public_inputs) {
+        coeffs[0].add_assign(r);
+        for (coeff, input) in coeffs[1..].iter_mut().zip(inputs.iter()) {
+            let mut t = *input;
+            t.mul_assign(r);
+            coeff.add_assign(&t);
+        }
+    }
+    let acc = G1Projective::multi_exp_vartime(&pvk.ic, &coeffs).into_affine();
+    let ml = Bls12::miller_loop(&[(&acc, &pvk.neg_gamma_g2), (&proof.agg_c, &pvk.neg_delta_g2)]);
+    let mut lhs = Bls12::final_exponentiation(&ml).ok_or(Error::VerifyFailed)?;
+    lhs.mul_assign(&proof.ip_ab);
+    if lhs != pvk.alpha_g1_beta_g2.pow(coeffs[0].into_repr()) {
+        return Err(Error::VerifyFailed);
+    }
+
+    Ok(())
+}
+
+/// Starts a transcript bound to the public inputs of the aggregated proofs.
+fn start_transcript(public_inputs: &[&[Scalar]]) -> Transcript {
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    for inputs in public_inputs {
+        transcript.append_message(b"inputs", &(inputs.len() as u64).to_le_bytes());
+        for input in inputs.iter() {
+            transcript.append_scalar(b"input", input);
+        }
+    }
+    transcript
+}
+
+impl Transcript {
+    fn append_commitment(&mut self, label: &[u8], com: &Commitment) {
+        self.append_gt(label, &com.t);
+        self.append_gt(label, &com.u);
+    }
+
+    fn append_round(&mut self, round: &GipaRound) {
+        self.append_commitment(b"com_ab_l", &round.comms_ab.0);
+        self.append_commitment(b"com_ab_r", &round.comms_ab.1);
+        self.append_commitment(b"com_c_l", &round.comms_c.0);
+        self.append_commitment(b"com_c_r", &round.comms_c.1);
+        self.append_gt(b"z_ab_l", &round.z_ab.0);
+        self.append_gt(b"z_ab_r", &round.z_ab.1);
+        self.append_g1(b"z_c_l", &round.z_c.0);
+        self.append_g1(b"z_c_r", &round.z_c.1);
+    }
+
+    fn append_finals(
+        &mut self,
+        a: &G1Affine,
+        b: &G2Affine,
+        c: &G1Affine,
+        vkey: &(G2Affine, G2Affine),
+        wkey: &(G1Affine, G1Affine),
+    ) {
+        self.append_g1(b"final_a", a);
+        self.append_g2(b"final_b", b);
+        self.append_g1(b"final_c", c);
+        self.append_g2(b"final_vkey", &vkey.0);
+        self.append_g2(b"final_vkey", &vkey.1);
+        self.append_g1(b"final_wkey", &wkey.0);
+        self.append_g1(b"final_wkey", &wkey.1);
+    }
+}
+
+/// The coefficients `c_j` of `f_v = prod (1 + c_j X^(2^(k - 1 - j)))`, the
+/// polynomial of the folded `v` keys, which are `1 / x_j`.
+fn vkey_factors(challenges: &[Scalar]) -> Vec<Scalar> {
+    challenges
+        .iter()
+        .map(|x| x.inverse().expect("challenges are not zero"))
+        .collect()
+}
+
+/// The coefficients `c_j` of `f_w`, with the `w` keys of the `j`-th round rescaled
+/// by `r^-(2^(k - 1 - j))`, which are `x_j r^-(2^(k - 1 - j))`.
+fn wkey_factors(challenges: &[Scalar], r_inv: &Scalar) -> Vec<Scalar> {
+    let mut r_inv = *r_inv;
+    let mut factors: Vec<Scalar> = challenges
+        .iter()
+        .rev()
+        .map(|x| {
+            let mut c = *x;
+            c.mul_assign(&r_inv);
+            r_inv.square();
+            c
+        })
+        .collect();
+    factors.reverse();
+    factors
+}
+
+/// Computes the coefficients of `prod (1 + c_j X^(2^(k - 1 - j)))`.
+fn expand(factors: &[Scalar]) -> Vec<Scalar> {
+    let mut coeffs = vec![Scalar::one()];
+    for c in factors.iter().rev() {
+        let high: Vec<Scalar> = coeffs
+            .iter()
+            .map(|coeff| {
+                let mut t = *coeff;
+                t.mul_assign(c);
+                t
+            })
+            .collect();
+        coeffs.extend(high);
+    }
+    coeffs
+}
+
+/// Evaluates `prod (1 + c_j X^(2^(k - 1 - j)))` at `x`.
+fn evaluate(factors: &[Scalar], x: &Scalar) -> Scalar {
+    let mut x_pow = *x;
+    let mut out = Scalar::one();
+    for c in factors.iter().rev() {
+        let mut t = *c;
+        t.mul_assign(&x_pow);
+        t.add_assign(&Scalar::one());
+        out.mul_assign(&t);
+        x_pow.square();
+    }
+    out
+}
+
+/// Computes `left^x middle right^(1 / x)`.
+fn fold_gt(middle: &Fp12, left: &Fp12, right: &Fp12, x: &Scalar, x_inv: &Scalar) -> Fp12 {
+    let mut out = left.pow(x.into_repr());
+    out.mul_assign(middle);
+    out.mul_assign(&right.pow(x_inv.into_repr()));
+    out
+}
+
+/// Computes `prod e(a_i, b_i)`.
+fn inner_pairing_product(a: &[G1Affine], b: &[G2Affine]) -> Fp12 {
+    let pairs: Vec<_> = a.iter().zip(b).collect();
+    multi_pairing(&pairs)
+}
+
+/// Computes `s^i` for `i < n`.
+fn powers_of(s: &Scalar, n: usize) -> Vec<Scalar> {
+    let mut acc = Scalar::one();
+    (0..n)
+        .map(|_| {
+            let power = acc;
+            acc.mul_assign(s);
+            power
+        })
+        .collect()
+}
+
+/// Computes `[s^i]` for `i < n`.
+fn powers<G: CurveProjective<Scalar = Scalar>>(s: &Scalar, n: usize) -> Vec<G::Affine> {
+    let generator = vec![G::Affine::one(); n];
+    scale::<G>(&generator, &powers_of(s, n))
+}
+
+/// Computes `p_i s_i`.
+fn scale<G: CurveProjective<Scalar = Scalar>>(
+    points: &[G::Affine],
+    scalars: &[Scalar],
+) -> Vec<G::Affine> {
+    let mut out: Vec<G> = points
+        .iter()
+        .zip(scalars)
+        .map(|(p, s)| p.mul(s.into_repr()))
+        .collect();
+    G::batch_normalization(&mut out);
+    out.iter().map(|p| p.into_affine()).collect()
+}
+
+/// Computes `l_i + x r_i`.
+fn fold<G: CurveProjective<Scalar = Scalar>>(
+    left: &[G::Affine],
+    right: &[G::Affine],
+    x: &Scalar,
+) -> Vec<G::Affine> {
+    let mut out: Vec<G> = left
+        .iter()
+        .zip(right)
+        .map(|(l, r)| {
+            let mut p = r.mul(x.into_repr());
+            p.add_assign_mixed(l);
+            p
+        })
+        .collect();
+    G::batch_normalization(&mut out);
+    out.iter().map(|p| p.into_affine()).collect()
+}
+
+fn read_g1<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+    let mut bytes = [0u8; 48];
+    reader.read_exact(&mut bytes)?;
+    Ok(G1Affine::from_compressed(&bytes)?)
+}
+
+fn read_g2<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+    let mut bytes = [0u8; 96];
+    reader.read_exact(&mut bytes)?;
+    Ok(G2Affine::from_compressed(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use crate::groth16::prepare_verifying_key;
+    use crate::groth16::tests::{setup, simulate};
+
+    #[test]
+    fn test_expand() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let factors: Vec<Scalar> = (0..4).map(|_| Scalar::random(&mut rng)).collect();
+        let p = Polynomial::from_coeffs(expand(&factors));
+        assert_eq!(p.coeffs().len(), 16);
+        let x = Scalar::random(&mut rng);
+        assert_eq!(p.evaluate(&x), evaluate(&factors, &x));
+    }
+
+    #[test]
+    fn test_aggregate_proofs() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let generic = GenericSrs::setup_insecure(8, &mut rng);
+        assert_eq!(generic.max_proofs(), 8);
+        assert_eq!(generic.specialize(3), Err(Error::InvalidAggregationSize));
+        assert_eq!(generic.specialize(16), Err(Error::InvalidAggregationSize));
+
+        let (vk, td) = setup(2, &mut rng);
+        let pvk = prepare_verifying_key(&vk);
+        for n in &[2, 8] {
+            let (prover_srs, verifier_srs) = generic.specialize(*n).unwrap();
+            assert_eq!(prover_srs.n(), *n);
+            assert_eq!(verifier_srs.n(), *n);
+
+            let inputs: Vec<Vec<Scalar>> = (0..*n)
+                .map(|_| (0..2).map(|_| Scalar::random(&mut rng)).collect())
+                .collect();
+            let mut inputs: Vec<&[Scalar]> = inputs.iter().map(|inputs| &inputs[..]).collect();
+            let mut proofs: Vec<Proof> = inputs
+                .iter()
+                .map(|inputs| simulate(&td, inputs, &mut rng))
+                .collect();
+
+            let aggregate = aggregate_proofs(&prover_srs, &proofs, &inputs).unwrap();
+            assert_eq!(aggregate.rounds.len(), n.trailing_zeros() as usize);
+            assert_eq!(
+                verify_aggregate_proof(&verifier_srs, &pvk, &inputs, &aggregate),
+                Ok(())
+            );
+
+            let mut bytes = Vec::new();
+            aggregate.write(&mut bytes).unwrap();
+            let read = AggregateProof::read(&bytes[..]).unwrap();
+            assert_eq!(read, aggregate);
+            assert!(AggregateProof::read(&bytes[..bytes.len() - 1]).is_err());
+
+            // Inputs that do not match the proofs.
+            let wrong = [Scalar::one(), Scalar::one()];
+            let first = inputs[0];
+            inputs[0] = &wrong;
+            assert_eq!(
+                verify_aggregate_proof(&verifier_srs, &pvk, &inputs, &aggregate),
+                Err(Error::VerifyFailed)
+            );
+            let bad = aggregate_proofs(&prover_srs, &proofs, &inputs).unwrap();
+            assert_eq!(
+                verify_aggregate_proof(&verifier_srs, &pvk, &inputs, &bad),
+                Err(Error::VerifyFailed)
+            );
+            inputs[0] = first;
+
+            // An invalid proof.
+            proofs[n - 1].c = proofs[0].c;
+            let bad = aggregate_proofs(&prover_srs, &proofs, &inputs).unwrap();
+            assert_eq!(
+                verify_aggregate_proof(&verifier_srs, &pvk, &inputs, &bad),
+                Err(Error::VerifyFailed)
+            );
+
+            // A tampered aggregate.
+            let mut bad = aggregate.clone();
+            bad.final_vkey = (bad.final_vkey.1, bad.final_vkey.0);
+            assert_eq!(
+                verify_aggregate_proof(&verifier_srs, &pvk, &inputs, &bad),
+                Err(Error::VerifyFailed)
+            );
+
+            assert_eq!(
+                verify_aggregate_proof(&verifier_srs, &pvk, &inputs[1..], &aggregate),
+                Err(Error::InvalidAggregationSize)
+            );
+            assert_eq!(
+                aggregate_proofs(&prover_srs, &proofs[1..], &inputs[1..]),
+                Err(Error::InvalidAggregationSize)
+            );
+        }
+    }
+}
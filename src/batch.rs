@@ -0,0 +1,106 @@
+//! Batch inversion of field elements with Montgomery's trick.
+
+use fff::Field;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::{Fp, Fp12, Fp2, Fp6, Scalar};
+
+/// Inversion of many field elements at the cost of a single inversion and three
+/// multiplications per element.
+pub trait BatchInvert: Field + ConditionallySelectable + ConstantTimeEq {
+    /// Inverts the element in constant time, returning none if it is zero.
+    fn invert_ct(&self) -> CtOption<Self>;
+
+    /// Replaces each non-zero element of `v` by its inverse, leaving zeros in place,
+    /// and returns the number of zeros.
+    ///
+    /// The running time depends on the positions of the zeros, use `batch_invert_ct`
+    /// for secret data.
+    fn batch_invert(v: &mut [Self]) -> usize {
+        // prods[i] is the product of the non-zero elements before index i.
+        let mut prods = Vec::with_capacity(v.len());
+        let mut acc = Self::one();
+        let mut zeros = 0;
+        for el in v.iter() {
+            prods.push(acc);
+            if el.is_zero() {
+                zeros += 1;
+            } else {
+                acc.mul_assign(el);
+            }
+        }
+
+        // The product of non-zero elements is non-zero.
+        let mut inv = acc.inverse().unwrap();
+        for (el, prod) in v.iter_mut().zip(prods).rev() {
+            if el.is_zero() {
+                continue;
+            }
+            let mut tmp = inv;
+            tmp.mul_assign(&prod);
+            inv.mul_assign(el);
+            *el = tmp;
+        }
+
+        zeros
+    }
+
+    /// Replaces each element of `v` by its inverse in constant time, leaving zeros in
+    /// place, and returns whether all elements were non-zero.
+    fn batch_invert_ct(v: &mut [Self]) -> Choice {
+        let mut prods = Vec::with_capacity(v.len());
+        let mut acc = Self::one();
+        let mut all_nonzero = Choice::from(1);
+        for el in v.iter() {
+            prods.push(acc);
+            let is_zero = el.ct_eq(&Self::zero());
+            all_nonzero &= !is_zero;
+            acc.mul_assign(&Self::conditional_select(el, &Self::one(), is_zero));
+        }
+
+        let mut inv = acc.invert_ct().unwrap();
+        for (el, prod) in v.iter_mut().zip(prods).rev() {
+            let is_zero = el.ct_eq(&Self::zero());
+            let mut tmp = inv;
+            tmp.mul_assign(&prod);
+            inv.mul_assign(&Self::conditional_select(el, &Self::one(), is_zero));
+            el.conditional_assign(&tmp, !is_zero);
+        }
+
+        all_nonzero
+    }
+
+    /// Like `batch_invert`, splitting `v` into chunks inverted in parallel.
+    #[cfg(feature = "parallel")]
+    fn par_batch_invert(v: &mut [Self]) -> usize {
+        use rayon::prelude::*;
+
+        let chunk_size = (v.len() / rayon::current_num_threads()).max(1024);
+        v.par_chunks_mut(chunk_size).map(Self::batch_invert).sum()
+    }
+
+    /// Like `batch_invert_ct`, splitting `v` into chunks inverted in parallel.
+    #[cfg(feature = "parallel")]
+    fn par_batch_invert_ct(v: &mut [Self]) -> Choice {
+        use rayon::prelude::*;
+
+        let chunk_size = (v.len() / rayon::current_num_threads()).max(1024);
+        v.par_chunks_mut(chunk_size)
+            .map(Self::batch_invert_ct)
+            .reduce(|| Choice::from(1), |a, b| a & b)
+    }
+}
+
+macro_rules! impl_batch_invert {
+    ($($field:ident),*) => {
+        $(
+            impl BatchInvert for $field {
+                fn invert_ct(&self) -> CtOption<Self> {
+                    self.inverse_ct()
+                }
+            }
+        )*
+    };
+}
+
+impl_batch_invert!(Fp, Fp2, Fp6, Fp12, Scalar);
@@ -0,0 +1,851 @@
+//! BBS signatures over BLS12-381, following the ciphersuite BLS12-381-SHA-256 of
+//! draft-irtf-cfrg-bbs-signatures-05.
+//!
+//! A signer with a public key in $\mathbb{G}_2$ signs a vector of messages with a
+//! signature `(A, e)` in $\mathbb{G}_1$, where `A = B / (SK + e)` and
+//! `B = P1 + Q_1 domain + sum H_i msg_i`. The messages are mapped to scalars with
+//! `hash_to_scalar`, and the generators `Q_1` and `H_i` are derived with
+//! `G1Projective::hash_to_curve`. A holder of a signature proves in zero knowledge
+//! that it signed a subset of disclosed messages, without revealing the others or
+//! the signature, and two such proofs cannot be linked.
+
+use std::convert::TryInto;
+
+use fff::Field;
+use groupy::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::scalar::expand_message_xmd;
+use crate::{multi_pairing, Error, Fp12, G1Affine, G1Projective, G2Affine, Scalar, SecretScalar};
+
+/// Identifier of the ciphersuite.
+pub const CIPHERSUITE_ID: &[u8] = b"BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Identifier of the interface hashing messages to scalars, prefixed to all tags.
+const API_ID: &[u8] = b"BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_H2G_HM2S_";
+
+/// Default domain separation tag of `key_gen`.
+pub const KEYGEN_DST: &[u8] = b"BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_H2G_HM2S_KEYGEN_DST_";
+
+/// Length of the seeds from which generators are derived.
+const EXPAND_LEN: usize = 48;
+
+/// A signature `(A, e)` on a vector of messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub a: G1Affine,
+    pub e: Scalar,
+}
+
+/// A proof of knowledge of a signature on messages of which some are disclosed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    pub a_bar: G1Affine,
+    pub b_bar: G1Affine,
+    pub d: G1Affine,
+    pub e_hat: Scalar,
+    pub r1_hat: Scalar,
+    pub r3_hat: Scalar,
+    /// The responses for the undisclosed messages, in ascending order of index.
+    pub m_hat: Vec<Scalar>,
+    pub challenge: Scalar,
+}
+
+/// Derives a secret key from at least 32 bytes of secret key material, and
+/// optional public key information, with the tag `KEYGEN_DST` unless another is
+/// required.
+///
+/// The public key is `sk.public_key_g2()`.
+pub fn key_gen(
+    key_material: &[u8],
+    key_info: &[u8],
+    key_dst: &[u8],
+) -> Result<SecretScalar, Error> {
+    if key_material.len() < 32 || key_info.len() > u16::MAX as usize {
+        return Err(Error::InvalidKeyMaterial);
+    }
+
+    let mut input = Zeroizing::new(Vec::with_capacity(key_material.len() + 2 + key_info.len()));
+    input.extend_from_slice(key_material);
+    input.extend_from_slice(&(key_info.len() as u16).to_be_bytes());
+    input.extend_from_slice(key_info);
+
+    Ok(SecretScalar::new(Scalar::hash_to_scalar(&input, key_dst)))
+}
+
+/// Signs `messages` and `header`, which is bound to the signature but is not part
+/// of the messages a proof discloses.
+///
+/// Signing is deterministic: `rng` only blinds the multiplications by the secret
+/// key and the messages, see `G1Projective::mul_secret`.
+pub fn sign<R: RngCore>(
+    sk: &SecretScalar,
+    pk: &G2Affine,
+    header: &[u8],
+    messages: &[&[u8]],
+    rng: &mut R,
+) -> Signature {
+    let messages = messages_to_scalars(messages);
+    let generators = Generators::new(messages.len());
+    let domain = calculate_domain(pk, &generators, header);
+
+    let mut input = Zeroizing::new(Vec::with_capacity(32 * (messages.len() + 2)));
+    input.extend_from_slice(&sk.expose().to_bytes_be());
+    for msg in &messages {
+        input.extend_from_slice(&msg.to_bytes_be());
+    }
+    input.extend_from_slice(&domain.to_bytes_be());
+    let e = Scalar::hash_to_scalar(&input, &dst(b"H2S_"));
+
+    // A = B / (SK + e)
+    let b = generators.b_ct(&domain, &messages, rng);
+    let mut exponent = *sk.expose() + e;
+    let mut inv = exponent
+        .inverse()
+        .expect("SK + e is zero with negligible probability");
+    let a = b.mul_secret(&inv, rng).into_affine();
+    exponent.zeroize();
+    inv.zeroize();
+
+    Signature { a, e }
+}
+
+/// Verifies a signature on `messages` and `header`.
+///
+/// Fails with `PublicKeyIsInfinity` for the public key at infinity, and with
+/// `VerifyFailed` if the signature is invalid.
+pub fn verify(
+    pk: &G2Affine,
+    signature: &Signature,
+    header: &[u8],
+    messages: &[&[u8]],
+) -> Result<(), Error> {
+    if pk.is_zero() {
+        return Err(Error::PublicKeyIsInfinity);
+    }
+
+    let messages = messages_to_scalars(messages);
+    let generators = Generators::new(messages.len());
+    let domain = calculate_domain(pk, &generators, header);
+    let b = generators.b_vartime(&domain, &messages);
+
+    // e(A, W + e BP2) e(B, -BP2) = 1
+    let mut w = G2Affine::one().mul(signature.e);
+    w.add_assign_mixed(pk);
+    let mut neg_bp2 = G2Affine::one();
+    neg_bp2.negate();
+    check_pairings(&[
+        (&signature.a, &w.into_affine()),
+        (&b.into_affine(), &neg_bp2),
+    ])
+}
+
+/// Proves knowledge of a signature on `messages` and `header`, disclosing the
+/// messages at `disclosed_indexes`, which must be in ascending order. The proof is
+/// bound to the presentation header `ph`.
+///
+/// Fails with `InvalidMessageIndex` if an index is out of range or the indices are
+/// not ascending. The signature is not verified.
+pub fn proof_gen<R: RngCore>(
+    pk: &G2Affine,
+    signature: &Signature,
+    header: &[u8],
+    ph: &[u8],
+    messages: &[&[u8]],
+    disclosed_indexes: &[usize],
+    rng: &mut R,
+) -> Result<Proof, Error> {
+    check_indexes(disclosed_indexes, messages.len())?;
+    let undisclosed: Vec<usize> = (0..messages.len())
+        .filter(|i| disclosed_indexes.binary_search(i).is_err())
+        .collect();
+
+    let mut messages = messages_to_scalars(messages);
+    let generators = Generators::new(messages.len());
+    let domain = calculate_domain(pk, &generators, header);
+
+    let mut random = [Scalar::zero(); 5];
+    for r in random.iter_mut() {
+        *r = Scalar::random(rng);
+    }
+    let [mut r1, mut r2, mut e_tilde, mut r1_tilde, mut r3_tilde] = random;
+    let mut m_tilde: Vec<Scalar> = undisclosed.iter().map(|_| Scalar::random(rng)).collect();
+
+    // D = B r2, Abar = A r1 r2, Bbar = D r1 - Abar e
+    let b = generators.b_ct(&domain, &messages, rng);
+    let d = b.mul_secret(&r2, rng);
+    let mut r1_r2 = r1 * r2;
+    let a_bar = signature.a.into_projective().mul_secret(&r1_r2, rng);
+    r1_r2.zeroize();
+    let mut b_bar = d.mul_secret(&r1, rng);
+    b_bar.sub_assign(&a_bar.mul_secret(&signature.e, rng));
+
+    // T1 = Abar e~ + D r1~, T2 = D r3~ + sum H_j m~_j
+    let mut t1 = a_bar.mul_secret(&e_tilde, rng);
+    t1.add_assign(&d.mul_secret(&r1_tilde, rng));
+    let mut t2 = d.mul_secret(&r3_tilde, rng);
+    for (j, m) in undisclosed.iter().zip(&m_tilde) {
+        t2.add_assign(&generators.h[*j].into_projective().mul_secret(m, rng));
+    }
+    let (a_bar, b_bar, d) = (a_bar.into_affine(), b_bar.into_affine(), d.into_affine());
+
+    let challenge = calculate_challenge(
+        &[a_bar, b_bar, d, t1.into_affine(), t2.into_affine()],
+        &domain,
+        disclosed_indexes,
+        &messages,
+        ph,
+    );
+
+    let mut r3 = r2
+        .inverse()
+        .expect("r2 is zero with negligible probability");
+    let proof = Proof {
+        a_bar,
+        b_bar,
+        d,
+        e_hat: e_tilde + signature.e * challenge,
+        r1_hat: r1_tilde - r1 * challenge,
+        r3_hat: r3_tilde - r3 * challenge,
+        m_hat: undisclosed
+            .iter()
+            .zip(&m_tilde)
+            .map(|(j, m)| *m + messages[*j] * challenge)
+            .collect(),
+        challenge,
+    };
+
+    for s in [
+        &mut r1,
+        &mut r2,
+        &mut r3,
+        &mut e_tilde,
+        &mut r1_tilde,
+        &mut r3_tilde,
+    ]
+    .iter_mut()
+    {
+        s.zeroize();
+    }
+    for s in m_tilde.iter_mut().chain(messages.iter_mut()) {
+        s.zeroize();
+    }
+
+    Ok(proof)
+}
+
+/// Verifies a proof that `disclosed_messages`, at the ascending indices
+/// `disclosed_indexes`, are among messages signed with `header`, bound to the
+/// presentation header `ph`.
+///
+/// Fails with `LengthMismatch` if there are not as many messages as indices,
+/// with `InvalidMessageIndex` if an index is out of range or the indices are not
+/// ascending, with `PublicKeyIsInfinity` for the public key at infinity, and with
+/// `VerifyFailed` if the proof is invalid.
+pub fn proof_verify(
+    pk: &G2Affine,
+    proof: &Proof,
+    header: &[u8],
+    ph: &[u8],
+    disclosed_messages: &[&[u8]],
+    disclosed_indexes: &[usize],
+) -> Result<(), Error> {
+    if disclosed_messages.len() != disclosed_indexes.len() {
+        return Err(Error::LengthMismatch);
+    }
+    let num_messages = proof.m_hat.len() + disclosed_indexes.len();
+    check_indexes(disclosed_indexes, num_messages)?;
+    if pk.is_zero() {
+        return Err(Error::PublicKeyIsInfinity);
+    }
+
+    let disclosed = messages_to_scalars(disclosed_messages);
+    let generators = Generators::new(num_messages);
+    let domain = calculate_domain(pk, &generators, header);
+
+    // T1 = Bbar c + Abar e^ + D r1^
+    let t1 = G1Projective::multi_exp_vartime(
+        &[proof.b_bar, proof.a_bar, proof.d],
+        &[proof.challenge, proof.e_hat, proof.r1_hat],
+    );
+
+    // T2 = Bv c + D r3^ + sum H_j m^_j, with Bv = P1 + Q_1 domain + sum H_i msg_i
+    // over the disclosed messages.
+    let mut bases = vec![generators.p1, generators.q1, proof.d];
+    let mut scalars = vec![proof.challenge, domain * proof.challenge, proof.r3_hat];
+    let mut undisclosed = proof.m_hat.iter();
+    let mut disclosed_iter = disclosed_indexes.iter().zip(&disclosed).peekable();
+    for (i, h) in generators.h.iter().enumerate() {
+        bases.push(*h);
+        match disclosed_iter.peek() {
+            Some((j, msg)) if **j == i => {
+                scalars.push(**msg * proof.challenge);
+                disclosed_iter.next();
+            }
+            _ => scalars.push(*undisclosed.next().expect("counted above")),
+        }
+    }
+    let t2 = G1Projective::multi_exp_vartime(&bases, &scalars);
+
+    let mut messages = vec![Scalar::zero(); num_messages];
+    for (i, msg) in disclosed_indexes.iter().zip(&disclosed) {
+        messages[*i] = *msg;
+    }
+    let challenge = calculate_challenge(
+        &[
+            proof.a_bar,
+            proof.b_bar,
+            proof.d,
+            t1.into_affine(),
+            t2.into_affine(),
+        ],
+        &domain,
+        disclosed_indexes,
+        &messages,
+        ph,
+    );
+    if challenge != proof.challenge {
+        return Err(Error::VerifyFailed);
+    }
+
+    // e(Abar, W) e(Bbar, -BP2) = 1
+    let mut neg_bp2 = G2Affine::one();
+    neg_bp2.negate();
+    check_pairings(&[(&proof.a_bar, pk), (&proof.b_bar, &neg_bp2)])
+}
+
+impl Signature {
+    /// Length of the encoding returned by `to_bytes`.
+    pub const SIZE: usize = 80;
+
+    /// Encodes the signature as the compressed point `A` followed by `e` in
+    /// big-endian order.
+    pub fn to_bytes(&self) -> [u8; 80] {
+        let mut out = [0u8; 80];
+        out[..48].copy_from_slice(&self.a.to_compressed());
+        out[48..].copy_from_slice(&self.e.to_bytes_be());
+        out
+    }
+
+    /// Decodes a signature, rejecting `A` at infinity and `e` zero.
+    pub fn from_bytes(bytes: &[u8; 80]) -> Result<Self, Error> {
+        let a = read_point(bytes[..48].try_into().unwrap())?;
+        let e = read_scalar(bytes[48..].try_into().unwrap())?;
+        Ok(Signature { a, e })
+    }
+}
+
+impl Proof {
+    /// Length of the encoding of a proof with no undisclosed messages.
+    pub const BASE_SIZE: usize = 3 * 48 + 4 * 32;
+
+    /// Encodes the proof as the compressed points `Abar`, `Bbar` and `D`, followed
+    /// by the scalars `e^`, `r1^`, `r3^`, the `m^_j` and the challenge in big-endian
+    /// order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::BASE_SIZE + 32 * self.m_hat.len());
+        for p in &[self.a_bar, self.b_bar, self.d] {
+            out.extend_from_slice(&p.to_compressed());
+        }
+        for s in [self.e_hat, self.r1_hat, self.r3_hat]
+            .iter()
+            .chain(&self.m_hat)
+            .chain(Some(&self.challenge))
+        {
+            out.extend_from_slice(&s.to_bytes_be());
+        }
+        out
+    }
+
+    /// Decodes a proof, rejecting points at infinity and zero scalars.
+    ///
+    /// Fails with `LengthMismatch` if the length is not that of a proof.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < Self::BASE_SIZE || (bytes.len() - Self::BASE_SIZE) % 32 != 0 {
+            return Err(Error::LengthMismatch);
+        }
+
+        let (points, scalars) = bytes.split_at(3 * 48);
+        let points = points
+            .chunks_exact(48)
+            .map(|p| read_point(p.try_into().unwrap()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut scalars = scalars
+            .chunks_exact(32)
+            .map(|s| read_scalar(s.try_into().unwrap()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let challenge = scalars.pop().expect("at least four scalars");
+        let m_hat = scalars.split_off(3);
+        Ok(Proof {
+            a_bar: points[0],
+            b_bar: points[1],
+            d: points[2],
+            e_hat: scalars[0],
+            r1_hat: scalars[1],
+            r3_hat: scalars[2],
+            m_hat,
+            challenge,
+        })
+    }
+}
+
+/// The generators of a signature on `L` messages.
+struct Generators {
+    p1: G1Affine,
+    q1: G1Affine,
+    h: Vec<G1Affine>,
+}
+
+impl Generators {
+    fn new(num_messages: usize) -> Self {
+        let p1 = create_generators(1, &[API_ID, b"BP_MESSAGE_GENERATOR_SEED"].concat())[0];
+        let mut generators = create_generators(
+            num_messages + 1,
+            &[API_ID, b"MESSAGE_GENERATOR_SEED"].concat(),
+        );
+        let h = generators.split_off(1);
+
+        Generators {
+            p1,
+            q1: generators[0],
+            h,
+        }
+    }
+
+    /// `B = P1 + Q_1 domain + sum H_i msg_i`, in constant time in the messages,
+    /// which are blinded with `rng`.
+    fn b_ct<R: RngCore>(&self, domain: &Scalar, messages: &[Scalar], rng: &mut R) -> G1Projective {
+        let mut b = self.q1.mul(*domain);
+        b.add_assign_mixed(&self.p1);
+        for (h, msg) in self.h.iter().zip(messages) {
+            b.add_assign(&h.into_projective().mul_secret(msg, rng));
+        }
+        b
+    }
+
+    /// `B = P1 + Q_1 domain + sum H_i msg_i`, for public messages.
+    fn b_vartime(&self, domain: &Scalar, messages: &[Scalar]) -> G1Projective {
+        let mut bases = vec![self.p1, self.q1];
+        bases.extend_from_slice(&self.h);
+        let mut scalars = vec![Scalar::one(), *domain];
+        scalars.extend_from_slice(messages);
+        G1Projective::multi_exp_vartime(&bases, &scalars)
+    }
+}
+
+/// Derives `count` generators from a seed, as `create_generators` of the draft.
+fn create_generators(count: usize, generator_seed: &[u8]) -> Vec<G1Affine> {
+    let seed_dst = dst(b"SIG_GENERATOR_SEED_");
+    let generator_dst = dst(b"SIG_GENERATOR_DST_");
+
+    let mut v = expand_message_xmd(generator_seed, &seed_dst, EXPAND_LEN);
+    (1..=count as u64)
+        .map(|i| {
+            v.extend_from_slice(&i.to_be_bytes());
+            v = expand_message_xmd(&v, &seed_dst, EXPAND_LEN);
+            G1Projective::hash_to_curve(&v, &generator_dst, &[]).into_affine()
+        })
+        .collect()
+}
+
+fn messages_to_scalars(messages: &[&[u8]]) -> Vec<Scalar> {
+    let dst = dst(b"MAP_MSG_TO_SCALAR_AS_HASH_");
+    messages
+        .iter()
+        .map(|msg| Scalar::hash_to_scalar(msg, &dst))
+        .collect()
+}
+
+/// Hashes the public key, the generators and the header into the scalar `domain`.
+fn calculate_domain(pk: &G2Affine, generators: &Generators, header: &[u8]) -> Scalar {
+    let mut input = Vec::with_capacity(96 + 8 + 48 * (generators.h.len() + 1) + API_ID.len() + 8);
+    input.extend_from_slice(&pk.to_compressed());
+    input.extend_from_slice(&(generators.h.len() as u64).to_be_bytes());
+    input.extend_from_slice(&generators.q1.to_compressed());
+    for h in &generators.h {
+        input.extend_from_slice(&h.to_compressed());
+    }
+    input.extend_from_slice(API_ID);
+    input.extend_from_slice(&(header.len() as u64).to_be_bytes());
+    input.extend_from_slice(header);
+
+    Scalar::hash_to_scalar(&input, &dst(b"H2S_"))
+}
+
+/// Hashes the disclosed messages, the points `(Abar, Bbar, D, T1, T2)`, the domain
+/// and the presentation header into the challenge of a proof.
+fn calculate_challenge(
+    points: &[G1Affine; 5],
+    domain: &Scalar,
+    disclosed_indexes: &[usize],
+    messages: &[Scalar],
+    ph: &[u8],
+) -> Scalar {
+    let mut input = Vec::new();
+    input.extend_from_slice(&(disclosed_indexes.len() as u64).to_be_bytes());
+    for i in disclosed_indexes {
+        input.extend_from_slice(&(*i as u64).to_be_bytes());
+        input.extend_from_slice(&messages[*i].to_bytes_be());
+    }
+    for p in points {
+        input.extend_from_slice(&p.to_compressed());
+    }
+    input.extend_from_slice(&domain.to_bytes_be());
+    input.extend_from_slice(&(ph.len() as u64).to_be_bytes());
+    input.extend_from_slice(ph);
+
+    Scalar::hash_to_scalar(&input, &dst(b"H2S_"))
+}
+
+fn check_indexes(indexes: &[usize], num_messages: usize) -> Result<(), Error> {
+    let ascending = indexes.windows(2).all(|w| w[0] < w[1]);
+    if !ascending || indexes.last().map_or(false, |i| *i >= num_messages) {
+        return Err(Error::InvalidMessageIndex);
+    }
+    Ok(())
+}
+
+fn check_pairings(pairs: &[(&G1Affine, &G2Affine)]) -> Result<(), Error> {
+    if multi_pairing(pairs) != Fp12::one() {
+        return Err(Error::VerifyFailed);
+    }
+    Ok(())
+}
+
+fn dst(suffix: &[u8]) -> Vec<u8> {
+    [API_ID, suffix].concat()
+}
+
+fn read_point(bytes: &[u8; 48]) -> Result<G1Affine, Error> {
+    let p = G1Affine::from_compressed(bytes)?;
+    if p.is_zero() {
+        return Err(Error::BadEncoding);
+    }
+    Ok(p)
+}
+
+fn read_scalar(bytes: &[u8; 32]) -> Result<Scalar, Error> {
+    match Scalar::from_bytes_be(bytes) {
+        Some(s) if !s.is_zero() => Ok(s),
+        _ => Err(Error::InvalidScalar),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ])
+    }
+
+    const MESSAGES: [&[u8]; 4] = [b"name", b"age", b"country", b"expiry"];
+
+    fn keypair() -> (SecretScalar, G2Affine) {
+        let sk = key_gen(&[7u8; 32], b"key info", KEYGEN_DST).unwrap();
+        let pk = sk.public_key_g2().into_affine();
+        (sk, pk)
+    }
+
+    /// The messages of the draft's fixtures.
+    const FIXTURE_MESSAGES: [&str; 10] = [
+        "9872ad089e452c7b6e283dfac2a80d58e8d0ff71cc4d5e310a1debdda4a45f02",
+        "c344136d9ab02da4dd5908bbba913ae6f58c2cc844b802a6f811f5fb075f9b80",
+        "7372e9daa5ed31e6cd5c825eac1b855e84476a1d94932aa348e07b73",
+        "77fe97eb97a1ebe2e81e4e3597a3ee740a66e9ef2412472c",
+        "496694774c5604ab1b2544eababcf0f53278ff50",
+        "515ae153e22aae04ad16f759e07237b4",
+        "d183ddc6e2665aa4e2f088af",
+        "ac55fb33a75909ed",
+        "96012096",
+        "",
+    ];
+
+    /// Generators, key pair, and single and multiple message signatures of the
+    /// draft's fixtures for BLS12-381-SHA-256.
+    #[test]
+    fn test_fixtures() {
+        let generators = Generators::new(1);
+        assert_eq!(
+            hex::encode(generators.p1.to_compressed()),
+            "a8ce256102840821a3e94ea9025e4662b205762f9776b3a766c872b948f1fd225e7c59698588e70d11406d161b4e28c9"
+        );
+        assert_eq!(
+            hex::encode(generators.q1.to_compressed()),
+            "a9ec65b70a7fbe40c874c9eb041c2cb0a7af36ccec1bea48fa2ba4c2eb67ef7f9ecb17ed27d38d27cdeddff44c8137be"
+        );
+        assert_eq!(
+            hex::encode(generators.h[0].to_compressed()),
+            "98cd5313283aaf5db1b3ba8611fe6070d19e605de4078c38df36019fbaad0bd28dd090fd24ed27f7f4d22d5ff5dea7d4"
+        );
+
+        let mut rng = rng();
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(
+            "60e55110f76883a13d030b2f6bd11883422d5abde717569fc0731f51237169fc",
+            &mut bytes,
+        )
+        .unwrap();
+        let sk = SecretScalar::new(Scalar::from_bytes_be(&bytes).unwrap());
+        assert_eq!(
+            hex::encode(sk.public_key_g2().into_affine().to_compressed()),
+            "a820f230f6ae38503b86c70dc50b61c58a77e45c39ab25c0652bbaa8fa136f2851bd4781c9dcde39fc9d1d52c9e60268061e7d7632171d91aa8d460acee0e96f1e7c4cfb12d3ff9ab5d5dc91c277db75c845d649ef3c4f63aebc364cd55ded0c"
+        );
+
+        let pk = sk.public_key_g2().into_affine();
+        let header = hex::decode("11223344556677889900aabbccddeeff").unwrap();
+        let msg = hex::decode("9872ad089e452c7b6e283dfac2a80d58e8d0ff71cc4d5e310a1debdda4a45f02")
+            .unwrap();
+        let signature = sign(&sk, &pk, &header, &[&msg], &mut rng);
+        assert_eq!(
+            hex::encode(&signature.to_bytes()[..]),
+            "84773160b824e194073a57493dac1a20b667af70cd2352d8af241c77658da5253aa8458317cca0eae615690d55b1f27164657dcafee1d5c1973947aa70e2cfbb4c892340be5969920d0916067b4565a0"
+        );
+        assert_eq!(verify(&pk, &signature, &header, &[&msg]), Ok(()));
+
+        let messages: Vec<Vec<u8>> = FIXTURE_MESSAGES
+            .iter()
+            .map(|m| hex::decode(m).unwrap())
+            .collect();
+        let messages: Vec<&[u8]> = messages.iter().map(|m| &m[..]).collect();
+        let signature = sign(&sk, &pk, &header, &messages, &mut rng);
+        assert_eq!(
+            hex::encode(&signature.to_bytes()[..]),
+            "8339b285a4acd89dec7777c09543a43e3cc60684b0a6f8ab335da4825c96e1463e28f8c5f4fd0641d19cec5920d3a8ff4bedb6c9691454597bbd298288abed3632078557b2ace7d44caed846e1a0a1e8"
+        );
+        assert_eq!(verify(&pk, &signature, &header, &messages), Ok(()));
+        assert_eq!(
+            verify(&pk, &signature, &header, &messages[..9]),
+            Err(Error::VerifyFailed)
+        );
+
+        // Proofs over the fixture messages, disclosing some of them.
+        let ph = hex::decode("bed231d880675ed101ead304512e043ade9958dd0241ea70b4b3957fba941501")
+            .unwrap();
+        let disclosed = [0, 2, 4, 6];
+        let disclosed_messages: Vec<&[u8]> = disclosed.iter().map(|i| messages[*i]).collect();
+        let proof = proof_gen(
+            &pk, &signature, &header, &ph, &messages, &disclosed, &mut rng,
+        )
+        .unwrap();
+        assert_eq!(proof.m_hat.len(), 6);
+        assert_eq!(
+            proof_verify(&pk, &proof, &header, &ph, &disclosed_messages, &disclosed),
+            Ok(())
+        );
+        assert_eq!(
+            proof_verify(&pk, &proof, &header, &[], &disclosed_messages, &disclosed),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_key_gen() {
+        // The key pair fixture of the draft.
+        let sk = key_gen(
+            b"this-IS-just-an-Test-IKM-to-generate-$e(r@t#-key",
+            b"this-IS-some-key-metadata-to-be-used-in-test-key-gen",
+            KEYGEN_DST,
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(sk.expose().to_bytes_be()),
+            "60e55110f76883a13d030b2f6bd11883422d5abde717569fc0731f51237169fc"
+        );
+
+        let sk = key_gen(&[7u8; 32], b"key info", KEYGEN_DST).unwrap();
+        let other = key_gen(&[7u8; 32], b"other info", KEYGEN_DST).unwrap();
+        assert_ne!(sk.expose(), other.expose());
+
+        assert_eq!(
+            key_gen(&[7u8; 31], b"", KEYGEN_DST).err(),
+            Some(Error::InvalidKeyMaterial)
+        );
+        assert_eq!(
+            key_gen(&[7u8; 32], &vec![0u8; 1 << 16], KEYGEN_DST).err(),
+            Some(Error::InvalidKeyMaterial)
+        );
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let mut rng = rng();
+        let (sk, pk) = keypair();
+        let signature = sign(&sk, &pk, b"header", &MESSAGES, &mut rng);
+        assert_eq!(verify(&pk, &signature, b"header", &MESSAGES), Ok(()));
+
+        assert_eq!(
+            verify(&pk, &signature, b"other", &MESSAGES),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            verify(
+                &pk,
+                &signature,
+                b"header",
+                &[b"name", b"age", b"country", b"other"]
+            ),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            verify(&pk, &signature, b"header", &MESSAGES[..3]),
+            Err(Error::VerifyFailed)
+        );
+        let (_, other_pk) = {
+            let sk = key_gen(&[8u8; 32], b"", KEYGEN_DST).unwrap();
+            let pk = sk.public_key_g2().into_affine();
+            (sk, pk)
+        };
+        assert_eq!(
+            verify(&other_pk, &signature, b"header", &MESSAGES),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            verify(&G2Affine::zero(), &signature, b"header", &MESSAGES),
+            Err(Error::PublicKeyIsInfinity)
+        );
+
+        // Signing is deterministic despite the blinding, and works without messages.
+        assert_eq!(sign(&sk, &pk, b"header", &MESSAGES, &mut rng), signature);
+        let empty = sign(&sk, &pk, b"", &[], &mut rng);
+        assert_eq!(verify(&pk, &empty, b"", &[]), Ok(()));
+
+        let bytes = signature.to_bytes();
+        assert_eq!(Signature::from_bytes(&bytes), Ok(signature));
+
+        let mut zero_e = bytes;
+        zero_e[48..].copy_from_slice(&[0u8; 32]);
+        assert_eq!(Signature::from_bytes(&zero_e), Err(Error::InvalidScalar));
+        let mut infinity = bytes;
+        infinity[..48].copy_from_slice(&G1Affine::zero().to_compressed());
+        assert_eq!(Signature::from_bytes(&infinity), Err(Error::BadEncoding));
+    }
+
+    #[test]
+    fn test_proof() {
+        let mut rng = rng();
+        let (sk, pk) = keypair();
+        let signature = sign(&sk, &pk, b"header", &MESSAGES, &mut rng);
+
+        let proof = proof_gen(
+            &pk,
+            &signature,
+            b"header",
+            b"presentation",
+            &MESSAGES,
+            &[0, 2],
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(proof.m_hat.len(), 2);
+        let disclosed: [&[u8]; 2] = [b"name", b"country"];
+        assert_eq!(
+            proof_verify(&pk, &proof, b"header", b"presentation", &disclosed, &[0, 2]),
+            Ok(())
+        );
+
+        assert_eq!(
+            proof_verify(&pk, &proof, b"header", b"other", &disclosed, &[0, 2]),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            proof_verify(&pk, &proof, b"other", b"presentation", &disclosed, &[0, 2]),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            proof_verify(
+                &pk,
+                &proof,
+                b"header",
+                b"presentation",
+                &[b"name", b"other"],
+                &[0, 2]
+            ),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            proof_verify(&pk, &proof, b"header", b"presentation", &disclosed, &[0, 1]),
+            Err(Error::VerifyFailed)
+        );
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), Proof::BASE_SIZE + 2 * 32);
+        assert_eq!(Proof::from_bytes(&bytes), Ok(proof.clone()));
+        assert_eq!(Proof::from_bytes(&bytes[1..]), Err(Error::LengthMismatch));
+
+        // Proofs of the same signature are randomized.
+        let other = proof_gen(
+            &pk,
+            &signature,
+            b"header",
+            b"presentation",
+            &MESSAGES,
+            &[0, 2],
+            &mut rng,
+        )
+        .unwrap();
+        assert_ne!(other.a_bar, proof.a_bar);
+        assert_ne!(other.d, proof.d);
+
+        // All messages, or none, can be disclosed.
+        let all = proof_gen(
+            &pk,
+            &signature,
+            b"header",
+            b"",
+            &MESSAGES,
+            &[0, 1, 2, 3],
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(
+            proof_verify(&pk, &all, b"header", b"", &MESSAGES, &[0, 1, 2, 3]),
+            Ok(())
+        );
+        let none = proof_gen(&pk, &signature, b"header", b"", &MESSAGES, &[], &mut rng).unwrap();
+        assert_eq!(proof_verify(&pk, &none, b"header", b"", &[], &[]), Ok(()));
+
+        // A proof from an invalid signature does not verify.
+        let forged = Signature {
+            a: signature.a,
+            e: signature.e + Scalar::one(),
+        };
+        let proof = proof_gen(&pk, &forged, b"header", b"", &MESSAGES, &[1], &mut rng).unwrap();
+        assert_eq!(
+            proof_verify(&pk, &proof, b"header", b"", &[b"age"], &[1]),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_invalid_indexes() {
+        let mut rng = rng();
+        let (sk, pk) = keypair();
+        let signature = sign(&sk, &pk, b"", &MESSAGES, &mut rng);
+        let gen = |indexes: &[usize], rng: &mut XorShiftRng| {
+            proof_gen(&pk, &signature, b"", b"", &MESSAGES, indexes, rng).err()
+        };
+
+        assert_eq!(gen(&[4], &mut rng), Some(Error::InvalidMessageIndex));
+        assert_eq!(gen(&[2, 1], &mut rng), Some(Error::InvalidMessageIndex));
+        assert_eq!(gen(&[1, 1], &mut rng), Some(Error::InvalidMessageIndex));
+
+        let proof = proof_gen(&pk, &signature, b"", b"", &MESSAGES, &[1], &mut rng).unwrap();
+        assert_eq!(
+            proof_verify(&pk, &proof, b"", b"", &[b"age"], &[4]),
+            Err(Error::InvalidMessageIndex)
+        );
+        assert_eq!(
+            proof_verify(&pk, &proof, b"", b"", &[], &[1]),
+            Err(Error::LengthMismatch)
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! Contributions to powers of tau ceremonies, and their verification.
+//!
+//! A participant multiplies each power `[tau^i]` of the current setup by `x^i` for a
+//! secret `x`, which gives the powers of `tau x`, and publishes an `UpdateProof`
+//! that they know `x`. Anyone can then check the update against the previous setup
+//! with `verify_update`, and the chain of contributions recorded in an Ethereum
+//! transcript with `verify_witness`.
+
+use fff::Field;
+use groupy::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+use zeroize::Zeroize;
+
+use crate::powers_of_tau::{same_ratio, verify_powers, PowersOfTau, Transcript};
+use crate::{
+    multi_pairing, Error, Fp12, G1Affine, G1Projective, G2Affine, G2Projective, Scalar,
+    SecretScalar,
+};
+
+/// Domain separation tag of the points hashed in proofs of knowledge.
+const POK_DST: &[u8] = b"BLSTRS_CEREMONY_POK_BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// The proof that the powers of an update are those of the previous setup
+/// multiplied by a secret `x` known to the contributor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateProof {
+    /// `[x]_2`, the public key recorded in Ethereum transcripts.
+    pub x_g2: G2Affine,
+    /// A random point `s` of $\mathbb{G}_1$.
+    pub s_g1: G1Affine,
+    /// `s * x`.
+    pub s_x_g1: G1Affine,
+    /// `h * x`, for `h` the point of $\mathbb{G}_2$ hashed from `s`, `s * x` and the
+    /// updated `[tau]_1`.
+    pub h_x_g2: G2Affine,
+}
+
+impl UpdateProof {
+    /// Checks that the points are on the curve and in the prime order subgroups, as
+    /// `verify_update` does first. Proofs from untrusted contributors must be checked
+    /// before their points are used in any other way.
+    ///
+    /// Fails with `PointNotOnCurve` or `PointNotInGroup` otherwise.
+    pub fn check_points(&self) -> Result<(), Error> {
+        let g1 = [&self.s_g1, &self.s_x_g1];
+        let g2 = [&self.x_g2, &self.h_x_g2];
+        if !g1.iter().all(|p| p.is_on_curve()) || !g2.iter().all(|p| p.is_on_curve()) {
+            return Err(Error::PointNotOnCurve);
+        }
+        if !g1.iter().all(|p| p.is_torsion_free()) || !g2.iter().all(|p| p.is_torsion_free()) {
+            return Err(Error::PointNotInGroup);
+        }
+
+        Ok(())
+    }
+}
+
+/// Multiplies the powers of `tau` by the powers of the secret `x` and returns the
+/// proof of the update.
+///
+/// The secret should be sampled with `SecretScalar::random` and dropped afterwards.
+///
+/// # Panics
+///
+/// Panics if there are fewer than two powers in $\mathbb{G}_1$, or if `x` is zero.
+pub fn contribute<R: RngCore>(
+    powers: &mut PowersOfTau,
+    x: &SecretScalar,
+    rng: &mut R,
+) -> UpdateProof {
+    assert!(
+        powers.g1_powers.len() >= 2,
+        "the setup must contain [tau]_1"
+    );
+    let x = x.expose();
+    assert!(!x.is_zero(), "the secret must not be zero");
+
+    for (p, x_i) in powers.g1_powers.iter_mut().zip(SecretPowers::new(x)) {
+        *p = p.into_projective().mul_secret(&x_i, rng).into_affine();
+    }
+    for (p, x_i) in powers.g2_powers.iter_mut().zip(SecretPowers::new(x)) {
+        *p = p.into_projective().mul_secret(&x_i, rng).into_affine();
+    }
+
+    prove_update(&powers.g1_powers[1], x, rng)
+}
+
+/// Verifies that `next` is `prev` updated with the secret of `proof`, and that it is a
+/// well formed setup with `verify_powers`.
+///
+/// Fails with `PointNotOnCurve` or `PointNotInGroup` if a point of the proof is
+/// invalid, see `UpdateProof::check_points`, and with `LengthMismatch` if the setups
+/// do not have the same numbers of powers, or fewer than two.
+pub fn verify_update<R: RngCore>(
+    prev: &PowersOfTau,
+    next: &PowersOfTau,
+    proof: &UpdateProof,
+    rng: &mut R,
+) -> Result<(), Error> {
+    if prev.g1_powers.len() != next.g1_powers.len() || prev.g2_powers.len() != next.g2_powers.len()
+    {
+        return Err(Error::LengthMismatch);
+    }
+    proof.check_points()?;
+    verify_powers(&next.g1_powers, &next.g2_powers, rng)?;
+
+    // The contributor knows x, with s * x and h * x for h hashed from s * x.
+    if proof.s_g1.is_zero() {
+        return Err(Error::VerifyFailed);
+    }
+    let h = hash_to_g2(&proof.s_g1, &proof.s_x_g1, &next.g1_powers[1]).into_affine();
+    same_ratio((&proof.s_g1, &proof.s_x_g1), (&h, &proof.h_x_g2))?;
+    // [x]_2 has the same x.
+    same_ratio(
+        (&proof.s_g1, &proof.s_x_g1),
+        (&G2Affine::one(), &proof.x_g2),
+    )?;
+    // [tau x]_1 = [tau]_1 * x, the other powers follow from verify_powers.
+    same_ratio(
+        (&prev.g1_powers[1], &next.g1_powers[1]),
+        (&G2Affine::one(), &proof.x_g2),
+    )
+}
+
+/// Verifies that the witness of an Ethereum transcript is a chain of updates ending
+/// with its powers: each running product is the previous one, starting from the
+/// generator, multiplied by the secret of the public key, and the last one is
+/// `[tau]_1`.
+///
+/// The pairing checks are combined with random coefficients, for a single final
+/// exponentiation. Fails with `LengthMismatch` if there is not one public key per
+/// running product.
+pub fn verify_witness<R: RngCore>(transcript: &Transcript, rng: &mut R) -> Result<(), Error> {
+    let witness = &transcript.witness;
+    if witness.running_products.len() != witness.pot_pubkeys.len() {
+        return Err(Error::LengthMismatch);
+    }
+    let last = witness
+        .running_products
+        .last()
+        .copied()
+        .unwrap_or_else(G1Affine::one);
+    if transcript.powers_of_tau.g1_powers.get(1) != Some(&last) {
+        return Err(Error::VerifyFailed);
+    }
+
+    // e(sum_i r_i P_i, -[1]_2) prod_i e(r_i P_(i - 1), X_i) = 1
+    let coeffs: Vec<Scalar> = witness
+        .running_products
+        .iter()
+        .map(|_| Scalar::random(rng))
+        .collect();
+    let mut lhs = G1Projective::multi_exp_vartime(&witness.running_products, &coeffs).into_affine();
+    lhs.negate();
+
+    let mut prev = G1Affine::one();
+    let scaled: Vec<G1Affine> = witness
+        .running_products
+        .iter()
+        .zip(&coeffs)
+        .map(|(p, r)| {
+            let s = prev.into_projective().mul_vartime(r).into_affine();
+            prev = *p;
+            s
+        })
+        .collect();
+
+    let g2 = G2Affine::one();
+    let mut pairs = vec![(&lhs, &g2)];
+    pairs.extend(scaled.iter().zip(&witness.pot_pubkeys));
+    if multi_pairing(&pairs) == Fp12::one() {
+        Ok(())
+    } else {
+        Err(Error::VerifyFailed)
+    }
+}
+
+/// Proves knowledge of `x` for the update to `[tau]_1` equal to `tau_g1`.
+fn prove_update<R: RngCore>(tau_g1: &G1Affine, x: &Scalar, rng: &mut R) -> UpdateProof {
+    let s = G1Projective::random(rng).into_affine();
+    let s_x = s.into_projective().mul_secret(x, rng).into_affine();
+    let h = hash_to_g2(&s, &s_x, tau_g1);
+
+    UpdateProof {
+        x_g2: G2Projective::one().mul_secret(x, rng).into_affine(),
+        s_g1: s,
+        s_x_g1: s_x,
+        h_x_g2: h.mul_secret(x, rng).into_affine(),
+    }
+}
+
+fn hash_to_g2(s: &G1Affine, s_x: &G1Affine, tau_g1: &G1Affine) -> G2Projective {
+    let mut msg = Vec::with_capacity(3 * 48);
+    msg.extend_from_slice(&s.to_compressed());
+    msg.extend_from_slice(&s_x.to_compressed());
+    msg.extend_from_slice(&tau_g1.to_compressed());
+
+    G2Projective::hash_to_curve(&msg, POK_DST, &[])
+}
+
+/// The powers `x^i` of a secret. The running power is wiped on drop, the caller
+/// must not keep the returned copies.
+struct SecretPowers<'a> {
+    x: &'a Scalar,
+    cur: Scalar,
+}
+
+impl<'a> SecretPowers<'a> {
+    fn new(x: &'a Scalar) -> Self {
+        SecretPowers {
+            x,
+            cur: Scalar::one(),
+        }
+    }
+}
+
+impl Iterator for SecretPowers<'_> {
+    type Item = Scalar;
+
+    fn next(&mut self) -> Option<Scalar> {
+        let res = self.cur;
+        self.cur *= self.x;
+        Some(res)
+    }
+}
+
+impl Drop for SecretPowers<'_> {
+    fn drop(&mut self) {
+        self.cur.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::powers_of_tau::Witness;
+    use crate::{Fp, Fp2};
+
+    use fff::SqrtField;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// The setup before any contribution, where `tau` is one.
+    fn initial_transcript(num_g1_powers: usize, num_g2_powers: usize) -> Transcript {
+        Transcript {
+            num_g1_powers,
+            num_g2_powers,
+            powers_of_tau: PowersOfTau {
+                g1_powers: vec![G1Affine::one(); num_g1_powers],
+                g2_powers: vec![G2Affine::one(); num_g2_powers],
+            },
+            witness: Witness {
+                running_products: vec![],
+                pot_pubkeys: vec![],
+                bls_signatures: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_ceremony_contribute() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut transcript = initial_transcript(16, 4);
+        assert_eq!(verify_witness(&transcript, &mut rng), Ok(()));
+
+        let mut tau = Scalar::one();
+        for _ in 0..3 {
+            let prev = transcript.powers_of_tau.clone();
+            let x = Scalar::random(&mut rng);
+            tau *= x;
+            let proof = contribute(
+                &mut transcript.powers_of_tau,
+                &SecretScalar::new(x),
+                &mut rng,
+            );
+            assert_eq!(
+                verify_update(&prev, &transcript.powers_of_tau, &proof, &mut rng),
+                Ok(())
+            );
+
+            transcript
+                .witness
+                .running_products
+                .push(transcript.powers_of_tau.g1_powers[1]);
+            transcript.witness.pot_pubkeys.push(proof.x_g2);
+            assert_eq!(verify_witness(&transcript, &mut rng), Ok(()));
+            assert_eq!(transcript.verify_consistency(&mut rng), Ok(()));
+        }
+
+        // The powers are those of the product of the secrets.
+        for (i, p) in transcript.powers_of_tau.g1_powers.iter().enumerate() {
+            assert_eq!(*p, (G1Affine::one() * tau.pow([i as u64])).into_affine());
+        }
+        for (i, p) in transcript.powers_of_tau.g2_powers.iter().enumerate() {
+            assert_eq!(*p, (G2Affine::one() * tau.pow([i as u64])).into_affine());
+        }
+    }
+
+    #[test]
+    fn test_ceremony_invalid_updates() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut transcript = initial_transcript(8, 3);
+        let prev = transcript.powers_of_tau.clone();
+        let x = SecretScalar::random(&mut rng);
+        let proof = contribute(&mut transcript.powers_of_tau, &x, &mut rng);
+        let next = transcript.powers_of_tau.clone();
+        assert_eq!(verify_update(&prev, &next, &proof, &mut rng), Ok(()));
+
+        // A proof for another secret or another update.
+        let mut other = prev.clone();
+        let other_proof = contribute(&mut other, &SecretScalar::random(&mut rng), &mut rng);
+        assert_eq!(
+            verify_update(&prev, &next, &other_proof, &mut rng),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            verify_update(&next, &other, &other_proof, &mut rng),
+            Err(Error::VerifyFailed)
+        );
+
+        // A proof whose public key does not match the proof of knowledge.
+        let mut bad = proof;
+        bad.x_g2 = G2Affine::one();
+        assert_eq!(
+            verify_update(&prev, &next, &bad, &mut rng),
+            Err(Error::VerifyFailed)
+        );
+        let mut bad = proof;
+        bad.h_x_g2 = G2Projective::random(&mut rng).into_affine();
+        assert_eq!(
+            verify_update(&prev, &next, &bad, &mut rng),
+            Err(Error::VerifyFailed)
+        );
+        let mut bad = proof;
+        bad.s_g1 = G1Affine::zero();
+        bad.s_x_g1 = G1Affine::zero();
+        assert_eq!(
+            verify_update(&prev, &next, &bad, &mut rng),
+            Err(Error::VerifyFailed)
+        );
+
+        // Points outside of the prime order subgroups, or off the curve.
+        let p = (0u64..)
+            .find_map(|x| {
+                let x = Fp::from(x);
+                (x * x * x + Fp::from(4))
+                    .sqrt()
+                    .map(|y| G1Affine::from_raw_unchecked(x, y, false))
+            })
+            .unwrap();
+        let mut bad = proof;
+        bad.s_x_g1 = p;
+        assert_eq!(
+            verify_update(&prev, &next, &bad, &mut rng),
+            Err(Error::PointNotInGroup)
+        );
+        let b = Fp2::new(Fp::from(4), Fp::from(4));
+        let q = (0u64..)
+            .find_map(|x| {
+                let x = Fp2::from(x);
+                (x * x * x + b)
+                    .sqrt()
+                    .map(|y| G2Affine::from_raw_unchecked(x, y, false))
+            })
+            .unwrap();
+        let mut bad = proof;
+        bad.h_x_g2 = q;
+        assert_eq!(bad.check_points(), Err(Error::PointNotInGroup));
+        let g = G2Affine::one();
+        bad.h_x_g2 = G2Affine::from_raw_unchecked(g.x(), g.y() + Fp2::one(), false);
+        assert_eq!(
+            verify_update(&prev, &next, &bad, &mut rng),
+            Err(Error::PointNotOnCurve)
+        );
+        assert_eq!(proof.check_points(), Ok(()));
+
+        // Powers that are not consistent.
+        let mut bad = next.clone();
+        bad.g1_powers.swap(4, 5);
+        assert_eq!(
+            verify_update(&prev, &bad, &proof, &mut rng),
+            Err(Error::VerifyFailed)
+        );
+        let mut bad = next.clone();
+        bad.g2_powers.pop();
+        assert_eq!(
+            verify_update(&prev, &bad, &proof, &mut rng),
+            Err(Error::LengthMismatch)
+        );
+
+        // Witnesses that do not chain.
+        transcript.witness.running_products.push(next.g1_powers[1]);
+        transcript.witness.pot_pubkeys.push(proof.x_g2);
+        assert_eq!(verify_witness(&transcript, &mut rng), Ok(()));
+        let mut bad = transcript.clone();
+        bad.witness.pot_pubkeys[0] = other_proof.x_g2;
+        assert_eq!(verify_witness(&bad, &mut rng), Err(Error::VerifyFailed));
+        let mut bad = transcript.clone();
+        bad.witness.running_products[0] = other.g1_powers[1];
+        assert_eq!(verify_witness(&bad, &mut rng), Err(Error::VerifyFailed));
+        let mut bad = transcript;
+        bad.witness.pot_pubkeys.pop();
+        assert_eq!(verify_witness(&bad, &mut rng), Err(Error::LengthMismatch));
+    }
+}
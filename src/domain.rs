@@ -0,0 +1,682 @@
+//! Radix-2 evaluation domains over the scalar field, for FFTs over subgroups of
+//! roots of unity.
+//!
+//! The FFTs also apply to vectors of points of $\mathbb{G}_1$ or $\mathbb{G}_2$,
+//! seen as polynomials with coefficients in the group.
+
+use fff::{Field, PrimeField};
+use groupy::CurveProjective;
+
+use crate::{BatchInvert, Error, Scalar};
+
+/// The multiplicative subgroup of the scalar field of order `2^log_size`, used to
+/// convert polynomials between coefficient and evaluation form.
+///
+/// Element `i` of the domain is `omega^i`. Coset variants evaluate over
+/// `g * omega^i` instead, with `g` the multiplicative generator of the field, which
+/// avoids the roots of the vanishing polynomial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluationDomain {
+    log_size: u32,
+    omega: Scalar,
+    omega_inv: Scalar,
+    size_inv: Scalar,
+    generator: Scalar,
+    generator_inv: Scalar,
+}
+
+impl EvaluationDomain {
+    /// Creates the smallest domain with at least `min_size` elements, failing if it
+    /// would be larger than `2^32`.
+    pub fn new(min_size: usize) -> Result<Self, Error> {
+        let log_size = min_size.max(1).next_power_of_two().trailing_zeros();
+        if log_size > Scalar::S {
+            return Err(Error::DomainTooLarge);
+        }
+
+        // The root of unity has order 2^S.
+        let mut omega = Scalar::root_of_unity();
+        for _ in log_size..Scalar::S {
+            omega.square();
+        }
+        let size_inv = Scalar::from(1u64 << log_size).inverse().unwrap();
+        let generator = Scalar::multiplicative_generator();
+
+        Ok(EvaluationDomain {
+            log_size,
+            omega,
+            omega_inv: omega.inverse().unwrap(),
+            size_inv,
+            generator,
+            generator_inv: generator.inverse().unwrap(),
+        })
+    }
+
+    /// Returns the number of elements of the domain.
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// Returns the base 2 logarithm of the size of the domain.
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Returns the generator of the domain, a primitive root of unity of order `size`.
+    pub fn omega(&self) -> Scalar {
+        self.omega
+    }
+
+    /// Returns the inverse of `omega`.
+    pub fn omega_inv(&self) -> Scalar {
+        self.omega_inv
+    }
+
+    /// Returns the inverse of the size of the domain.
+    pub fn size_inv(&self) -> Scalar {
+        self.size_inv
+    }
+
+    /// Returns the elements of the domain, `omega^i` for `i` in `0..size`.
+    pub fn elements(&self) -> Vec<Scalar> {
+        powers(Scalar::one(), self.omega, self.size())
+    }
+
+    /// Evaluates the polynomial with coefficients `a` over the domain, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn fft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        radix2_fft(a, self.omega);
+    }
+
+    /// Interpolates the evaluations `a` over the domain into coefficients, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn ifft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        radix2_fft(a, self.omega_inv);
+        scale(a, self.size_inv);
+    }
+
+    /// Evaluates the polynomial with coefficients `a` over the coset of the domain,
+    /// in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn coset_fft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        distribute_powers(a, self.generator);
+        radix2_fft(a, self.omega);
+    }
+
+    /// Interpolates the evaluations `a` over the coset of the domain into
+    /// coefficients, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn coset_ifft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        radix2_fft(a, self.omega_inv);
+        scale(a, self.size_inv);
+        distribute_powers(a, self.generator_inv);
+    }
+
+    /// Evaluates the polynomial with coefficients `a` in $\mathbb{G}_1$ or
+    /// $\mathbb{G}_2$ over the domain, in place.
+    ///
+    /// This costs a scalar multiplication per butterfly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn fft_group<G: CurveProjective<Scalar = Scalar>>(&self, a: &mut [G]) {
+        self.check_len(a);
+        radix2_fft(a, self.omega);
+    }
+
+    /// Interpolates the evaluations `a` in $\mathbb{G}_1$ or $\mathbb{G}_2$ over
+    /// the domain into coefficients, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn ifft_group<G: CurveProjective<Scalar = Scalar>>(&self, a: &mut [G]) {
+        self.check_len(a);
+        radix2_fft(a, self.omega_inv);
+        scale(a, self.size_inv);
+    }
+
+    /// Evaluates the vanishing polynomial of the domain, `tau^size - 1`, at `tau`.
+    pub fn evaluate_vanishing_polynomial(&self, tau: &Scalar) -> Scalar {
+        let mut res = tau.pow([self.size() as u64]);
+        res.sub_assign(&Scalar::one());
+        res
+    }
+
+    /// Divides the evaluations `a` over the coset of the domain by the vanishing
+    /// polynomial, which is the non-zero constant `g^size - 1` there.
+    pub fn divide_by_vanishing_poly_on_coset(&self, a: &mut [Scalar]) {
+        let z = self.evaluate_vanishing_polynomial(&self.generator);
+        scale(
+            a,
+            z.inverse().expect("the coset is disjoint from the domain"),
+        );
+    }
+
+    /// Evaluates every Lagrange basis polynomial of the domain at `tau`.
+    ///
+    /// The polynomial `L_i` is one at `omega^i` and zero at the other elements, so
+    /// that `sum_i L_i(tau) * a_i` is the value at `tau` of the polynomial with
+    /// evaluations `a`.
+    pub fn evaluate_all_lagrange_coefficients(&self, tau: &Scalar) -> Vec<Scalar> {
+        let size = self.size();
+        let z = self.evaluate_vanishing_polynomial(tau);
+        if z.is_zero() {
+            // tau is in the domain.
+            return self
+                .elements()
+                .iter()
+                .map(|w| {
+                    if w == tau {
+                        Scalar::one()
+                    } else {
+                        Scalar::zero()
+                    }
+                })
+                .collect();
+        }
+
+        // L_i(tau) = (tau^n - 1) / n * omega^i / (tau - omega^i).
+        let elements = self.elements();
+        let mut denoms: Vec<Scalar> = elements
+            .iter()
+            .map(|w| {
+                let mut d = *tau;
+                d.sub_assign(w);
+                d
+            })
+            .collect();
+        Scalar::batch_invert(&mut denoms);
+
+        let mut factor = z;
+        factor.mul_assign(&self.size_inv);
+        (0..size)
+            .map(|i| {
+                let mut l = factor;
+                l.mul_assign(&elements[i]);
+                l.mul_assign(&denoms[i]);
+                l
+            })
+            .collect()
+    }
+
+    fn check_len<T>(&self, a: &[T]) {
+        assert_eq!(a.len(), self.size(), "length must match the domain size");
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl EvaluationDomain {
+    /// Like `fft`, running the butterflies in parallel.
+    pub fn par_fft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        parallel::radix2_fft(a, self.omega);
+    }
+
+    /// Like `ifft`, running the butterflies in parallel.
+    pub fn par_ifft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        parallel::radix2_fft(a, self.omega_inv);
+        parallel::scale(a, self.size_inv);
+    }
+
+    /// Like `coset_fft`, running the butterflies in parallel.
+    pub fn par_coset_fft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        parallel::distribute_powers(a, self.generator);
+        parallel::radix2_fft(a, self.omega);
+    }
+
+    /// Like `coset_ifft`, running the butterflies in parallel.
+    pub fn par_coset_ifft(&self, a: &mut [Scalar]) {
+        self.check_len(a);
+        parallel::radix2_fft(a, self.omega_inv);
+        parallel::scale(a, self.size_inv);
+        parallel::distribute_powers(a, self.generator_inv);
+    }
+
+    /// Like `fft_group`, running the butterflies in parallel.
+    pub fn par_fft_group<G: CurveProjective<Scalar = Scalar>>(&self, a: &mut [G]) {
+        self.check_len(a);
+        parallel::radix2_fft(a, self.omega);
+    }
+
+    /// Like `ifft_group`, running the butterflies in parallel.
+    pub fn par_ifft_group<G: CurveProjective<Scalar = Scalar>>(&self, a: &mut [G]) {
+        self.check_len(a);
+        parallel::radix2_fft(a, self.omega_inv);
+        parallel::scale(a, self.size_inv);
+    }
+}
+
+/// The elements the FFTs apply to: scalars, and group elements with scalar
+/// multiplication.
+trait FftElement: Copy + Send + Sync {
+    /// Multiplies by `c` in place.
+    fn scale(&mut self, c: &Scalar);
+
+    /// Replaces `(x, y)` by `(x + w y, x - w y)`.
+    fn butterfly(x: &mut Self, y: &mut Self, w: &Scalar);
+}
+
+impl FftElement for Scalar {
+    fn scale(&mut self, c: &Scalar) {
+        self.mul_assign(c);
+    }
+
+    fn butterfly(x: &mut Self, y: &mut Self, w: &Scalar) {
+        let mut t = *y;
+        t.mul_assign(w);
+        *y = *x;
+        y.sub_assign(&t);
+        x.add_assign(&t);
+    }
+}
+
+impl<G: CurveProjective<Scalar = Scalar>> FftElement for G {
+    fn scale(&mut self, c: &Scalar) {
+        self.mul_assign(c.into_repr());
+    }
+
+    fn butterfly(x: &mut Self, y: &mut Self, w: &Scalar) {
+        let mut t = *y;
+        t.mul_assign(w.into_repr());
+        *y = *x;
+        y.sub_assign(&t);
+        x.add_assign(&t);
+    }
+}
+
+/// Returns `start * step^i` for `i` in `0..n`.
+fn powers(start: Scalar, step: Scalar, n: usize) -> Vec<Scalar> {
+    let mut res = Vec::with_capacity(n);
+    let mut cur = start;
+    for _ in 0..n {
+        res.push(cur);
+        cur.mul_assign(&step);
+    }
+    res
+}
+
+fn scale<T: FftElement>(a: &mut [T], c: Scalar) {
+    for x in a.iter_mut() {
+        x.scale(&c);
+    }
+}
+
+/// Multiplies `a[i]` by `g^i`.
+fn distribute_powers(a: &mut [Scalar], g: Scalar) {
+    let mut cur = Scalar::one();
+    for x in a.iter_mut() {
+        x.mul_assign(&cur);
+        cur.mul_assign(&g);
+    }
+}
+
+/// Permutes `a`, whose length must be a power of two, by moving each element to the
+/// index with reversed bits.
+pub(crate) fn bit_reverse<T>(a: &mut [T]) {
+    debug_assert!(a.is_empty() || a.len().is_power_of_two());
+    let log_n = a.len().trailing_zeros();
+    if log_n == 0 {
+        return;
+    }
+    for i in 0..a.len() {
+        let j = i.reverse_bits() >> (8 * core::mem::size_of::<usize>() as u32 - log_n);
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+}
+
+/// Butterflies over the two halves of a block, `twiddles[j]` being the twiddle of
+/// pair `j`.
+fn butterflies<'a, T: FftElement>(
+    lo: &mut [T],
+    hi: &mut [T],
+    twiddles: impl Iterator<Item = &'a Scalar>,
+) {
+    for ((x, y), w) in lo.iter_mut().zip(hi.iter_mut()).zip(twiddles) {
+        T::butterfly(x, y, w);
+    }
+}
+
+/// In place iterative Cooley-Tukey FFT with root of unity `omega` of order `a.len()`.
+fn radix2_fft<T: FftElement>(a: &mut [T], omega: Scalar) {
+    let n = a.len();
+    bit_reverse(a);
+    let twiddles = powers(Scalar::one(), omega, n / 2);
+
+    let mut half = 1;
+    while half < n {
+        let stride = n / (2 * half);
+        for block in a.chunks_mut(2 * half) {
+            let (lo, hi) = block.split_at_mut(half);
+            butterflies(lo, hi, twiddles.iter().step_by(stride));
+        }
+        half *= 2;
+    }
+}
+
+#[cfg(feature = "parallel")]
+mod parallel {
+    use fff::Field;
+    use rayon::prelude::*;
+
+    use super::{bit_reverse, butterflies, powers, FftElement};
+    use crate::Scalar;
+
+    /// Below this many elements per task, work is not split further.
+    const MIN_CHUNK: usize = 1 << 10;
+
+    pub(super) fn scale<T: FftElement>(a: &mut [T], c: Scalar) {
+        a.par_chunks_mut(MIN_CHUNK)
+            .for_each(|chunk| super::scale(chunk, c));
+    }
+
+    pub(super) fn distribute_powers(a: &mut [Scalar], g: Scalar) {
+        a.par_chunks_mut(MIN_CHUNK)
+            .enumerate()
+            .for_each(|(i, chunk)| {
+                let mut start = g.pow([(i * MIN_CHUNK) as u64]);
+                for x in chunk.iter_mut() {
+                    x.mul_assign(&start);
+                    start.mul_assign(&g);
+                }
+            });
+    }
+
+    pub(super) fn radix2_fft<T: FftElement>(a: &mut [T], omega: Scalar) {
+        let n = a.len();
+        bit_reverse(a);
+        let twiddles = powers(Scalar::one(), omega, n / 2);
+
+        let mut half = 1;
+        while half < n {
+            let stride = n / (2 * half);
+            if half < MIN_CHUNK {
+                // Many small blocks, one task per group of blocks.
+                let blocks_per_task = (MIN_CHUNK / half).max(1);
+                a.par_chunks_mut(2 * half * blocks_per_task)
+                    .for_each(|chunk| {
+                        for block in chunk.chunks_mut(2 * half) {
+                            let (lo, hi) = block.split_at_mut(half);
+                            butterflies(lo, hi, twiddles.iter().step_by(stride));
+                        }
+                    });
+            } else {
+                // Few large blocks, split each block into tasks.
+                for block in a.chunks_mut(2 * half) {
+                    let (lo, hi) = block.split_at_mut(half);
+                    lo.par_chunks_mut(MIN_CHUNK)
+                        .zip(hi.par_chunks_mut(MIN_CHUNK))
+                        .enumerate()
+                        .for_each(|(i, (lo, hi))| {
+                            let twiddles =
+                                twiddles[i * MIN_CHUNK * stride..].iter().step_by(stride);
+                            butterflies(lo, hi, twiddles);
+                        });
+                }
+            }
+            half *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{G1Projective, G2Projective};
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// Evaluates the polynomial with coefficients `a` at `x` with Horner's rule.
+    fn evaluate(a: &[Scalar], x: &Scalar) -> Scalar {
+        let mut res = Scalar::zero();
+        for c in a.iter().rev() {
+            res.mul_assign(x);
+            res.add_assign(c);
+        }
+        res
+    }
+
+    #[test]
+    fn test_domain_new() {
+        assert_eq!(EvaluationDomain::new(0).unwrap().size(), 1);
+        assert_eq!(EvaluationDomain::new(5).unwrap().size(), 8);
+        assert_eq!(EvaluationDomain::new(8).unwrap().log_size(), 3);
+
+        let domain = EvaluationDomain::new(1 << 32).unwrap();
+        assert_eq!(domain.omega(), Scalar::root_of_unity());
+        assert_eq!(
+            EvaluationDomain::new((1 << 32) + 1),
+            Err(Error::DomainTooLarge)
+        );
+
+        for log_size in 0..10 {
+            let domain = EvaluationDomain::new(1 << log_size).unwrap();
+            let omega = domain.omega();
+            assert_eq!(omega.pow([1u64 << log_size]), Scalar::one());
+            if log_size > 0 {
+                assert_ne!(omega.pow([1u64 << (log_size - 1)]), Scalar::one());
+            }
+            let mut one = omega;
+            one.mul_assign(&domain.omega_inv());
+            assert_eq!(one, Scalar::one());
+        }
+    }
+
+    #[test]
+    fn test_domain_fft() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for log_size in 0..8 {
+            let domain = EvaluationDomain::new(1 << log_size).unwrap();
+            let coeffs: Vec<Scalar> = (0..domain.size())
+                .map(|_| Scalar::random(&mut rng))
+                .collect();
+
+            let mut evals = coeffs.clone();
+            domain.fft(&mut evals);
+            for (w, e) in domain.elements().iter().zip(&evals) {
+                assert_eq!(evaluate(&coeffs, w), *e);
+            }
+            domain.ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+
+            let mut evals = coeffs.clone();
+            domain.coset_fft(&mut evals);
+            for (w, e) in domain.elements().iter().zip(&evals) {
+                let mut x = *w;
+                x.mul_assign(&Scalar::multiplicative_generator());
+                assert_eq!(evaluate(&coeffs, &x), *e);
+            }
+            domain.coset_ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_domain_fft_len() {
+        let domain = EvaluationDomain::new(4).unwrap();
+        domain.fft(&mut [Scalar::one(); 3]);
+    }
+
+    #[test]
+    fn test_domain_vanishing_polynomial() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let domain = EvaluationDomain::new(16).unwrap();
+        for w in domain.elements() {
+            assert!(domain.evaluate_vanishing_polynomial(&w).is_zero());
+        }
+
+        // On the coset, dividing evaluations matches dividing values at each point.
+        let coeffs: Vec<Scalar> = (0..domain.size())
+            .map(|_| Scalar::random(&mut rng))
+            .collect();
+        let mut evals = coeffs.clone();
+        domain.coset_fft(&mut evals);
+        domain.divide_by_vanishing_poly_on_coset(&mut evals);
+        for (w, e) in domain.elements().iter().zip(&evals) {
+            let mut x = *w;
+            x.mul_assign(&Scalar::multiplicative_generator());
+            let mut expected = evaluate(&coeffs, &x);
+            expected.mul_assign(&domain.evaluate_vanishing_polynomial(&x).inverse().unwrap());
+            assert_eq!(*e, expected);
+        }
+
+        let tau = Scalar::random(&mut rng);
+        let mut expected = tau.pow([16u64]);
+        expected.sub_assign(&Scalar::one());
+        assert_eq!(domain.evaluate_vanishing_polynomial(&tau), expected);
+    }
+
+    #[test]
+    fn test_domain_lagrange_coefficients() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let domain = EvaluationDomain::new(32).unwrap();
+        let coeffs: Vec<Scalar> = (0..domain.size())
+            .map(|_| Scalar::random(&mut rng))
+            .collect();
+        let mut evals = coeffs.clone();
+        domain.fft(&mut evals);
+
+        let tau = Scalar::random(&mut rng);
+        let lagrange = domain.evaluate_all_lagrange_coefficients(&tau);
+        let mut res = Scalar::zero();
+        for (l, e) in lagrange.iter().zip(&evals) {
+            let mut t = *l;
+            t.mul_assign(e);
+            res.add_assign(&t);
+        }
+        assert_eq!(res, evaluate(&coeffs, &tau));
+
+        let w = domain.elements()[3];
+        let lagrange = domain.evaluate_all_lagrange_coefficients(&w);
+        for (i, l) in lagrange.iter().enumerate() {
+            assert_eq!(
+                *l,
+                if i == 3 {
+                    Scalar::one()
+                } else {
+                    Scalar::zero()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_domain_fft_group() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for log_size in 0..5 {
+            let domain = EvaluationDomain::new(1 << log_size).unwrap();
+            let coeffs: Vec<Scalar> = (0..domain.size())
+                .map(|_| Scalar::random(&mut rng))
+                .collect();
+            let mut evals = coeffs.clone();
+            domain.fft(&mut evals);
+
+            // The FFT commutes with multiplying the generators by the scalars.
+            let mut g1: Vec<G1Projective> =
+                coeffs.iter().map(|c| G1Projective::one() * c).collect();
+            domain.fft_group(&mut g1);
+            for (p, e) in g1.iter().zip(&evals) {
+                assert_eq!(*p, G1Projective::one() * e);
+            }
+            domain.ifft_group(&mut g1);
+            for (p, c) in g1.iter().zip(&coeffs) {
+                assert_eq!(*p, G1Projective::one() * c);
+            }
+
+            let mut g2: Vec<G2Projective> =
+                coeffs.iter().map(|c| G2Projective::one() * c).collect();
+            domain.fft_group(&mut g2);
+            for (p, e) in g2.iter().zip(&evals) {
+                assert_eq!(*p, G2Projective::one() * e);
+            }
+            domain.ifft_group(&mut g2);
+            for (p, c) in g2.iter().zip(&coeffs) {
+                assert_eq!(*p, G2Projective::one() * c);
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_domain_par_fft() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for log_size in &[0, 3, 10, 12, 14] {
+            let domain = EvaluationDomain::new(1 << log_size).unwrap();
+            let coeffs: Vec<Scalar> = (0..domain.size())
+                .map(|_| Scalar::random(&mut rng))
+                .collect();
+
+            let mut expected = coeffs.clone();
+            domain.fft(&mut expected);
+            let mut evals = coeffs.clone();
+            domain.par_fft(&mut evals);
+            assert_eq!(evals, expected);
+            domain.par_ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+
+            let mut expected = coeffs.clone();
+            domain.coset_fft(&mut expected);
+            let mut evals = coeffs.clone();
+            domain.par_coset_fft(&mut evals);
+            assert_eq!(evals, expected);
+            domain.par_coset_ifft(&mut evals);
+            assert_eq!(evals, coeffs);
+        }
+
+        // Large enough to split the butterflies into tasks.
+        let domain = EvaluationDomain::new(1 << 11).unwrap();
+        let points: Vec<G1Projective> = (0..domain.size())
+            .map(|_| G1Projective::random(&mut rng))
+            .collect();
+        let mut expected = points.clone();
+        domain.fft_group(&mut expected);
+        let mut evals = points.clone();
+        domain.par_fft_group(&mut evals);
+        assert_eq!(evals, expected);
+        domain.par_ifft_group(&mut evals);
+        assert_eq!(evals, points);
+    }
+}
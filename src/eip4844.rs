@@ -0,0 +1,807 @@
+//! KZG commitments to blobs, as specified for EIP-4844 in the Deneb consensus specs.
+//!
+//! Blobs are polynomials in evaluation form over the roots of unity of order
+//! `FIELD_ELEMENTS_PER_BLOB`, in bit-reversed order. Field elements are encoded in
+//! big-endian order and points in the compressed encoding.
+
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use fff::{Field, PrimeField, PrimeFieldRepr};
+use groupy::{CurveAffine, CurveProjective};
+use sha2::{Digest, Sha256};
+
+use crate::domain::bit_reverse;
+use crate::hexstr;
+use crate::{
+    multi_pairing, BatchInvert, Error, EvaluationDomain, Fp12, G1Affine, G1Projective, G2Affine,
+    Scalar, ScalarRepr,
+};
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// The size of an encoded field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// The size of a blob.
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// The size of a compressed commitment.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+/// The size of a compressed proof.
+pub const BYTES_PER_PROOF: usize = 48;
+
+/// Domain separator of the challenge for a single blob.
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+/// Domain separator of the challenge for batch verification.
+const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8] = b"RCKZGBATCH___V1_";
+
+/// The compressed encoding of the point at infinity.
+const G1_POINT_AT_INFINITY: [u8; BYTES_PER_COMMITMENT] = {
+    let mut bytes = [0; BYTES_PER_COMMITMENT];
+    bytes[0] = 0xc0;
+    bytes
+};
+
+/// A blob of `FIELD_ELEMENTS_PER_BLOB` encoded field elements.
+pub type Blob = [u8; BYTES_PER_BLOB];
+
+/// A trusted setup for blob commitments, in Lagrange form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KzgSettings {
+    /// The Lagrange basis `[L_i(tau)]_1`, in bit-reversed order.
+    g1_lagrange_brp: Vec<G1Affine>,
+    /// The powers `[tau^i]_2`.
+    g2_monomial: Vec<G2Affine>,
+    /// The roots of unity, in bit-reversed order.
+    roots_of_unity_brp: Vec<Scalar>,
+}
+
+impl KzgSettings {
+    /// Creates the settings from the Lagrange basis in $\mathbb{G}_1$ in natural
+    /// order, and at least two powers of `tau` in $\mathbb{G}_2$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not `FIELD_ELEMENTS_PER_BLOB` points in $\mathbb{G}_1$ or
+    /// fewer than two in $\mathbb{G}_2$.
+    pub fn new(mut g1_lagrange: Vec<G1Affine>, g2_monomial: Vec<G2Affine>) -> Self {
+        assert_eq!(
+            g1_lagrange.len(),
+            FIELD_ELEMENTS_PER_BLOB,
+            "the setup must have one point per field element"
+        );
+        assert!(g2_monomial.len() >= 2, "the setup must contain [tau]_2");
+
+        bit_reverse(&mut g1_lagrange);
+        let mut roots_of_unity_brp = EvaluationDomain::new(FIELD_ELEMENTS_PER_BLOB)
+            .expect("the blob size is a valid domain size")
+            .elements();
+        bit_reverse(&mut roots_of_unity_brp);
+
+        KzgSettings {
+            g1_lagrange_brp: g1_lagrange,
+            g2_monomial,
+            roots_of_unity_brp,
+        }
+    }
+
+    /// Reads a trusted setup in the text format used by the reference
+    /// implementation: the number of points in $\mathbb{G}_1$ and in
+    /// $\mathbb{G}_2$, the Lagrange basis in $\mathbb{G}_1$ and the powers of `tau` in
+    /// $\mathbb{G}_2$ as hexadecimal compressed points. A trailing monomial basis in
+    /// $\mathbb{G}_1$ is ignored.
+    pub fn load_trusted_setup<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut tokens = Vec::new();
+        for line in reader.lines() {
+            tokens.extend(line?.split_whitespace().map(str::to_owned));
+        }
+        let mut tokens = tokens.into_iter();
+        let mut next = || {
+            tokens
+                .next()
+                .ok_or_else(|| invalid_setup("truncated trusted setup"))
+        };
+
+        let num_g1: usize = next()?
+            .parse()
+            .map_err(|_| invalid_setup("invalid number of points"))?;
+        let num_g2: usize = next()?
+            .parse()
+            .map_err(|_| invalid_setup("invalid number of points"))?;
+        if num_g1 != FIELD_ELEMENTS_PER_BLOB || num_g2 < 2 {
+            return Err(invalid_setup("unexpected number of points"));
+        }
+
+        let mut g1_lagrange = Vec::with_capacity(num_g1);
+        for _ in 0..num_g1 {
+            let mut bytes = [0; 48];
+            decode_hex(&next()?, &mut bytes)?;
+            g1_lagrange.push(G1Affine::from_compressed(&bytes)?);
+        }
+        let mut g2_monomial = Vec::with_capacity(num_g2);
+        for _ in 0..num_g2 {
+            let mut bytes = [0; 96];
+            decode_hex(&next()?, &mut bytes)?;
+            g2_monomial.push(G2Affine::from_compressed(&bytes)?);
+        }
+
+        Ok(KzgSettings::new(g1_lagrange, g2_monomial))
+    }
+
+    /// Reads a trusted setup from a file, see `load_trusted_setup`.
+    pub fn load_trusted_setup_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::load_trusted_setup(io::BufReader::new(file))
+    }
+
+    /// Computes the commitment to a blob.
+    pub fn blob_to_kzg_commitment(&self, blob: &Blob) -> Result<[u8; BYTES_PER_COMMITMENT], Error> {
+        let polynomial = blob_to_polynomial(blob)?;
+        Ok(self.g1_lincomb(&polynomial).to_compressed())
+    }
+
+    /// Computes the proof that the blob takes a value `y` at `z`, returning the proof
+    /// and `y`.
+    pub fn compute_kzg_proof(
+        &self,
+        blob: &Blob,
+        z_bytes: &[u8; BYTES_PER_FIELD_ELEMENT],
+    ) -> Result<([u8; BYTES_PER_PROOF], [u8; BYTES_PER_FIELD_ELEMENT]), Error> {
+        let polynomial = blob_to_polynomial(blob)?;
+        let z = bytes_to_bls_field(z_bytes)?;
+        let (proof, y) = self.compute_kzg_proof_impl(&polynomial, &z);
+
+        Ok((proof.to_compressed(), y.to_bytes_be()))
+    }
+
+    /// Computes the proof for a blob at the Fiat-Shamir challenge derived from the
+    /// blob and its commitment.
+    pub fn compute_blob_kzg_proof(
+        &self,
+        blob: &Blob,
+        commitment_bytes: &[u8; BYTES_PER_COMMITMENT],
+    ) -> Result<[u8; BYTES_PER_PROOF], Error> {
+        bytes_to_kzg_commitment(commitment_bytes)?;
+        let polynomial = blob_to_polynomial(blob)?;
+        let challenge = compute_challenge(blob, commitment_bytes);
+        let (proof, _) = self.compute_kzg_proof_impl(&polynomial, &challenge);
+
+        Ok(proof.to_compressed())
+    }
+
+    /// Verifies that the polynomial committed to takes the value `y` at `z`.
+    ///
+    /// Fails with `VerifyFailed` if the proof is wrong, and with other errors if an
+    /// input is not canonical.
+    pub fn verify_kzg_proof(
+        &self,
+        commitment_bytes: &[u8; BYTES_PER_COMMITMENT],
+        z_bytes: &[u8; BYTES_PER_FIELD_ELEMENT],
+        y_bytes: &[u8; BYTES_PER_FIELD_ELEMENT],
+        proof_bytes: &[u8; BYTES_PER_PROOF],
+    ) -> Result<(), Error> {
+        let commitment = bytes_to_kzg_commitment(commitment_bytes)?;
+        let z = bytes_to_bls_field(z_bytes)?;
+        let y = bytes_to_bls_field(y_bytes)?;
+        let proof = bytes_to_kzg_proof(proof_bytes)?;
+
+        self.verify_kzg_proof_impl(&commitment, &z, &y, &proof)
+    }
+
+    /// Verifies a proof computed by `compute_blob_kzg_proof`.
+    pub fn verify_blob_kzg_proof(
+        &self,
+        blob: &Blob,
+        commitment_bytes: &[u8; BYTES_PER_COMMITMENT],
+        proof_bytes: &[u8; BYTES_PER_PROOF],
+    ) -> Result<(), Error> {
+        let commitment = bytes_to_kzg_commitment(commitment_bytes)?;
+        let polynomial = blob_to_polynomial(blob)?;
+        let challenge = compute_challenge(blob, commitment_bytes);
+        let y = self.evaluate_polynomial_in_evaluation_form(&polynomial, &challenge);
+        let proof = bytes_to_kzg_proof(proof_bytes)?;
+
+        self.verify_kzg_proof_impl(&commitment, &challenge, &y, &proof)
+    }
+
+    /// Verifies proofs computed by `compute_blob_kzg_proof` for several blobs at once,
+    /// with a random linear combination of the pairing checks.
+    ///
+    /// Fails with `LengthMismatch` if the slices have different lengths.
+    pub fn verify_blob_kzg_proof_batch(
+        &self,
+        blobs: &[Blob],
+        commitments_bytes: &[[u8; BYTES_PER_COMMITMENT]],
+        proofs_bytes: &[[u8; BYTES_PER_PROOF]],
+    ) -> Result<(), Error> {
+        if blobs.len() != commitments_bytes.len() || blobs.len() != proofs_bytes.len() {
+            return Err(Error::LengthMismatch);
+        }
+
+        let mut commitments = Vec::with_capacity(blobs.len());
+        let mut challenges = Vec::with_capacity(blobs.len());
+        let mut ys = Vec::with_capacity(blobs.len());
+        let mut proofs = Vec::with_capacity(blobs.len());
+        for ((blob, commitment_bytes), proof_bytes) in
+            blobs.iter().zip(commitments_bytes).zip(proofs_bytes)
+        {
+            commitments.push(bytes_to_kzg_commitment(commitment_bytes)?);
+            let polynomial = blob_to_polynomial(blob)?;
+            let challenge = compute_challenge(blob, commitment_bytes);
+            ys.push(self.evaluate_polynomial_in_evaluation_form(&polynomial, &challenge));
+            challenges.push(challenge);
+            proofs.push(bytes_to_kzg_proof(proof_bytes)?);
+        }
+
+        self.verify_kzg_proof_batch(&commitments, &challenges, &ys, &proofs)
+    }
+
+    fn g1_lincomb(&self, scalars: &[Scalar]) -> G1Affine {
+        G1Projective::multi_exp_vartime(&self.g1_lagrange_brp, scalars).into_affine()
+    }
+
+    /// Evaluates the polynomial given by its values at the roots of unity, in
+    /// bit-reversed order, with the barycentric formula.
+    fn evaluate_polynomial_in_evaluation_form(&self, polynomial: &[Scalar], z: &Scalar) -> Scalar {
+        if let Some(i) = self.roots_of_unity_brp.iter().position(|w| w == z) {
+            return polynomial[i];
+        }
+
+        // sum_i p_i w_i / (z - w_i) * (z^n - 1) / n
+        let mut denoms: Vec<Scalar> = self.roots_of_unity_brp.iter().map(|w| z - w).collect();
+        Scalar::batch_invert(&mut denoms);
+        let sum = polynomial
+            .iter()
+            .zip(&self.roots_of_unity_brp)
+            .zip(&denoms)
+            .fold(Scalar::zero(), |acc, ((p, w), d)| acc + p * w * d);
+
+        let width = FIELD_ELEMENTS_PER_BLOB as u64;
+        let mut factor = z.pow([width]);
+        factor.sub_assign(&Scalar::one());
+        factor.mul_assign(&Scalar::from(width).inverse().unwrap());
+
+        sum * factor
+    }
+
+    /// Computes the proof at `z` of the polynomial in evaluation form, and its value
+    /// at `z`.
+    fn compute_kzg_proof_impl(&self, polynomial: &[Scalar], z: &Scalar) -> (G1Affine, Scalar) {
+        let y = self.evaluate_polynomial_in_evaluation_form(polynomial, z);
+
+        // The quotient (p - y) / (X - z) in evaluation form.
+        let mut denoms: Vec<Scalar> = self.roots_of_unity_brp.iter().map(|w| w - z).collect();
+        Scalar::batch_invert(&mut denoms);
+        let mut quotient: Vec<Scalar> = polynomial
+            .iter()
+            .zip(&denoms)
+            .map(|(p, d)| (p - y) * d)
+            .collect();
+
+        // At z = w_m the division is undefined, and the quotient there is
+        // sum_{i != m} (p_i - y) w_i / (z (z - w_i)) = -sum_{i != m} q_i w_i / z.
+        if let Some(m) = denoms.iter().position(|d| d.is_zero()) {
+            let sum = quotient
+                .iter()
+                .zip(&self.roots_of_unity_brp)
+                .fold(Scalar::zero(), |acc, (q, w)| acc + q * w);
+            quotient[m] = -sum * z.inverse().unwrap();
+        }
+
+        (self.g1_lincomb(&quotient), y)
+    }
+
+    /// Checks `e(C - [y]_1, -[1]_2) e(proof, [tau]_2 - [z]_2) = 1`.
+    fn verify_kzg_proof_impl(
+        &self,
+        commitment: &G1Affine,
+        z: &Scalar,
+        y: &Scalar,
+        proof: &G1Affine,
+    ) -> Result<(), Error> {
+        let x_minus_z = (self.g2_monomial[1].into_projective() - G2Affine::one() * z).into_affine();
+        let p_minus_y = (commitment.into_projective() - G1Affine::one() * y).into_affine();
+
+        pairing_check(&[(&p_minus_y, &neg_g2()), (proof, &x_minus_z)])
+    }
+
+    /// Checks the openings at once with the powers of a challenge derived from all
+    /// of them.
+    fn verify_kzg_proof_batch(
+        &self,
+        commitments: &[G1Affine],
+        zs: &[Scalar],
+        ys: &[Scalar],
+        proofs: &[G1Affine],
+    ) -> Result<(), Error> {
+        let n = commitments.len();
+
+        let mut hasher = Sha256::new();
+        hasher.update(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+        hasher.update((FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+        hasher.update((n as u64).to_be_bytes());
+        for i in 0..n {
+            hasher.update(commitments[i].to_compressed());
+            hasher.update(zs[i].to_bytes_be());
+            hasher.update(ys[i].to_bytes_be());
+            hasher.update(proofs[i].to_compressed());
+        }
+        let r = hash_to_bls_field(&hasher.finalize().into());
+        let r_powers: Vec<Scalar> = core::iter::successors(Some(Scalar::one()), |p| Some(p * r))
+            .take(n)
+            .collect();
+
+        let proof_lincomb = G1Projective::multi_exp_vartime(proofs, &r_powers).into_affine();
+        let z_r_powers: Vec<Scalar> = zs.iter().zip(&r_powers).map(|(z, r)| z * r).collect();
+        let proof_z_lincomb = G1Projective::multi_exp_vartime(proofs, &z_r_powers);
+
+        let c_minus_ys: Vec<G1Affine> = commitments
+            .iter()
+            .zip(ys)
+            .map(|(c, y)| (c.into_projective() - G1Affine::one() * y).into_affine())
+            .collect();
+        let c_minus_y_lincomb = G1Projective::multi_exp_vartime(&c_minus_ys, &r_powers);
+
+        let rhs = (c_minus_y_lincomb + proof_z_lincomb).into_affine();
+        let mut neg_tau = self.g2_monomial[1];
+        neg_tau.negate();
+
+        pairing_check(&[(&proof_lincomb, &neg_tau), (&rhs, &G2Affine::one())])
+    }
+}
+
+fn invalid_setup(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn decode_hex(hex: &str, out: &mut [u8]) -> io::Result<()> {
+    hexstr::decode(hex, out).ok_or_else(|| invalid_setup("invalid hexadecimal point"))
+}
+
+fn neg_g2() -> G2Affine {
+    let mut g = G2Affine::one();
+    g.negate();
+    g
+}
+
+fn pairing_check(pairs: &[(&G1Affine, &G2Affine)]) -> Result<(), Error> {
+    if multi_pairing(pairs) == Fp12::one() {
+        Ok(())
+    } else {
+        Err(Error::VerifyFailed)
+    }
+}
+
+/// Decodes a canonical big-endian field element.
+fn bytes_to_bls_field(bytes: &[u8; BYTES_PER_FIELD_ELEMENT]) -> Result<Scalar, Error> {
+    Scalar::from_bytes_be(bytes).ok_or(Error::InvalidScalar)
+}
+
+/// Reduces a big-endian SHA-256 digest modulo the group order.
+fn hash_to_bls_field(digest: &[u8; 32]) -> Scalar {
+    let mut repr = ScalarRepr::default();
+    repr.read_be(&digest[..]).unwrap();
+    let modulus = Scalar::char();
+    while repr >= modulus {
+        repr.sub_noborrow(&modulus);
+    }
+
+    Scalar::from_repr(repr).unwrap()
+}
+
+/// Decodes a commitment or proof, which is either the point at infinity or a
+/// compressed point of the prime order subgroup.
+fn validate_kzg_g1(bytes: &[u8; 48]) -> Result<G1Affine, Error> {
+    if *bytes == G1_POINT_AT_INFINITY {
+        return Ok(G1Affine::zero());
+    }
+
+    // The all zero string is not a valid compressed point.
+    if bytes.iter().all(|&b| b == 0) {
+        return Err(Error::BadEncoding);
+    }
+    let point = G1Affine::from_compressed(bytes)?;
+    if point.is_zero() {
+        return Err(Error::BadEncoding);
+    }
+
+    Ok(point)
+}
+
+fn bytes_to_kzg_commitment(bytes: &[u8; BYTES_PER_COMMITMENT]) -> Result<G1Affine, Error> {
+    validate_kzg_g1(bytes)
+}
+
+fn bytes_to_kzg_proof(bytes: &[u8; BYTES_PER_PROOF]) -> Result<G1Affine, Error> {
+    validate_kzg_g1(bytes)
+}
+
+fn blob_to_polynomial(blob: &Blob) -> Result<Vec<Scalar>, Error> {
+    blob.chunks(BYTES_PER_FIELD_ELEMENT)
+        .map(|chunk| {
+            let mut bytes = [0; BYTES_PER_FIELD_ELEMENT];
+            bytes.copy_from_slice(chunk);
+            bytes_to_bls_field(&bytes)
+        })
+        .collect()
+}
+
+/// Derives the evaluation point for a blob from the blob and its commitment.
+fn compute_challenge(blob: &Blob, commitment_bytes: &[u8; BYTES_PER_COMMITMENT]) -> Scalar {
+    let mut hasher = Sha256::new();
+    hasher.update(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    hasher.update((FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    hasher.update(&blob[..]);
+    hasher.update(commitment_bytes);
+
+    hash_to_bls_field(&hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::TryInto;
+
+    // Vectors computed with the reference implementation, c-kzg 1.0.3.
+    const VECTORS: [(&str, &str, &str, &str, &str, &str, &str); 3] = [
+        (
+            "92940ba110cff3ceae5ff319e2c970a6e189f04a6ad4bbb01264dcc5a68c448190017d7f7effe8e17c604d65ff66587c",
+            "84ca93f08c2a1114b88a9b18f4eaef3bec990a5c16153d15f74346b1fcb46b22894249bb7338a49a0442c9cda121125a",
+            "001d367d6bbbbc97e1928af982bd5a414a8e9d18be54b745112b24e8c52ef49f",
+            "85b54ba802acd32bd7d068f30ba96d40db1944419d641e21172a7feaca6cfac894f67b69dda6c3262ab5aaaa087aec19",
+            "4e21a348ae3bac4a1069c990c6e6e1a781b279c14563321118a0f475d18eac82",
+            "af88e22d76f1531782d4be47244fd1e784fa7dc3b8d6564a4fdf3eef094fef7951dff8dc2073609aaf3da562572b0e61",
+            "005570f5a1810b7af78caf4bc70a660f0df51e42baf91d4de5b2328de0e83dfc",
+        ),
+        (
+            "926c08ef60132111eb5e171e91544039752caeea42df951ceabe381dfb5dda88419f738c6eee831b2b2d74c50edc64c2",
+            "a7777257fe88e4a43f4ef435954548b1d04c47ae6563724e6acfdc11c5361eaf7bff502092070833d8e4c2749a478322",
+            "00f1a3e660c61a7603e3d9ac615ff1b281316ab5fb23f7aa734660519efacc46",
+            "8b49e49ad31675054c30d804afa7f06b19617032367d112729e1efd285d256447894051c94ace424daef5b839916a0e0",
+            "14774d99d8f5f427c087e4925096338b786b7fd4b873d6ad50b9035a23697cfb",
+            "94455d5d79d1fe493e569976a24235c3e2e5fb21937ceb5d385fdfc30f5be47d4f09187ff134f029b736ca5283577274",
+            "00bc48750debb8535093b3deaf88ac7f4cff87425576a58de2bac754acdb4616",
+        ),
+        (
+            "8c5021a4994f893227106299f8026b6f7ac78060301de8f69f31debc937e33402620e303a2333406f2a1272b54697a4d",
+            "8ac4b4b5ce4c04b56be1cc7b11b6b70734095a3f0bc89f98cd1f463ed0b8651a8296aa30d187c265b8d677f3fd8cd98b",
+            "00ac978dd6bb48a865916cc2c97f9c8d94453a4c70c147581edb8b3390bea5e1",
+            "92cf1f8db888bbb764f30b709846e5925da62c663cc3e1979d76ae0821e90a7d2fd7df19078fab9ec8a68a0702c10636",
+            "5ff83d684444152589029315cbd3d5b0fd9c35c3694c1f2415ca957ada5323e9",
+            "a1dce6ba41c32a39f2c3017bdaba366c7ec0fa808227d637474bc59dc2ba36ba022960d1ff1a04679ba6524d5a3ece29",
+            "00e50aea7e52f17dc807488bbd631e368da3a3ad3d5a31ad4b0f049581366c4d",
+        ),
+    ];
+
+    const TEST_DATA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/eip4844");
+
+    fn settings() -> KzgSettings {
+        KzgSettings::load_trusted_setup_file(Path::new(TEST_DATA).join("trusted_setup_4096.txt"))
+            .unwrap()
+    }
+
+    fn hex<const N: usize>(s: &str) -> [u8; N] {
+        let mut out = [0; N];
+        decode_hex(s, &mut out).unwrap();
+        out
+    }
+
+    /// Hashes `tag` and `i` to a canonical field element.
+    fn element(tag: &[u8], i: u32) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+        let mut hasher = Sha256::new();
+        hasher.update(tag);
+        hasher.update(i.to_be_bytes());
+        let mut bytes: [u8; 32] = hasher.finalize().into();
+        bytes[0] = 0;
+        bytes
+    }
+
+    fn test_blob(seed: u32) -> Box<Blob> {
+        let mut blob = Box::new([0; BYTES_PER_BLOB]);
+        for (i, chunk) in blob.chunks_mut(BYTES_PER_FIELD_ELEMENT).enumerate() {
+            chunk.copy_from_slice(&element(&seed.to_be_bytes(), i as u32));
+        }
+        blob
+    }
+
+    #[test]
+    fn test_load_trusted_setup() {
+        let settings = settings();
+        assert_eq!(settings.g1_lagrange_brp.len(), FIELD_ELEMENTS_PER_BLOB);
+        assert_eq!(settings.g2_monomial.len(), 65);
+        assert_eq!(settings.g2_monomial[0], G2Affine::one());
+
+        // The Lagrange basis sums to [1]_1.
+        let sum = settings
+            .g1_lagrange_brp
+            .iter()
+            .fold(G1Projective::zero(), |mut acc, p| {
+                acc.add_assign_mixed(p);
+                acc
+            });
+        assert_eq!(sum.into_affine(), G1Affine::one());
+
+        assert!(KzgSettings::load_trusted_setup(&b"4096\n65\n"[..]).is_err());
+        assert!(KzgSettings::load_trusted_setup(&b"4095\n65\n"[..]).is_err());
+        assert!(KzgSettings::load_trusted_setup(&b""[..]).is_err());
+    }
+
+    #[test]
+    fn test_reference_vectors() {
+        let settings = settings();
+
+        // From the tests of the reference implementation.
+        let mut blob = Box::new([0; BYTES_PER_BLOB]);
+        blob[..32].copy_from_slice(&hex::<32>(
+            "14629a3a39f7b854e6aa49aa2edb450267eac2c14bb2d4f97a0b81a3f57055ad",
+        ));
+        assert_eq!(
+            settings.blob_to_kzg_commitment(&blob),
+            Ok(hex("91a5e1c143820d2e7bec38a5404c5145807cb88c0abbbecbcb4bccc83a4b417326e337574cff43303f8a6648ecbee7ac"))
+        );
+        blob[..32].copy_from_slice(&hex::<32>(
+            "69386e69dbae0357b399b8d645a57a3062dfbe00bd8e97170b9bdd6bc6168a13",
+        ));
+        let z = hex("03ea4fb841b4f9e01aa917c5e40dbd67efb4b8d4d9052069595f0647feba320d");
+        let (proof, _) = settings.compute_kzg_proof(&blob, &z).unwrap();
+        assert_eq!(
+            proof,
+            hex::<48>("b21f8f9b85e52fd9c4a6d4fb4e9a27ebdc5a09c3f5ca17f6bcd85c26f04953b0e6925607aaebed1087e5cc2fe4b2b356")
+        );
+
+        let mut one = [0; 32];
+        one[31] = 1;
+        let mut blobs = Vec::new();
+        let mut commitments = Vec::new();
+        let mut proofs = Vec::new();
+        for (seed, v) in VECTORS.iter().enumerate() {
+            let blob = test_blob(seed as u32);
+            let commitment = settings.blob_to_kzg_commitment(&blob).unwrap();
+            assert_eq!(commitment, hex(v.0));
+            let proof = settings.compute_blob_kzg_proof(&blob, &commitment).unwrap();
+            assert_eq!(proof, hex(v.1));
+            assert_eq!(
+                settings.verify_blob_kzg_proof(&blob, &commitment, &proof),
+                Ok(())
+            );
+
+            let z = hex(v.2);
+            assert_eq!(z, element(b"z", seed as u32));
+            let (proof, y) = settings.compute_kzg_proof(&blob, &z).unwrap();
+            assert_eq!((proof, y), (hex(v.3), hex(v.4)));
+            assert_eq!(
+                settings.verify_kzg_proof(&commitment, &z, &y, &proof),
+                Ok(())
+            );
+
+            // Evaluation at a root of unity.
+            let (proof, y) = settings.compute_kzg_proof(&blob, &one).unwrap();
+            assert_eq!((proof, y), (hex(v.5), hex(v.6)));
+            assert_eq!(y, element(&(seed as u32).to_be_bytes(), 0));
+            assert_eq!(
+                settings.verify_kzg_proof(&commitment, &one, &y, &proof),
+                Ok(())
+            );
+
+            blobs.push(*blob);
+            commitments.push(commitment);
+            proofs.push(settings.compute_blob_kzg_proof(&blob, &commitment).unwrap());
+        }
+
+        assert_eq!(
+            settings.verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs),
+            Ok(())
+        );
+        assert_eq!(settings.verify_blob_kzg_proof_batch(&[], &[], &[]), Ok(()));
+        assert_eq!(
+            settings.verify_blob_kzg_proof_batch(&blobs, &commitments[1..], &proofs),
+            Err(Error::LengthMismatch)
+        );
+        proofs.swap(0, 1);
+        assert_eq!(
+            settings.verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let settings = settings();
+
+        // The zero blob commits to the point at infinity, and so do its proofs.
+        let zero = Box::new([0; BYTES_PER_BLOB]);
+        let commitment = settings.blob_to_kzg_commitment(&zero).unwrap();
+        assert_eq!(commitment, G1_POINT_AT_INFINITY);
+        let proof = settings.compute_blob_kzg_proof(&zero, &commitment).unwrap();
+        assert_eq!(proof, G1_POINT_AT_INFINITY);
+        assert_eq!(
+            settings.verify_blob_kzg_proof(&zero, &commitment, &proof),
+            Ok(())
+        );
+
+        let blob = test_blob(0);
+        let commitment = settings.blob_to_kzg_commitment(&blob).unwrap();
+        let proof = settings.compute_blob_kzg_proof(&blob, &commitment).unwrap();
+        assert_eq!(
+            settings.verify_blob_kzg_proof(&blob, &commitment, &G1_POINT_AT_INFINITY),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            settings.verify_blob_kzg_proof(&zero, &commitment, &proof),
+            Err(Error::VerifyFailed)
+        );
+
+        // Non-canonical field elements.
+        let modulus = hex::<32>("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+        let mut bad_blob = blob.clone();
+        bad_blob[32..64].copy_from_slice(&modulus);
+        assert_eq!(
+            settings.blob_to_kzg_commitment(&bad_blob),
+            Err(Error::InvalidScalar)
+        );
+        assert_eq!(
+            settings.compute_kzg_proof(&blob, &modulus),
+            Err(Error::InvalidScalar)
+        );
+        assert_eq!(
+            settings.verify_kzg_proof(&commitment, &[0; 32], &modulus, &proof),
+            Err(Error::InvalidScalar)
+        );
+
+        // Invalid points.
+        assert_eq!(
+            settings.verify_blob_kzg_proof(&blob, &[0; 48], &proof),
+            Err(Error::BadEncoding)
+        );
+        let mut not_on_curve = commitment;
+        not_on_curve[47] ^= 1;
+        assert!(settings
+            .verify_blob_kzg_proof(&blob, &commitment, &not_on_curve)
+            .is_err());
+        assert!(settings
+            .compute_blob_kzg_proof(&blob, &not_on_curve)
+            .is_err());
+    }
+
+    /// Decodes a `0x` prefixed hexadecimal input, returning `None` if it is malformed
+    /// or has the wrong length, which the spec tests expect to be rejected.
+    fn spec_bytes<const N: usize>(value: &serde_yaml::Value) -> Option<[u8; N]> {
+        let s = value.as_str().unwrap();
+        let bytes = ::hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok()?;
+        bytes.try_into().ok()
+    }
+
+    fn spec_blob(value: &serde_yaml::Value) -> Option<Box<Blob>> {
+        let s = value.as_str().unwrap();
+        let bytes = ::hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok()?;
+        bytes.into_boxed_slice().try_into().ok()
+    }
+
+    fn spec_list<T>(
+        value: &serde_yaml::Value,
+        decode: impl Fn(&serde_yaml::Value) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        value.as_sequence().unwrap().iter().map(decode).collect()
+    }
+
+    /// Maps a verification result to the expected output of a spec test: true or
+    /// false for a valid or invalid proof, null for malformed inputs.
+    fn spec_verdict(result: Option<Result<(), Error>>) -> Option<bool> {
+        match result {
+            Some(Ok(())) => Some(true),
+            Some(Err(Error::VerifyFailed)) => Some(false),
+            _ => None,
+        }
+    }
+
+    fn run_spec_case(settings: &KzgSettings, handler: &str, path: &Path) {
+        let data: serde_yaml::Value =
+            serde_yaml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let input = &data["input"];
+        let output = &data["output"];
+
+        match handler {
+            "blob_to_kzg_commitment" => {
+                let result = spec_blob(&input["blob"])
+                    .and_then(|blob| settings.blob_to_kzg_commitment(&blob).ok());
+                let expected = output.as_str().map(|_| spec_bytes::<48>(output).unwrap());
+                assert_eq!(result, expected, "{}", path.display());
+            }
+            "compute_kzg_proof" => {
+                let result = spec_blob(&input["blob"])
+                    .zip(spec_bytes(&input["z"]))
+                    .and_then(|(blob, z)| settings.compute_kzg_proof(&blob, &z).ok());
+                let expected = output.as_sequence().map(|pair| {
+                    (
+                        spec_bytes::<48>(&pair[0]).unwrap(),
+                        spec_bytes::<32>(&pair[1]).unwrap(),
+                    )
+                });
+                assert_eq!(result, expected, "{}", path.display());
+            }
+            "compute_blob_kzg_proof" => {
+                let result = spec_blob(&input["blob"])
+                    .zip(spec_bytes(&input["commitment"]))
+                    .and_then(|(blob, commitment)| {
+                        settings.compute_blob_kzg_proof(&blob, &commitment).ok()
+                    });
+                let expected = output.as_str().map(|_| spec_bytes::<48>(output).unwrap());
+                assert_eq!(result, expected, "{}", path.display());
+            }
+            "verify_kzg_proof" => {
+                let result = (|| {
+                    let commitment = spec_bytes(&input["commitment"])?;
+                    let z = spec_bytes(&input["z"])?;
+                    let y = spec_bytes(&input["y"])?;
+                    let proof = spec_bytes(&input["proof"])?;
+                    Some(settings.verify_kzg_proof(&commitment, &z, &y, &proof))
+                })();
+                assert_eq!(spec_verdict(result), output.as_bool(), "{}", path.display());
+            }
+            "verify_blob_kzg_proof" => {
+                let result = (|| {
+                    let blob = spec_blob(&input["blob"])?;
+                    let commitment = spec_bytes(&input["commitment"])?;
+                    let proof = spec_bytes(&input["proof"])?;
+                    Some(settings.verify_blob_kzg_proof(&blob, &commitment, &proof))
+                })();
+                assert_eq!(spec_verdict(result), output.as_bool(), "{}", path.display());
+            }
+            "verify_blob_kzg_proof_batch" => {
+                let result = (|| {
+                    let blobs = spec_list(&input["blobs"], spec_blob)?;
+                    let blobs: Vec<Blob> = blobs.into_iter().map(|blob| *blob).collect();
+                    let commitments = spec_list(&input["commitments"], spec_bytes)?;
+                    let proofs = spec_list(&input["proofs"], spec_bytes)?;
+                    Some(settings.verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs))
+                })();
+                assert_eq!(spec_verdict(result), output.as_bool(), "{}", path.display());
+            }
+            _ => panic!("unknown handler {}", handler),
+        }
+    }
+
+    /// Runs test cases laid out as `<handler>/<suite>/<case>/data.yaml`, like the
+    /// `tests/general/deneb/kzg` directory of the consensus-spec-tests.
+    fn run_spec_cases(settings: &KzgSettings, root: &Path) {
+        let sorted_dirs = |dir: &Path| {
+            let mut dirs: Vec<_> = std::fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect();
+            dirs.sort();
+            dirs
+        };
+
+        let mut handlers = 0;
+        for handler_dir in sorted_dirs(root) {
+            let handler = handler_dir.file_name().unwrap().to_str().unwrap();
+            let mut cases = 0;
+            for suite_dir in sorted_dirs(&handler_dir) {
+                for case_dir in sorted_dirs(&suite_dir) {
+                    run_spec_case(settings, handler, &case_dir.join("data.yaml"));
+                    cases += 1;
+                }
+            }
+            assert!(cases > 0, "no cases for {}", handler);
+            handlers += 1;
+        }
+        assert_eq!(handlers, 6);
+    }
+
+    #[test]
+    fn test_spec_vectors() {
+        let settings = settings();
+
+        // Cases in the consensus-spec-tests format, valid and invalid inputs for every
+        // public function, generated with the reference implementation, c-kzg 1.0.3.
+        run_spec_cases(&settings, &Path::new(TEST_DATA).join("kzg"));
+
+        // The official cases run when this variable points at the
+        // `tests/general/deneb/kzg` directory of a consensus-spec-tests release, as
+        // the `spec_tests` CI job does with a pinned release.
+        if let Some(dir) = std::env::var_os("CONSENSUS_SPEC_TESTS_KZG") {
+            run_spec_cases(&settings, Path::new(&dir));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Errors returned when decoding points and when aggregating or verifying pairings.
+
+use core::fmt;
+
+use blst::BLST_ERROR;
+
+/// Errors returned by the decoding and pairing APIs of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The bytes are not a valid encoding of a point.
+    BadEncoding,
+    /// The point is not on the curve.
+    PointNotOnCurve,
+    /// The point is not in the prime order subgroup.
+    PointNotInGroup,
+    /// An aggregation mixed elements of $\mathbb{G}_1$ and $\mathbb{G}_2$.
+    AggregateTypeMismatch,
+    /// A public key is the point at infinity.
+    PublicKeyIsInfinity,
+    /// The verification equation does not hold.
+    VerifyFailed,
+    /// A domain separation tag is empty or longer than 255 bytes.
+    InvalidDst,
+    /// A pairing context snapshot was taken with a different domain separation tag.
+    DstMismatch,
+    /// An evaluation domain would have more than $2^{32}$ elements.
+    DomainTooLarge,
+    /// Two interpolation points share the same x coordinate.
+    DuplicateInterpolationPoint,
+    /// A polynomial has a larger degree than the reference string supports.
+    PolynomialDegreeTooLarge,
+    /// Bytes are not the canonical encoding of a scalar.
+    InvalidScalar,
+    /// Inputs that must have the same length do not.
+    LengthMismatch,
+    /// The number of aggregated proofs is not a power of two supported by the
+    /// reference string.
+    InvalidAggregationSize,
+    /// Key material is shorter than 32 bytes, or key information longer than 65535.
+    InvalidKeyMaterial,
+    /// A message index is out of range, or indices are not in ascending order.
+    InvalidMessageIndex,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            Error::BadEncoding => "bad point encoding",
+            Error::PointNotOnCurve => "point is not on the curve",
+            Error::PointNotInGroup => "point is not in the prime order subgroup",
+            Error::AggregateTypeMismatch => "aggregate type mismatch",
+            Error::PublicKeyIsInfinity => "public key is the point at infinity",
+            Error::VerifyFailed => "verification failed",
+            Error::InvalidDst => "domain separation tag must be 1 to 255 bytes long",
+            Error::DstMismatch => "domain separation tag does not match the snapshot",
+            Error::DomainTooLarge => "evaluation domain is larger than 2^32",
+            Error::DuplicateInterpolationPoint => "interpolation points are not distinct",
+            Error::PolynomialDegreeTooLarge => "polynomial degree exceeds the reference string",
+            Error::InvalidScalar => "bytes are not a canonical scalar",
+            Error::LengthMismatch => "input lengths do not match",
+            Error::InvalidAggregationSize => {
+                "number of proofs must be a power of two supported by the reference string"
+            }
+            Error::InvalidKeyMaterial => "key material is too short or key information too long",
+            Error::InvalidMessageIndex => "message indices must be ascending and in range",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// Converts a status code returned by blst, mapping success to `Ok(())`.
+    pub(crate) fn from_blst(err: BLST_ERROR) -> Result<(), Error> {
+        match err {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            BLST_ERROR::BLST_BAD_ENCODING => Err(Error::BadEncoding),
+            BLST_ERROR::BLST_POINT_NOT_ON_CURVE => Err(Error::PointNotOnCurve),
+            BLST_ERROR::BLST_POINT_NOT_IN_GROUP => Err(Error::PointNotInGroup),
+            BLST_ERROR::BLST_AGGR_TYPE_MISMATCH => Err(Error::AggregateTypeMismatch),
+            BLST_ERROR::BLST_VERIFY_FAIL => Err(Error::VerifyFailed),
+            BLST_ERROR::BLST_PK_IS_INFINITY => Err(Error::PublicKeyIsInfinity),
+        }
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+impl From<Error> for groupy::GroupDecodingError {
+    fn from(err: Error) -> groupy::GroupDecodingError {
+        match err {
+            Error::PointNotOnCurve => groupy::GroupDecodingError::NotOnCurve,
+            Error::PointNotInGroup => groupy::GroupDecodingError::NotInSubgroup,
+            _ => groupy::GroupDecodingError::UnexpectedInformation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    use blst::BLST_ERROR;
+
+    #[test]
+    fn test_from_blst() {
+        assert_eq!(Error::from_blst(BLST_ERROR::BLST_SUCCESS), Ok(()));
+        assert_eq!(
+            Error::from_blst(BLST_ERROR::BLST_BAD_ENCODING),
+            Err(Error::BadEncoding)
+        );
+        assert_eq!(
+            Error::from_blst(BLST_ERROR::BLST_POINT_NOT_ON_CURVE),
+            Err(Error::PointNotOnCurve)
+        );
+        assert_eq!(
+            Error::from_blst(BLST_ERROR::BLST_POINT_NOT_IN_GROUP),
+            Err(Error::PointNotInGroup)
+        );
+        assert_eq!(
+            Error::from_blst(BLST_ERROR::BLST_AGGR_TYPE_MISMATCH),
+            Err(Error::AggregateTypeMismatch)
+        );
+        assert_eq!(
+            Error::from_blst(BLST_ERROR::BLST_VERIFY_FAIL),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            Error::from_blst(BLST_ERROR::BLST_PK_IS_INFINITY),
+            Err(Error::PublicKeyIsInfinity)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Error::VerifyFailed.to_string(), "verification failed");
+        let err: std::io::Error = Error::BadEncoding.into();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,204 @@
+//! KZG openings at all points of a domain in $O(n \log n)$ group operations, with
+//! the algorithm of Feist and Khovratovich (FK20).
+//!
+//! The proofs for a polynomial `f` are the evaluations over the domain of a
+//! polynomial with coefficients in $\mathbb{G}_1$, which are a Toeplitz matrix in
+//! the coefficients of `f` times the reference string. The product is computed with
+//! a circulant embedding and FFTs over $\mathbb{G}_1$.
+
+use fff::Field;
+use groupy::{CurveAffine, CurveProjective};
+
+use crate::{Error, EvaluationDomain, G1Affine, G1Projective, KzgSrs, Polynomial, Scalar};
+
+/// Precomputed data to open polynomials of degree less than `size` at every point
+/// of the domain of that size, or on every coset of a subgroup of order
+/// `cell_size`.
+///
+/// With `cell_size` equal to `l`, the domain of size `n` with generator `w` is
+/// split into the `n / l` cells `{w^(i + j n / l) : j < l}`, and the proof for cell
+/// `i` is a commitment to the quotient of `f` by `X^l - w^(i l)`. A cell size of
+/// one gives the usual single point proofs.
+#[derive(Debug, Clone)]
+pub struct Fk20 {
+    domain: EvaluationDomain,
+    cell_size: usize,
+    /// The domain of size `2 n / l` of the circulant embedding.
+    toeplitz_domain: EvaluationDomain,
+    /// The domain of size `n / l` over which the proofs are evaluated.
+    cell_domain: EvaluationDomain,
+    /// For each residue `r` modulo `l`, the FFT of the reversed reference string
+    /// `[tau^(r + l j)]_1`, padded to the size of the circulant embedding.
+    srs_fft: Vec<Vec<G1Projective>>,
+}
+
+impl Fk20 {
+    /// Prepares openings over the domain of at least `min_size` elements, for cells
+    /// of `cell_size` points.
+    ///
+    /// Fails with `PolynomialDegreeTooLarge` if the reference string does not
+    /// support polynomials of degree less than the domain size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not a power of two or exceeds the domain size.
+    pub fn new(srs: &KzgSrs, min_size: usize, cell_size: usize) -> Result<Self, Error> {
+        let domain = EvaluationDomain::new(min_size)?;
+        let n = domain.size();
+        assert!(
+            cell_size.is_power_of_two() && cell_size <= n,
+            "the cell size must be a power of two dividing the domain size"
+        );
+        if srs.max_degree() + 1 < n {
+            return Err(Error::PolynomialDegreeTooLarge);
+        }
+
+        let cells = n / cell_size;
+        let toeplitz_domain = EvaluationDomain::new(2 * cells)?;
+        let cell_domain = EvaluationDomain::new(cells)?;
+        let powers = srs.g1_powers();
+        let srs_fft = (0..cell_size)
+            .map(|r| {
+                // a_i = [tau^(r + l (k - 2 - i))]_1 for i < k - 1, with k cells.
+                let mut a = vec![G1Projective::zero(); 2 * cells];
+                for (i, a) in a.iter_mut().enumerate().take(cells - 1) {
+                    *a = powers[r + cell_size * (cells - 2 - i)].into_projective();
+                }
+                toeplitz_domain.fft_group(&mut a);
+                a
+            })
+            .collect();
+
+        Ok(Fk20 {
+            domain,
+            cell_size,
+            toeplitz_domain,
+            cell_domain,
+            srs_fft,
+        })
+    }
+
+    /// Returns the domain the polynomials are opened over.
+    pub fn domain(&self) -> &EvaluationDomain {
+        &self.domain
+    }
+
+    /// Returns the number of points covered by each proof.
+    pub fn cell_size(&self) -> usize {
+        self.cell_size
+    }
+
+    /// Computes the proofs for all cells of the domain, in order, as described on
+    /// `Fk20`. With a cell size of one, proof `i` opens `p` at `w^i`.
+    ///
+    /// Fails with `PolynomialDegreeTooLarge` if the degree of `p` is not less than
+    /// the domain size.
+    pub fn open_all(&self, p: &Polynomial) -> Result<Vec<G1Affine>, Error> {
+        let coeffs = p.coeffs();
+        if coeffs.len() > self.domain.size() {
+            return Err(Error::PolynomialDegreeTooLarge);
+        }
+
+        // The quotient by X^l - c is sum_u c^u h_u with h_u = sum_j f_(j + (u + 1) l)
+        // [tau^j]_1, a sum over the residues r of j modulo l of Toeplitz products.
+        let cells = self.cell_domain.size();
+        let mut h = vec![G1Projective::zero(); 2 * cells];
+        let mut f = vec![Scalar::zero(); 2 * cells];
+        for (r, srs_fft) in self.srs_fft.iter().enumerate() {
+            for (w, f) in f.iter_mut().enumerate().take(cells) {
+                *f = coeffs
+                    .get(r + self.cell_size * w)
+                    .copied()
+                    .unwrap_or_else(Scalar::zero);
+            }
+            for f in f[cells..].iter_mut() {
+                *f = Scalar::zero();
+            }
+            self.toeplitz_domain.fft(&mut f);
+
+            for ((h, a), f) in h.iter_mut().zip(srs_fft).zip(&f) {
+                h.add_assign(&a.mul_vartime(f));
+            }
+        }
+        self.toeplitz_domain.ifft_group(&mut h);
+
+        // The product is in the upper half of the cyclic convolution.
+        let mut h = h.split_off(cells - 1);
+        h.truncate(cells);
+        h[cells - 1] = G1Projective::zero();
+        self.cell_domain.fft_group(&mut h);
+        G1Projective::batch_normalization(&mut h);
+
+        Ok(h.iter().map(|p| p.into_affine()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_fk20_single_point() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(15, &mut rng);
+        let fk20 = Fk20::new(&srs, 16, 1).unwrap();
+        assert_eq!(fk20.domain().size(), 16);
+
+        for degree in &[0, 1, 7, 15] {
+            let p = Polynomial::random(*degree, &mut rng);
+            let commitment = srs.commit(&p).unwrap();
+            let proofs = fk20.open_all(&p).unwrap();
+            assert_eq!(proofs.len(), 16);
+
+            for (proof, z) in proofs.iter().zip(fk20.domain().elements()) {
+                let (value, expected) = srs.open(&p, &z).unwrap();
+                assert_eq!(*proof, expected);
+                assert_eq!(srs.verify(&commitment, &z, &value, proof), Ok(()));
+            }
+        }
+
+        assert_eq!(
+            fk20.open_all(&Polynomial::random(16, &mut rng)),
+            Err(Error::PolynomialDegreeTooLarge)
+        );
+        assert_eq!(
+            Fk20::new(&srs, 32, 1).unwrap_err(),
+            Error::PolynomialDegreeTooLarge
+        );
+    }
+
+    #[test]
+    fn test_fk20_cells() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(31, &mut rng);
+        let omega = EvaluationDomain::new(32).unwrap().omega();
+        for cell_size in &[2, 4, 8, 32] {
+            let fk20 = Fk20::new(&srs, 32, *cell_size).unwrap();
+            assert_eq!(fk20.cell_size(), *cell_size);
+
+            let p = Polynomial::random(31, &mut rng);
+            let proofs = fk20.open_all(&p).unwrap();
+            assert_eq!(proofs.len(), 32 / cell_size);
+
+            for (i, proof) in proofs.iter().enumerate() {
+                // The vanishing polynomial X^l - w^(i l) of cell i.
+                let mut vanishing = vec![Scalar::zero(); cell_size + 1];
+                vanishing[0] = -omega.pow([(i * cell_size) as u64]);
+                vanishing[*cell_size] = Scalar::one();
+                let (quotient, _) = p.div_rem(&Polynomial::from_coeffs(vanishing)).unwrap();
+                assert_eq!(*proof, srs.commit(&quotient).unwrap());
+            }
+        }
+    }
+}
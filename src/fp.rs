@@ -10,6 +10,7 @@ use core::{
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 use fff::{Field, PrimeField};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 use crate::Fp2;
 
@@ -529,7 +530,24 @@ impl PartialEq for Fp {
     }
 }
 
-impl<'a> Neg for &'a Fp {
+impl ConstantTimeEq for Fp {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.l[..].ct_eq(&other.0.l[..])
+    }
+}
+
+impl ConditionallySelectable for Fp {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut out = blst_fp::default();
+        for (out, (a, b)) in out.l.iter_mut().zip(a.0.l.iter().zip(b.0.l.iter())) {
+            *out = u64::conditional_select(a, b, choice);
+        }
+
+        Fp(out)
+    }
+}
+
+impl Neg for &Fp {
     type Output = Fp;
 
     #[inline]
@@ -547,29 +565,29 @@ impl Neg for Fp {
     }
 }
 
-impl<'a, 'b> Sub<&'b Fp> for &'a Fp {
+impl Sub<&Fp> for &Fp {
     type Output = Fp;
 
     #[inline]
-    fn sub(self, rhs: &'b Fp) -> Fp {
+    fn sub(self, rhs: &Fp) -> Fp {
         self.sub(rhs)
     }
 }
 
-impl<'a, 'b> Add<&'b Fp> for &'a Fp {
+impl Add<&Fp> for &Fp {
     type Output = Fp;
 
     #[inline]
-    fn add(self, rhs: &'b Fp) -> Fp {
+    fn add(self, rhs: &Fp) -> Fp {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Fp> for &'a Fp {
+impl Mul<&Fp> for &Fp {
     type Output = Fp;
 
     #[inline]
-    fn mul(self, rhs: &'b Fp) -> Fp {
+    fn mul(self, rhs: &Fp) -> Fp {
         self.mul(rhs)
     }
 }
@@ -763,7 +781,7 @@ impl fff::SqrtField for Fp {
         // Shank's algorithm for q mod 4 = 3
         // https://eprint.iacr.org/2012/685.pdf (page 9, algorithm 2)
 
-        let mut a1 = self.pow(&[
+        let mut a1 = self.pow([
             17185665809301629610u64,
             552535377879302143u64,
             15693976698673184137u64,
@@ -819,6 +837,65 @@ impl Fp {
         Some(Fp(raw))
     }
 
+    /// Converts a little-endian byte representation into an `Fp` in constant time,
+    /// failing if the input is not canonical.
+    pub fn from_bytes_le_ct(bytes: &[u8; 48]) -> CtOption<Fp> {
+        let mut limbs = [0u64; 6];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        // The subtraction of the modulus borrows iff the input is canonical.
+        let mut borrow = 0u64;
+        for (limb, m) in limbs.iter().zip(MODULUS.0.iter()) {
+            let (d, b0) = limb.overflowing_sub(*m);
+            let (_, b1) = d.overflowing_sub(borrow);
+            borrow = (b0 | b1) as u64;
+        }
+
+        let mut raw = blst_fp::default();
+        unsafe { blst_fp_from_uint64(&mut raw, limbs.as_ptr()) };
+
+        CtOption::new(Fp(raw), Choice::from(borrow as u8))
+    }
+
+    /// Converts a big-endian byte representation into an `Fp` in constant time,
+    /// failing if the input is not canonical.
+    pub fn from_bytes_be_ct(bytes: &[u8; 48]) -> CtOption<Fp> {
+        let mut le = *bytes;
+        le.reverse();
+        Fp::from_bytes_le_ct(&le)
+    }
+
+    /// Computes `self^exp`, where `exp` is given as little-endian limbs, with a
+    /// sliding window. The running time depends on the exponent.
+    pub fn pow_vartime(&self, exp: &[u64]) -> Fp {
+        crate::vartime::pow_vartime(self, exp)
+    }
+
+    /// Returns whether this element is a square, zero included, by computing its
+    /// Legendre symbol with the binary Jacobi algorithm. The running time depends on
+    /// the value.
+    pub fn is_square_vartime(&self) -> bool {
+        crate::vartime::legendre_vartime(self.into_repr(), MODULUS) >= 0
+    }
+
+    /// Computes the multiplicative inverse in constant time, failing for zero.
+    pub fn inverse_ct(&self) -> CtOption<Fp> {
+        let mut out = blst_fp::default();
+        unsafe { blst_fp_inverse(&mut out, &self.0) };
+
+        CtOption::new(Fp(out), !self.ct_eq(&Fp::zero()))
+    }
+
+    /// Computes a square root in constant time, failing if there is none.
+    pub fn sqrt_ct(&self) -> CtOption<Fp> {
+        let mut out = blst_fp::default();
+        let is_square = unsafe { blst_fp_sqrt(&mut out, &self.0) };
+
+        CtOption::new(Fp(out), Choice::from(is_square as u8))
+    }
+
     /// Converts an element of `Fp` into a byte representation in
     /// little-endian byte order.
     pub fn to_bytes_le(&self) -> [u8; 48] {
@@ -1833,7 +1910,7 @@ mod tests {
             // Exponentiate by various small numbers and ensure it consists with repeated
             // multiplication.
             let a = Fp::random(&mut rng);
-            let target = a.pow(&[i]);
+            let target = a.pow([i]);
             let mut c = Fp::one();
             for _ in 0..i {
                 c.mul_assign(&a);
@@ -2007,7 +2084,7 @@ mod tests {
             Fp::from_repr(FpRepr::from(2)).unwrap()
         );
         assert_eq!(
-            Fp::multiplicative_generator().pow(&[
+            Fp::multiplicative_generator().pow([
                 0xdcff7fffffffd555,
                 0xf55ffff58a9ffff,
                 0xb39869507b587b12,
@@ -2018,12 +2095,90 @@ mod tests {
             Fp::root_of_unity()
         );
         assert_eq!(
-            Fp::root_of_unity().pow(&[1 << Fp::S, 0, 0, 0, 0, 0]),
+            Fp::root_of_unity().pow([1 << Fp::S, 0, 0, 0, 0, 0]),
             Fp::one()
         );
         assert!(Fp::multiplicative_generator().sqrt().is_none());
     }
 
+    #[test]
+    fn test_fp_vartime() {
+        use fff::SqrtField;
+        use rand_core::RngCore;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fp::random(&mut rng);
+            let exp = [rng.next_u64(), rng.next_u64(), rng.next_u64() >> 7];
+            assert_eq!(a.pow_vartime(&exp), a.pow(exp));
+            let small = [rng.next_u64() & 0xff];
+            assert_eq!(a.pow_vartime(&small), a.pow(small));
+
+            assert_eq!(
+                a.is_square_vartime(),
+                a.legendre() != fff::LegendreSymbol::QuadraticNonResidue
+            );
+            let mut square = a;
+            square.square();
+            assert!(square.is_square_vartime());
+        }
+
+        let a = Fp::random(&mut rng);
+        assert_eq!(a.pow_vartime(&[]), Fp::one());
+        assert_eq!(a.pow_vartime(&[0, 0]), Fp::one());
+        assert_eq!(a.pow_vartime(&[1]), a);
+        assert_eq!(a.pow_vartime(&[u64::MAX; 6]), a.pow([u64::MAX; 6]));
+        assert!(Fp::zero().is_square_vartime());
+        assert!(!(-Fp::one()).is_square_vartime());
+    }
+
+    #[test]
+    fn test_fp_ct() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fp::random(&mut rng);
+            assert_eq!(a.inverse_ct().unwrap(), a.inverse().unwrap());
+
+            let mut square = a;
+            square.square();
+            let root = square.sqrt_ct().unwrap();
+            assert!(root == a || root == -a);
+
+            assert_eq!(Fp::from_bytes_le_ct(&a.to_bytes_le()).unwrap(), a);
+            assert_eq!(Fp::from_bytes_be_ct(&a.to_bytes_be()).unwrap(), a);
+        }
+
+        assert!(bool::from(Fp::zero().inverse_ct().is_none()));
+        assert_eq!(Fp::zero().sqrt_ct().unwrap(), Fp::zero());
+        // -1 is not a square, as p = 3 mod 4.
+        assert!(bool::from((-Fp::one()).sqrt_ct().is_none()));
+
+        let mut modulus = [0u8; 48];
+        for (chunk, limb) in modulus.chunks_exact_mut(8).zip(super::MODULUS.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        assert!(bool::from(Fp::from_bytes_le_ct(&modulus).is_none()));
+        modulus.reverse();
+        assert!(bool::from(Fp::from_bytes_be_ct(&modulus).is_none()));
+        assert!(bool::from(Fp::from_bytes_le_ct(&[0xff; 48]).is_none()));
+        assert_eq!(Fp::from_bytes_le_ct(&[0; 48]).unwrap(), Fp::zero());
+
+        crate::tests::field::random_ct_tests::<Fp>();
+    }
+
+    #[test]
+    fn test_fp_batch_invert() {
+        crate::tests::field::random_batch_invert_tests::<Fp>();
+    }
+
     #[test]
     fn fp_field_tests() {
         crate::tests::field::random_field_tests::<Fp>();
@@ -7,6 +7,7 @@ use core::{
 
 use blst::*;
 use fff::Field;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 use crate::{Fp, Fp2, Fp6};
 
@@ -65,7 +66,22 @@ impl Default for Fp12 {
     }
 }
 
-impl<'a> Neg for &'a Fp12 {
+impl ConstantTimeEq for Fp12 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0().ct_eq(&other.c0()) & self.c1().ct_eq(&other.c1())
+    }
+}
+
+impl ConditionallySelectable for Fp12 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fp12::new(
+            Fp6::conditional_select(&a.c0(), &b.c0(), choice),
+            Fp6::conditional_select(&a.c1(), &b.c1(), choice),
+        )
+    }
+}
+
+impl Neg for &Fp12 {
     type Output = Fp12;
 
     #[inline]
@@ -83,29 +99,29 @@ impl Neg for Fp12 {
     }
 }
 
-impl<'a, 'b> Sub<&'b Fp12> for &'a Fp12 {
+impl Sub<&Fp12> for &Fp12 {
     type Output = Fp12;
 
     #[inline]
-    fn sub(self, rhs: &'b Fp12) -> Fp12 {
+    fn sub(self, rhs: &Fp12) -> Fp12 {
         self.sub(rhs)
     }
 }
 
-impl<'a, 'b> Add<&'b Fp12> for &'a Fp12 {
+impl Add<&Fp12> for &Fp12 {
     type Output = Fp12;
 
     #[inline]
-    fn add(self, rhs: &'b Fp12) -> Fp12 {
+    fn add(self, rhs: &Fp12) -> Fp12 {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Fp12> for &'a Fp12 {
+impl Mul<&Fp12> for &Fp12 {
     type Output = Fp12;
 
     #[inline]
-    fn mul(self, rhs: &'b Fp12) -> Fp12 {
+    fn mul(self, rhs: &Fp12) -> Fp12 {
         self.mul(rhs)
     }
 }
@@ -164,6 +180,14 @@ impl Fp12 {
         unsafe { blst_fp12_conjugate(&mut self.0) };
     }
 
+    /// Computes the multiplicative inverse in constant time, failing for zero.
+    pub fn inverse_ct(&self) -> CtOption<Fp12> {
+        let mut out = blst_fp12::default();
+        unsafe { blst_fp12_inverse(&mut out, &self.0) };
+
+        CtOption::new(Fp12(out), !self.ct_eq(&Fp12::zero()))
+    }
+
     fn is_cyc(&self) -> bool {
         // Check if a^(p^4 - p^2 + 1) == 1.
         let mut t0 = *self;
@@ -645,6 +669,27 @@ mod tests {
         crate::tests::field::random_frobenius_tests::<Fp12, _>(crate::Fp::char(), 13);
     }
 
+    #[test]
+    fn test_fp12_ct() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fp12::random(&mut rng);
+            assert_eq!(a.inverse_ct().unwrap(), a.inverse().unwrap());
+        }
+        assert!(bool::from(Fp12::zero().inverse_ct().is_none()));
+
+        crate::tests::field::random_ct_tests::<Fp12>();
+    }
+
+    #[test]
+    fn test_fp12_batch_invert() {
+        crate::tests::field::random_batch_invert_tests::<Fp12>();
+    }
+
     #[test]
     fn fp12_random_field_tests() {
         crate::tests::field::random_field_tests::<Fp12>();
@@ -8,6 +8,7 @@ use core::{
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 use fff::{Field, SqrtField};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 use crate::{
     fp::{FROBENIUS_COEFF_FP2_C1, NEGATIVE_ONE},
@@ -90,7 +91,22 @@ impl PartialEq for Fp2 {
     }
 }
 
-impl<'a> Neg for &'a Fp2 {
+impl ConstantTimeEq for Fp2 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0().ct_eq(&other.c0()) & self.c1().ct_eq(&other.c1())
+    }
+}
+
+impl ConditionallySelectable for Fp2 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fp2::new(
+            Fp::conditional_select(&a.c0(), &b.c0(), choice),
+            Fp::conditional_select(&a.c1(), &b.c1(), choice),
+        )
+    }
+}
+
+impl Neg for &Fp2 {
     type Output = Fp2;
 
     #[inline]
@@ -108,29 +124,29 @@ impl Neg for Fp2 {
     }
 }
 
-impl<'a, 'b> Sub<&'b Fp2> for &'a Fp2 {
+impl Sub<&Fp2> for &Fp2 {
     type Output = Fp2;
 
     #[inline]
-    fn sub(self, rhs: &'b Fp2) -> Fp2 {
+    fn sub(self, rhs: &Fp2) -> Fp2 {
         self.sub(rhs)
     }
 }
 
-impl<'a, 'b> Add<&'b Fp2> for &'a Fp2 {
+impl Add<&Fp2> for &Fp2 {
     type Output = Fp2;
 
     #[inline]
-    fn add(self, rhs: &'b Fp2) -> Fp2 {
+    fn add(self, rhs: &Fp2) -> Fp2 {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Fp2> for &'a Fp2 {
+impl Mul<&Fp2> for &Fp2 {
     type Output = Fp2;
 
     #[inline]
-    fn mul(self, rhs: &'b Fp2) -> Fp2 {
+    fn mul(self, rhs: &Fp2) -> Fp2 {
         self.mul(rhs)
     }
 }
@@ -213,6 +229,28 @@ impl Fp2 {
         self.0.fp[1].into()
     }
 
+    /// Returns whether this element is a square, zero included, in time depending on
+    /// the value. This is the case if and only if its norm is a square in `Fp`.
+    pub fn is_square_vartime(&self) -> bool {
+        self.norm().is_square_vartime()
+    }
+
+    /// Computes the multiplicative inverse in constant time, failing for zero.
+    pub fn inverse_ct(&self) -> CtOption<Fp2> {
+        let mut out = blst_fp2::default();
+        unsafe { blst_fp2_inverse(&mut out, &self.0) };
+
+        CtOption::new(Fp2(out), !self.ct_eq(&Fp2::zero()))
+    }
+
+    /// Computes a square root in constant time, failing if there is none.
+    pub fn sqrt_ct(&self) -> CtOption<Fp2> {
+        let mut out = blst_fp2::default();
+        let is_square = unsafe { blst_fp2_sqrt(&mut out, &self.0) };
+
+        CtOption::new(Fp2(out), Choice::from(is_square as u8))
+    }
+
     /// Multiply this element by the cubic and quadratic nonresidue 1 + u.
     pub fn mul_by_nonresidue(&mut self) {
         let t0 = self.c0();
@@ -1037,6 +1075,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fp2_is_square_vartime() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fp2::random(&mut rng);
+            assert_eq!(a.is_square_vartime(), a.legendre() != QuadraticNonResidue);
+            let mut square = a;
+            square.square();
+            assert!(square.is_square_vartime());
+        }
+        assert!(Fp2::zero().is_square_vartime());
+    }
+
+    #[test]
+    fn test_fp2_ct() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fp2::random(&mut rng);
+            assert_eq!(a.inverse_ct().unwrap(), a.inverse().unwrap());
+
+            let mut square = a;
+            square.square();
+            let root = square.sqrt_ct().unwrap();
+            assert!(root == a || root == -a);
+
+            let b = Fp2::random(&mut rng);
+            assert_eq!(
+                bool::from(b.sqrt_ct().is_some()),
+                b.legendre() != QuadraticNonResidue
+            );
+        }
+
+        assert!(bool::from(Fp2::zero().inverse_ct().is_none()));
+        assert_eq!(Fp2::zero().sqrt_ct().unwrap(), Fp2::zero());
+
+        crate::tests::field::random_ct_tests::<Fp2>();
+    }
+
+    #[test]
+    fn test_fp2_batch_invert() {
+        crate::tests::field::random_batch_invert_tests::<Fp2>();
+    }
+
     #[test]
     fn fp2_field_tests() {
         crate::tests::field::random_field_tests::<Fp2>();
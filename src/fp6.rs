@@ -13,6 +13,7 @@ use crate::{
 };
 
 use fff::Field;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 /// This represents an element $c_0 + c_1 v + c_2 v^2$ of $\mathbb{F}_{p^6} = \mathbb{F}_{p^2} / v^3 - u - 1$.
 #[derive(Copy, Clone)]
@@ -90,7 +91,23 @@ impl PartialEq for Fp6 {
     }
 }
 
-impl<'a> Neg for &'a Fp6 {
+impl ConstantTimeEq for Fp6 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0().ct_eq(&other.c0()) & self.c1().ct_eq(&other.c1()) & self.c2().ct_eq(&other.c2())
+    }
+}
+
+impl ConditionallySelectable for Fp6 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fp6::new(
+            Fp2::conditional_select(&a.c0(), &b.c0(), choice),
+            Fp2::conditional_select(&a.c1(), &b.c1(), choice),
+            Fp2::conditional_select(&a.c2(), &b.c2(), choice),
+        )
+    }
+}
+
+impl Neg for &Fp6 {
     type Output = Fp6;
 
     #[inline]
@@ -108,29 +125,29 @@ impl Neg for Fp6 {
     }
 }
 
-impl<'a, 'b> Sub<&'b Fp6> for &'a Fp6 {
+impl Sub<&Fp6> for &Fp6 {
     type Output = Fp6;
 
     #[inline]
-    fn sub(self, rhs: &'b Fp6) -> Fp6 {
+    fn sub(self, rhs: &Fp6) -> Fp6 {
         self.sub(rhs)
     }
 }
 
-impl<'a, 'b> Add<&'b Fp6> for &'a Fp6 {
+impl Add<&Fp6> for &Fp6 {
     type Output = Fp6;
 
     #[inline]
-    fn add(self, rhs: &'b Fp6) -> Fp6 {
+    fn add(self, rhs: &Fp6) -> Fp6 {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Fp6> for &'a Fp6 {
+impl Mul<&Fp6> for &Fp6 {
     type Output = Fp6;
 
     #[inline]
-    fn mul(self, rhs: &'b Fp6) -> Fp6 {
+    fn mul(self, rhs: &Fp6) -> Fp6 {
         self.mul(rhs)
     }
 }
@@ -192,6 +209,52 @@ impl Fp6 {
         self.0.fp2[2].into()
     }
 
+    /// Computes the multiplicative inverse in constant time, failing for zero.
+    pub fn inverse_ct(&self) -> CtOption<Fp6> {
+        let (norm, c0, c1, c2) = self.inverse_parts();
+        norm.inverse_ct().map(|t| Fp6::new(t * c0, t * c1, t * c2))
+    }
+
+    /// Returns the norm down to `Fp2` and the coefficients of the adjugate, such
+    /// that the inverse is the adjugate divided by the norm.
+    fn inverse_parts(&self) -> (Fp2, Fp2, Fp2, Fp2) {
+        let mut c0 = self.c2();
+        c0.mul_by_nonresidue();
+        c0 *= &self.c1();
+        c0 = -c0;
+        {
+            let mut c0s = self.c0();
+            c0s.square();
+            c0 += &c0s;
+        }
+        let mut c1 = self.c2();
+        c1.square();
+        c1.mul_by_nonresidue();
+        {
+            let mut c01 = self.c0();
+            c01 *= &self.c1();
+            c1 -= &c01;
+        }
+        let mut c2 = self.c1();
+        c2.square();
+        {
+            let mut c02 = self.c0();
+            c02 *= &self.c2();
+            c2 -= &c02;
+        }
+        let mut tmp1 = self.c2();
+        tmp1 *= &c1;
+        let mut tmp2 = self.c1();
+        tmp2 *= &c2;
+        tmp1 += &tmp2;
+        tmp1.mul_by_nonresidue();
+        tmp2 = self.c0();
+        tmp2 *= &c0;
+        tmp1 += &tmp2;
+
+        (tmp1, c0, c1, c2)
+    }
+
     /// Multiply by quadratic nonresidue v.
     pub fn mul_by_nonresidue(&mut self) {
         self.0.fp2.swap(0, 1);
@@ -347,43 +410,8 @@ impl Field for Fp6 {
     }
 
     fn inverse(&self) -> Option<Self> {
-        let mut c0 = self.c2();
-        c0.mul_by_nonresidue();
-        c0 *= &self.c1();
-        c0 = -c0;
-        {
-            let mut c0s = self.c0();
-            c0s.square();
-            c0 += &c0s;
-        }
-        let mut c1 = self.c2();
-        c1.square();
-        c1.mul_by_nonresidue();
-        {
-            let mut c01 = self.c0();
-            c01 *= &self.c1();
-            c1 -= &c01;
-        }
-        let mut c2 = self.c1();
-        c2.square();
-        {
-            let mut c02 = self.c0();
-            c02 *= &self.c2();
-            c2 -= &c02;
-        }
-        let mut tmp1 = self.c2();
-        tmp1 *= &c1;
-        let mut tmp2 = self.c1();
-        tmp2 *= &c2;
-        tmp1 += &tmp2;
-        tmp1.mul_by_nonresidue();
-        tmp2 = self.c0();
-        tmp2 *= &c0;
-        tmp1 += &tmp2;
-        match tmp1.inverse() {
-            Some(t) => Some(Fp6::new(t * c0, t * c1, t * c2)),
-            None => None,
-        }
+        let (norm, c0, c1, c2) = self.inverse_parts();
+        norm.inverse().map(|t| Fp6::new(t * c0, t * c1, t * c2))
     }
 }
 
@@ -414,6 +442,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fp6_ct() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Fp6::random(&mut rng);
+            assert_eq!(a.inverse_ct().unwrap(), a.inverse().unwrap());
+        }
+        assert!(bool::from(Fp6::zero().inverse_ct().is_none()));
+
+        crate::tests::field::random_ct_tests::<Fp6>();
+    }
+
+    #[test]
+    fn test_fp6_batch_invert() {
+        crate::tests::field::random_batch_invert_tests::<Fp6>();
+    }
+
     #[test]
     fn fp6_random_field_tests() {
         crate::tests::field::random_field_tests::<Fp6>();
@@ -12,8 +12,9 @@ use blst::*;
 use fff::{Field, PrimeField, PrimeFieldRepr};
 use groupy::{CurveAffine, CurveProjective, EncodedPoint};
 use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
-use crate::{Fp, Fp12, G2Affine, Scalar, ScalarRepr};
+use crate::{BatchInvert, Error, Fp, Fp12, G2Affine, Scalar, ScalarRepr};
 
 /// This is an element of $\mathbb{G}_1$ represented in the affine coordinate space.
 /// It is ideal to keep elements in this representation to reduce memory usage and
@@ -83,6 +84,21 @@ impl PartialEq for G1Affine {
     }
 }
 
+impl ConstantTimeEq for G1Affine {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from(unsafe { blst_p1_affine_is_equal(&self.0, &other.0) } as u8)
+    }
+}
+
+impl ConditionallySelectable for G1Affine {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        G1Affine(blst_p1_affine {
+            x: Fp::conditional_select(&a.0.x.into(), &b.0.x.into(), choice).0,
+            y: Fp::conditional_select(&a.0.y.into(), &b.0.y.into(), choice).0,
+        })
+    }
+}
+
 impl Neg for &G1Affine {
     type Output = G1Affine;
 
@@ -104,38 +120,38 @@ impl Neg for G1Affine {
     }
 }
 
-impl<'a, 'b> Add<&'b G1Projective> for &'a G1Affine {
+impl Add<&G1Projective> for &G1Affine {
     type Output = G1Projective;
 
     #[inline]
-    fn add(self, rhs: &'b G1Projective) -> G1Projective {
+    fn add(self, rhs: &G1Projective) -> G1Projective {
         rhs.add_mixed(self)
     }
 }
 
-impl<'a, 'b> Add<&'b G1Affine> for &'a G1Projective {
+impl Add<&G1Affine> for &G1Projective {
     type Output = G1Projective;
 
     #[inline]
-    fn add(self, rhs: &'b G1Affine) -> G1Projective {
+    fn add(self, rhs: &G1Affine) -> G1Projective {
         self.add_mixed(rhs)
     }
 }
 
-impl<'a, 'b> Sub<&'b G1Projective> for &'a G1Affine {
+impl Sub<&G1Projective> for &G1Affine {
     type Output = G1Projective;
 
     #[inline]
-    fn sub(self, rhs: &'b G1Projective) -> G1Projective {
+    fn sub(self, rhs: &G1Projective) -> G1Projective {
         self + (-rhs)
     }
 }
 
-impl<'a, 'b> Sub<&'b G1Affine> for &'a G1Projective {
+impl Sub<&G1Affine> for &G1Projective {
     type Output = G1Projective;
 
     #[inline]
-    fn sub(self, rhs: &'b G1Affine) -> G1Projective {
+    fn sub(self, rhs: &G1Affine) -> G1Projective {
         self + (-rhs)
     }
 }
@@ -180,12 +196,9 @@ impl groupy::CurveAffine for G1Affine {
     }
 
     fn negate(&mut self) {
-        // Missing for affine in blst
-        if !self.is_zero() {
-            let mut y = self.y();
-            y.negate();
-            self.0.y = y.0;
-        }
+        // Missing for affine in blst. Negating y in constant time leaves the point at
+        // infinity, encoded as (0, 0), unchanged.
+        unsafe { blst_fp_cneg(&mut self.0.y, &self.0.y, true) }
     }
 
     fn into_projective(&self) -> Self::Projective {
@@ -217,14 +230,8 @@ impl G1Affine {
     }
 
     /// Attempts to deserialize an uncompressed element.
-    pub fn from_uncompressed(bytes: &[u8; 96]) -> Option<Self> {
-        G1Affine::from_uncompressed_unchecked(bytes).and_then(|el| {
-            if el.is_zero() || el.is_torsion_free() {
-                Some(el)
-            } else {
-                None
-            }
-        })
+    pub fn from_uncompressed(bytes: &[u8; 96]) -> Result<Self, Error> {
+        G1Affine::from_uncompressed_unchecked(bytes).and_then(Self::check_subgroup)
     }
 
     /// Attempts to deserialize an uncompressed element, not checking if the
@@ -232,27 +239,20 @@ impl G1Affine {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_uncompressed()` instead.
-    pub fn from_uncompressed_unchecked(bytes: &[u8; 96]) -> Option<Self> {
+    pub fn from_uncompressed_unchecked(bytes: &[u8; 96]) -> Result<Self, Error> {
         if bytes.iter().all(|&b| b == 0) {
-            return Some(Self::zero());
+            return Ok(Self::zero());
         }
+
         let mut raw = blst_p1_affine::default();
-        if unsafe { blst_p1_deserialize(&mut raw, bytes.as_ptr()) != BLST_ERROR::BLST_SUCCESS } {
-            return None;
-        }
+        Error::from_blst(unsafe { blst_p1_deserialize(&mut raw, bytes.as_ptr()) })?;
 
-        Some(G1Affine(raw))
+        Ok(G1Affine(raw))
     }
 
     /// Attempts to deserialize a compressed element.
-    pub fn from_compressed(bytes: &[u8; 48]) -> Option<Self> {
-        G1Affine::from_compressed_unchecked(bytes).and_then(|el| {
-            if el.is_zero() || el.is_torsion_free() {
-                Some(el)
-            } else {
-                None
-            }
-        })
+    pub fn from_compressed(bytes: &[u8; 48]) -> Result<Self, Error> {
+        G1Affine::from_compressed_unchecked(bytes).and_then(Self::check_subgroup)
     }
 
     /// Attempts to deserialize an uncompressed element, not checking if the
@@ -260,18 +260,23 @@ impl G1Affine {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_compressed()` instead.
-    pub fn from_compressed_unchecked(bytes: &[u8; 48]) -> Option<Self> {
+    pub fn from_compressed_unchecked(bytes: &[u8; 48]) -> Result<Self, Error> {
         if bytes.iter().all(|&b| b == 0) {
-            return Some(Self::zero());
+            return Ok(Self::zero());
         }
 
         let mut raw = blst_p1_affine::default();
+        Error::from_blst(unsafe { blst_p1_uncompress(&mut raw, bytes.as_ptr()) })?;
 
-        if unsafe { blst_p1_uncompress(&mut raw, bytes.as_ptr()) != BLST_ERROR::BLST_SUCCESS } {
-            return None;
-        }
+        Ok(G1Affine(raw))
+    }
 
-        Some(G1Affine(raw))
+    fn check_subgroup(self) -> Result<Self, Error> {
+        if self.is_zero() || self.is_torsion_free() {
+            Ok(self)
+        } else {
+            Err(Error::PointNotInGroup)
+        }
     }
 
     /// Returns true if this point is free of an $h$-torsion component, and so it
@@ -342,12 +347,7 @@ impl G1Affine {
 
         let mut buf = [0u8; 96];
         reader.read_exact(&mut buf)?;
-        Self::from_uncompressed_unchecked(&buf).ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                groupy::GroupDecodingError::NotOnCurve,
-            )
-        })
+        Ok(Self::from_uncompressed_unchecked(&buf)?)
     }
 
     pub fn read_raw_checked<R: Read>(mut reader: R) -> Result<Self, std::io::Error> {
@@ -357,12 +357,7 @@ impl G1Affine {
 
         let mut buf = [0u8; 96];
         reader.read_exact(&mut buf)?;
-        Self::from_uncompressed(&buf).ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                groupy::GroupDecodingError::NotOnCurve,
-            )
-        })
+        Ok(Self::from_uncompressed(&buf)?)
     }
 }
 
@@ -425,7 +420,23 @@ impl PartialEq for G1Projective {
     }
 }
 
-impl<'a> Neg for &'a G1Projective {
+impl ConstantTimeEq for G1Projective {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from(unsafe { blst_p1_is_equal(&self.0, &other.0) } as u8)
+    }
+}
+
+impl ConditionallySelectable for G1Projective {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        G1Projective(blst_p1 {
+            x: Fp::conditional_select(&a.0.x.into(), &b.0.x.into(), choice).0,
+            y: Fp::conditional_select(&a.0.y.into(), &b.0.y.into(), choice).0,
+            z: Fp::conditional_select(&a.0.z.into(), &b.0.z.into(), choice).0,
+        })
+    }
+}
+
+impl Neg for &G1Projective {
     type Output = G1Projective;
 
     #[inline]
@@ -446,36 +457,36 @@ impl Neg for G1Projective {
     }
 }
 
-impl<'a, 'b> Add<&'b G1Projective> for &'a G1Projective {
+impl Add<&G1Projective> for &G1Projective {
     type Output = G1Projective;
 
     #[inline]
-    fn add(self, rhs: &'b G1Projective) -> G1Projective {
+    fn add(self, rhs: &G1Projective) -> G1Projective {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Sub<&'b G1Projective> for &'a G1Projective {
+impl Sub<&G1Projective> for &G1Projective {
     type Output = G1Projective;
 
     #[inline]
-    fn sub(self, rhs: &'b G1Projective) -> G1Projective {
+    fn sub(self, rhs: &G1Projective) -> G1Projective {
         self + (-rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Scalar> for &'a G1Projective {
+impl Mul<&Scalar> for &G1Projective {
     type Output = G1Projective;
 
-    fn mul(self, other: &'b Scalar) -> Self::Output {
+    fn mul(self, other: &Scalar) -> Self::Output {
         self.multiply(&other.into_repr())
     }
 }
 
-impl<'a, 'b> Mul<&'b Scalar> for &'a G1Affine {
+impl Mul<&Scalar> for &G1Affine {
     type Output = G1Projective;
 
-    fn mul(self, other: &'b Scalar) -> Self::Output {
+    fn mul(self, other: &Scalar) -> Self::Output {
         G1Projective::from(self).multiply(&other.into_repr())
     }
 }
@@ -491,14 +502,83 @@ impl MulAssign<Scalar> for G1Projective {
     }
 }
 
-impl<'b> MulAssign<&'b Scalar> for G1Projective {
+impl MulAssign<&Scalar> for G1Projective {
     #[inline]
-    fn mul_assign(&mut self, rhs: &'b Scalar) {
+    fn mul_assign(&mut self, rhs: &Scalar) {
         *self = *self * rhs;
     }
 }
 
 impl G1Projective {
+    /// Multiplies by a public scalar, using its w-NAF form for scalars of up to 160 bits,
+    /// such as random coefficients in batch verification.
+    ///
+    /// The running time depends on the scalar, so this must not be used with secrets
+    /// such as signing keys, see `mul_secret` instead.
+    pub fn mul_vartime(&self, scalar: &Scalar) -> G1Projective {
+        // The constant time multiplication of blst uses the GLV endomorphism, which
+        // is faster than w-NAF for longer scalars.
+        const WNAF_MAX_BITS: u32 = 160;
+
+        let repr = scalar.into_repr();
+        if repr.num_bits() <= WNAF_MAX_BITS {
+            crate::vartime::wnaf_mul(self, repr)
+        } else {
+            self.multiply(&repr)
+        }
+    }
+
+    /// Computes the multi-scalar multiplication `sum_i scalars[i] * bases[i]` with
+    /// Pippenger's bucket method.
+    ///
+    /// The running time depends on the scalars, which must be public.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn multi_exp_vartime(bases: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+        let scalars: Vec<ScalarRepr> = scalars.iter().map(|s| s.into_repr()).collect();
+        crate::vartime::multi_exp(bases, &scalars)
+    }
+
+    /// Multiplies by a secret scalar, such as a signing key.
+    ///
+    /// The scalar is randomized by adding a random multiple of the group order, and
+    /// the projective coordinates of the point are blinded, so that repeated
+    /// multiplications by the same scalar do not process the same values. The point
+    /// must be in the prime order subgroup, as for all elements of this type.
+    pub fn mul_secret<R: RngCore>(&self, scalar: &Scalar, rng: &mut R) -> G1Projective {
+        // Randomized scalars are at most 320 bits wide.
+        const NBITS: usize = 320;
+
+        let scalar = scalar.randomized_bytes_le(rng);
+        let point = self.blind(rng);
+
+        let mut out = blst_p1::default();
+        unsafe { blst_p1_mult(&mut out, &point.0, scalar.as_ptr(), NBITS) };
+
+        G1Projective(out)
+    }
+
+    /// Rescales the Jacobian coordinates `(X, Y, Z)` to `(l^2 X, l^3 Y, l Z)` for a
+    /// random non-zero `l`, which represents the same point.
+    fn blind<R: RngCore>(&self, rng: &mut R) -> G1Projective {
+        let l = loop {
+            let l = Fp::random(rng);
+            if !l.is_zero() {
+                break l;
+            }
+        };
+        let l2 = l * l;
+        let l3 = l2 * l;
+
+        G1Projective(blst_p1 {
+            x: (Fp::from(self.0.x) * l2).0,
+            y: (Fp::from(self.0.y) * l3).0,
+            z: (Fp::from(self.0.z) * l).0,
+        })
+    }
+
     /// Serializes this element into compressed form.
     pub fn to_compressed(&self) -> [u8; 48] {
         let mut out = [0u8; 48];
@@ -522,7 +602,7 @@ impl G1Projective {
     }
 
     /// Attempts to deserialize an uncompressed element.
-    pub fn from_uncompressed(bytes: &[u8; 96]) -> Option<Self> {
+    pub fn from_uncompressed(bytes: &[u8; 96]) -> Result<Self, Error> {
         G1Affine::from_uncompressed(bytes).map(Into::into)
     }
 
@@ -531,12 +611,12 @@ impl G1Projective {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_uncompressed()` instead.
-    pub fn from_uncompressed_unchecked(bytes: &[u8; 96]) -> Option<Self> {
+    pub fn from_uncompressed_unchecked(bytes: &[u8; 96]) -> Result<Self, Error> {
         G1Affine::from_uncompressed_unchecked(bytes).map(Into::into)
     }
 
     /// Attempts to deserialize a compressed element.
-    pub fn from_compressed(bytes: &[u8; 48]) -> Option<Self> {
+    pub fn from_compressed(bytes: &[u8; 48]) -> Result<Self, Error> {
         G1Affine::from_compressed(bytes).map(Into::into)
     }
 
@@ -545,7 +625,7 @@ impl G1Projective {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_compressed()` instead.
-    pub fn from_compressed_unchecked(bytes: &[u8; 48]) -> Option<Self> {
+    pub fn from_compressed_unchecked(bytes: &[u8; 48]) -> Result<Self, Error> {
         G1Affine::from_compressed_unchecked(bytes).map(Into::into)
     }
 
@@ -676,14 +756,20 @@ impl groupy::CurveProjective for G1Projective {
     }
 
     fn batch_normalization<S: std::borrow::BorrowMut<Self>>(v: &mut [S]) {
-        for el in v {
-            let el = el.borrow_mut();
-            let mut tmp = blst_p1_affine::default();
+        // Jacobian coordinates, the affine point is (x / z^2, y / z^3).
+        let mut zs: Vec<Fp> = v.iter_mut().map(|el| el.borrow_mut().z()).collect();
+        // The points may be secret, so neither the inversion nor the handling of the
+        // point at infinity may branch on their values.
+        Fp::batch_invert_ct(&mut zs);
 
-            unsafe {
-                blst_p1_to_affine(&mut tmp, &el.0);
-                blst_p1_from_affine(&mut el.0, &tmp);
-            }
+        for (el, z_inv) in v.iter_mut().zip(zs) {
+            let el = el.borrow_mut();
+            let z_inv2 = z_inv * z_inv;
+            let x = el.x() * z_inv2;
+            let y = el.y() * z_inv2 * z_inv;
+            let normalized = Self::from_raw_unchecked(x, y, Fp::one());
+            let is_zero = el.z().ct_eq(&Fp::zero());
+            *el = Self::conditional_select(&normalized, el, is_zero);
         }
     }
 
@@ -765,12 +851,11 @@ impl EncodedPoint for G1Uncompressed {
         96
     }
     fn into_affine(&self) -> Result<G1Affine, groupy::GroupDecodingError> {
-        G1Affine::from_uncompressed(&self.0).ok_or(groupy::GroupDecodingError::NotInSubgroup)
+        G1Affine::from_uncompressed(&self.0).map_err(Into::into)
     }
 
     fn into_affine_unchecked(&self) -> Result<G1Affine, groupy::GroupDecodingError> {
-        G1Affine::from_uncompressed_unchecked(&self.0)
-            .ok_or(groupy::GroupDecodingError::UnexpectedInformation)
+        G1Affine::from_uncompressed_unchecked(&self.0).map_err(Into::into)
     }
 
     fn from_affine(affine: G1Affine) -> Self {
@@ -793,12 +878,11 @@ impl EncodedPoint for G1Compressed {
         48
     }
     fn into_affine(&self) -> Result<G1Affine, groupy::GroupDecodingError> {
-        G1Affine::from_compressed(&self.0).ok_or(groupy::GroupDecodingError::NotInSubgroup)
+        G1Affine::from_compressed(&self.0).map_err(Into::into)
     }
 
     fn into_affine_unchecked(&self) -> Result<G1Affine, groupy::GroupDecodingError> {
-        G1Affine::from_compressed_unchecked(&self.0)
-            .ok_or(groupy::GroupDecodingError::UnexpectedInformation)
+        G1Affine::from_compressed_unchecked(&self.0).map_err(Into::into)
     }
 
     fn from_affine(affine: G1Affine) -> Self {
@@ -832,11 +916,94 @@ mod tests {
 
     use super::*;
 
-    use fff::Field;
+    use fff::{Field, SqrtField};
     use groupy::{CurveAffine, CurveProjective};
     use rand_core::SeedableRng;
     use rand_xorshift::XorShiftRng;
 
+    #[test]
+    fn test_g1_mul_secret() {
+        use rand_core::RngCore;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..20 {
+            let p = G1Projective::random(&mut rng);
+            let k = Scalar::random(&mut rng);
+            let expected = p * k;
+
+            assert_eq!(p.mul_vartime(&k), expected);
+            let small = Scalar::from(rng.next_u64());
+            assert_eq!(p.mul_vartime(&small), p * small);
+            assert_eq!(p.mul_secret(&k, &mut rng), expected);
+            assert_eq!(p.mul_secret(&k, &mut rng), expected);
+            assert_eq!(p.mul_secret(&-k, &mut rng), -expected);
+
+            let blinded = p.blind(&mut rng);
+            assert_eq!(blinded, p);
+            assert_ne!(blinded.0.z, p.0.z);
+
+            assert!(p.mul_secret(&Scalar::zero(), &mut rng).is_zero());
+            assert!(G1Projective::zero().mul_secret(&k, &mut rng).is_zero());
+            assert!(p.mul_vartime(&Scalar::zero()).is_zero());
+            assert_eq!(p.mul_vartime(&Scalar::one()), p);
+            assert_eq!(p.mul_vartime(&-Scalar::one()), -p);
+            assert!(G1Projective::zero().mul_vartime(&k).is_zero());
+        }
+    }
+
+    #[test]
+    fn test_g1_ct() {
+        use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..20 {
+            let a = G1Projective::random(&mut rng);
+            let b = G1Projective::random(&mut rng);
+
+            // Equal points with different Z coordinates.
+            let mut doubled = a;
+            doubled.double();
+            let added = a + a;
+            assert!(bool::from(doubled.ct_eq(&added)));
+            assert!(!bool::from(a.ct_eq(&b)));
+            assert!(!bool::from(a.ct_eq(&G1Projective::zero())));
+
+            assert_eq!(G1Projective::conditional_select(&a, &b, Choice::from(0)), a);
+            assert_eq!(G1Projective::conditional_select(&a, &b, Choice::from(1)), b);
+            let mut c = a;
+            c.conditional_negate(Choice::from(1));
+            assert_eq!(c, -a);
+
+            let a = a.into_affine();
+            let b = b.into_affine();
+            assert!(bool::from(a.ct_eq(&a)));
+            assert!(!bool::from(a.ct_eq(&b)));
+            assert_eq!(G1Affine::conditional_select(&a, &b, Choice::from(0)), a);
+            assert_eq!(G1Affine::conditional_select(&a, &b, Choice::from(1)), b);
+            let mut c = a;
+            c.conditional_negate(Choice::from(0));
+            assert_eq!(c, a);
+            c.conditional_negate(Choice::from(1));
+            assert_eq!(c, -a);
+        }
+
+        assert!(bool::from(
+            G1Projective::zero().ct_eq(&G1Projective::zero())
+        ));
+        let mut zero = G1Affine::zero();
+        zero.conditional_negate(Choice::from(1));
+        assert!(zero.is_zero());
+        assert!(bool::from(zero.ct_eq(&G1Affine::zero())));
+    }
+
     #[test]
     fn curve_tests() {
         let mut rng = XorShiftRng::from_seed([
@@ -893,6 +1060,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn g1_test_is_valid() {
+        // Reject point not on the curve
+        {
+            let gen = G1Affine::one();
+            let p = G1Affine::from_raw_unchecked(gen.x(), gen.y() + Fp::one(), false);
+            assert!(!p.is_on_curve());
+            assert_eq!(
+                G1Affine::from_uncompressed_unchecked(&p.to_uncompressed()),
+                Err(Error::PointNotOnCurve)
+            );
+            assert_eq!(
+                G1Affine::from_uncompressed(&p.to_uncompressed()),
+                Err(Error::PointNotOnCurve)
+            );
+        }
+
+        // Reject point on the curve but outside of the r-order subgroup
+        {
+            let p = (0u64..)
+                .find_map(|x| {
+                    let x = Fp::from(x);
+                    (x * x * x + Fp::from(4))
+                        .sqrt()
+                        .map(|y| G1Affine::from_raw_unchecked(x, y, false))
+                })
+                .unwrap();
+            assert!(p.is_on_curve());
+            assert!(!p.is_torsion_free());
+            assert_eq!(
+                G1Affine::from_compressed(&p.to_compressed()),
+                Err(Error::PointNotInGroup)
+            );
+            assert_eq!(
+                G1Affine::from_uncompressed(&p.to_uncompressed()),
+                Err(Error::PointNotInGroup)
+            );
+        }
+
+        // Reject an x coordinate that is not on the curve
+        {
+            let x = (0u64..)
+                .map(Fp::from)
+                .find(|&x| (x * x * x + Fp::from(4)).sqrt().is_none())
+                .unwrap();
+            let mut bytes = x.to_bytes_be();
+            bytes[0] |= 0x80;
+            assert_eq!(
+                G1Affine::from_compressed_unchecked(&bytes),
+                Err(Error::PointNotOnCurve)
+            );
+        }
+
+        assert_eq!(
+            G1Affine::from_compressed(&[0xff; 48]),
+            Err(Error::BadEncoding)
+        );
+        assert_eq!(
+            G1Affine::from_uncompressed(&[0xff; 96]),
+            Err(Error::BadEncoding)
+        );
+    }
+
     #[test]
     fn test_is_on_curve() {
         assert!(G1Projective::zero().is_on_curve());
@@ -1366,4 +1596,29 @@ mod tests {
         assert!(G1Affine::zero().is_zero());
         assert!(!G1Affine::one().is_zero());
     }
+
+    #[test]
+    fn test_g1_batch_normalization() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut v: Vec<G1Projective> = (0..20)
+            .map(|i| {
+                if i % 5 == 0 {
+                    G1Projective::zero()
+                } else {
+                    G1Projective::random(&mut rng)
+                }
+            })
+            .collect();
+        let expected: Vec<G1Affine> = v.iter().map(|p| p.into_affine()).collect();
+
+        G1Projective::batch_normalization(&mut v);
+        for (p, q) in v.iter().zip(expected) {
+            assert!(p.is_normalized());
+            assert_eq!(p.into_affine(), q);
+        }
+    }
 }
@@ -2,17 +2,20 @@
 
 use core::{
     borrow::Borrow,
+    convert::TryInto,
     fmt,
     iter::Sum,
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
+use std::io::{self, Read, Write};
 
 use blst::*;
 use fff::{Field, PrimeField, PrimeFieldRepr};
 use groupy::{CurveAffine, CurveProjective};
 use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
-use crate::{Fp12, Fp2, G1Affine, Scalar, ScalarRepr};
+use crate::{BatchInvert, Error, Fp, Fp12, Fp2, FpRepr, G1Affine, Scalar, ScalarRepr};
 
 /// This is an element of $\mathbb{G}_2$ represented in the affine coordinate space.
 /// It is ideal to keep elements in this representation to reduce memory usage and
@@ -82,6 +85,21 @@ impl PartialEq for G2Affine {
     }
 }
 
+impl ConstantTimeEq for G2Affine {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from(unsafe { blst_p2_affine_is_equal(&self.0, &other.0) } as u8)
+    }
+}
+
+impl ConditionallySelectable for G2Affine {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        G2Affine(blst_p2_affine {
+            x: Fp2::conditional_select(&a.0.x.into(), &b.0.x.into(), choice).0,
+            y: Fp2::conditional_select(&a.0.y.into(), &b.0.y.into(), choice).0,
+        })
+    }
+}
+
 impl Neg for &G2Affine {
     type Output = G2Affine;
 
@@ -103,38 +121,38 @@ impl Neg for G2Affine {
     }
 }
 
-impl<'a, 'b> Add<&'b G2Projective> for &'a G2Affine {
+impl Add<&G2Projective> for &G2Affine {
     type Output = G2Projective;
 
     #[inline]
-    fn add(self, rhs: &'b G2Projective) -> G2Projective {
+    fn add(self, rhs: &G2Projective) -> G2Projective {
         rhs.add_mixed(self)
     }
 }
 
-impl<'a, 'b> Add<&'b G2Affine> for &'a G2Projective {
+impl Add<&G2Affine> for &G2Projective {
     type Output = G2Projective;
 
     #[inline]
-    fn add(self, rhs: &'b G2Affine) -> G2Projective {
+    fn add(self, rhs: &G2Affine) -> G2Projective {
         self.add_mixed(rhs)
     }
 }
 
-impl<'a, 'b> Sub<&'b G2Projective> for &'a G2Affine {
+impl Sub<&G2Projective> for &G2Affine {
     type Output = G2Projective;
 
     #[inline]
-    fn sub(self, rhs: &'b G2Projective) -> G2Projective {
+    fn sub(self, rhs: &G2Projective) -> G2Projective {
         self + (-rhs)
     }
 }
 
-impl<'a, 'b> Sub<&'b G2Affine> for &'a G2Projective {
+impl Sub<&G2Affine> for &G2Projective {
     type Output = G2Projective;
 
     #[inline]
-    fn sub(self, rhs: &'b G2Affine) -> G2Projective {
+    fn sub(self, rhs: &G2Affine) -> G2Projective {
         self + (-rhs)
     }
 }
@@ -179,12 +197,9 @@ impl groupy::CurveAffine for G2Affine {
     }
 
     fn negate(&mut self) {
-        // Missing for affine in blst
-        if !self.is_zero() {
-            let mut y = self.y();
-            y.negate();
-            self.0.y = y.0;
-        }
+        // Missing for affine in blst. Negating y in constant time leaves the point at
+        // infinity, encoded as (0, 0), unchanged.
+        unsafe { blst_fp2_cneg(&mut self.0.y, &self.0.y, true) }
     }
 
     fn into_projective(&self) -> Self::Projective {
@@ -231,14 +246,8 @@ impl G2Affine {
     }
 
     /// Attempts to deserialize an uncompressed element.
-    pub fn from_uncompressed(bytes: &[u8; 192]) -> Option<Self> {
-        G2Affine::from_uncompressed_unchecked(bytes).and_then(|el| {
-            if el.is_zero() || el.is_torsion_free() {
-                Some(el)
-            } else {
-                None
-            }
-        })
+    pub fn from_uncompressed(bytes: &[u8; 192]) -> Result<Self, Error> {
+        G2Affine::from_uncompressed_unchecked(bytes).and_then(Self::check_subgroup)
     }
 
     /// Attempts to deserialize an uncompressed element, not checking if the
@@ -246,29 +255,20 @@ impl G2Affine {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_uncompressed()` instead.
-    pub fn from_uncompressed_unchecked(bytes: &[u8; 192]) -> Option<Self> {
+    pub fn from_uncompressed_unchecked(bytes: &[u8; 192]) -> Result<Self, Error> {
         if bytes.iter().all(|&b| b == 0) {
-            return Some(Self::zero());
+            return Ok(Self::zero());
         }
 
         let mut raw = blst_p2_affine::default();
+        Error::from_blst(unsafe { blst_p2_deserialize(&mut raw, bytes.as_ptr()) })?;
 
-        if unsafe { blst_p2_deserialize(&mut raw, bytes.as_ptr()) != BLST_ERROR::BLST_SUCCESS } {
-            return None;
-        }
-
-        Some(G2Affine(raw))
+        Ok(G2Affine(raw))
     }
 
     /// Attempts to deserialize a compressed element.
-    pub fn from_compressed(bytes: &[u8; 96]) -> Option<Self> {
-        G2Affine::from_compressed_unchecked(bytes).and_then(|el| {
-            if el.is_zero() || el.is_torsion_free() {
-                Some(el)
-            } else {
-                None
-            }
-        })
+    pub fn from_compressed(bytes: &[u8; 96]) -> Result<Self, Error> {
+        G2Affine::from_compressed_unchecked(bytes).and_then(Self::check_subgroup)
     }
 
     /// Attempts to deserialize an uncompressed element, not checking if the
@@ -276,18 +276,23 @@ impl G2Affine {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_compressed()` instead.
-    pub fn from_compressed_unchecked(bytes: &[u8; 96]) -> Option<Self> {
+    pub fn from_compressed_unchecked(bytes: &[u8; 96]) -> Result<Self, Error> {
         if bytes.iter().all(|&b| b == 0) {
-            return Some(Self::zero());
+            return Ok(Self::zero());
         }
 
         let mut raw = blst_p2_affine::default();
+        Error::from_blst(unsafe { blst_p2_uncompress(&mut raw, bytes.as_ptr()) })?;
 
-        if unsafe { blst_p2_uncompress(&mut raw, bytes.as_ptr()) != BLST_ERROR::BLST_SUCCESS } {
-            return None;
-        }
+        Ok(G2Affine(raw))
+    }
 
-        Some(G2Affine(raw))
+    fn check_subgroup(self) -> Result<Self, Error> {
+        if self.is_zero() || self.is_torsion_free() {
+            Ok(self)
+        } else {
+            Err(Error::PointNotInGroup)
+        }
     }
 
     /// Returns true if this point is free of an $h$-torsion component, and so it
@@ -396,7 +401,23 @@ impl PartialEq for G2Projective {
     }
 }
 
-impl<'a> Neg for &'a G2Projective {
+impl ConstantTimeEq for G2Projective {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from(unsafe { blst_p2_is_equal(&self.0, &other.0) } as u8)
+    }
+}
+
+impl ConditionallySelectable for G2Projective {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        G2Projective(blst_p2 {
+            x: Fp2::conditional_select(&a.0.x.into(), &b.0.x.into(), choice).0,
+            y: Fp2::conditional_select(&a.0.y.into(), &b.0.y.into(), choice).0,
+            z: Fp2::conditional_select(&a.0.z.into(), &b.0.z.into(), choice).0,
+        })
+    }
+}
+
+impl Neg for &G2Projective {
     type Output = G2Projective;
 
     #[inline]
@@ -418,36 +439,36 @@ impl Neg for G2Projective {
     }
 }
 
-impl<'a, 'b> Add<&'b G2Projective> for &'a G2Projective {
+impl Add<&G2Projective> for &G2Projective {
     type Output = G2Projective;
 
     #[inline]
-    fn add(self, rhs: &'b G2Projective) -> G2Projective {
+    fn add(self, rhs: &G2Projective) -> G2Projective {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Sub<&'b G2Projective> for &'a G2Projective {
+impl Sub<&G2Projective> for &G2Projective {
     type Output = G2Projective;
 
     #[inline]
-    fn sub(self, rhs: &'b G2Projective) -> G2Projective {
+    fn sub(self, rhs: &G2Projective) -> G2Projective {
         self + (-rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Scalar> for &'a G2Projective {
+impl Mul<&Scalar> for &G2Projective {
     type Output = G2Projective;
 
-    fn mul(self, other: &'b Scalar) -> Self::Output {
+    fn mul(self, other: &Scalar) -> Self::Output {
         self.multiply(&other.into_repr())
     }
 }
 
-impl<'a, 'b> Mul<&'b Scalar> for &'a G2Affine {
+impl Mul<&Scalar> for &G2Affine {
     type Output = G2Projective;
 
-    fn mul(self, other: &'b Scalar) -> Self::Output {
+    fn mul(self, other: &Scalar) -> Self::Output {
         G2Projective::from(self).multiply(&other.into_repr())
     }
 }
@@ -463,17 +484,86 @@ impl MulAssign<Scalar> for G2Projective {
     }
 }
 
-impl<'b> MulAssign<&'b Scalar> for G2Projective {
+impl MulAssign<&Scalar> for G2Projective {
     #[inline]
-    fn mul_assign(&mut self, rhs: &'b Scalar) {
+    fn mul_assign(&mut self, rhs: &Scalar) {
         *self = *self * rhs;
     }
 }
 
 impl G2Projective {
+    /// Multiplies by a public scalar, using its w-NAF form for scalars of up to 192 bits,
+    /// such as random coefficients in batch verification.
+    ///
+    /// The running time depends on the scalar, so this must not be used with secrets
+    /// such as signing keys, see `mul_secret` instead.
+    pub fn mul_vartime(&self, scalar: &Scalar) -> G2Projective {
+        // The constant time multiplication of blst uses the GLV endomorphism, which
+        // is faster than w-NAF for longer scalars.
+        const WNAF_MAX_BITS: u32 = 192;
+
+        let repr = scalar.into_repr();
+        if repr.num_bits() <= WNAF_MAX_BITS {
+            crate::vartime::wnaf_mul(self, repr)
+        } else {
+            self.multiply(&repr)
+        }
+    }
+
+    /// Computes the multi-scalar multiplication `sum_i scalars[i] * bases[i]` with
+    /// Pippenger's bucket method.
+    ///
+    /// The running time depends on the scalars, which must be public.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn multi_exp_vartime(bases: &[G2Affine], scalars: &[Scalar]) -> G2Projective {
+        let scalars: Vec<ScalarRepr> = scalars.iter().map(|s| s.into_repr()).collect();
+        crate::vartime::multi_exp(bases, &scalars)
+    }
+
+    /// Multiplies by a secret scalar, such as a signing key.
+    ///
+    /// The scalar is randomized by adding a random multiple of the group order, and
+    /// the projective coordinates of the point are blinded, so that repeated
+    /// multiplications by the same scalar do not process the same values. The point
+    /// must be in the prime order subgroup, as for all elements of this type.
+    pub fn mul_secret<R: RngCore>(&self, scalar: &Scalar, rng: &mut R) -> G2Projective {
+        // Randomized scalars are at most 320 bits wide.
+        const NBITS: usize = 320;
+
+        let scalar = scalar.randomized_bytes_le(rng);
+        let point = self.blind(rng);
+
+        let mut out = blst_p2::default();
+        unsafe { blst_p2_mult(&mut out, &point.0, scalar.as_ptr(), NBITS) };
+
+        G2Projective(out)
+    }
+
+    /// Rescales the Jacobian coordinates `(X, Y, Z)` to `(l^2 X, l^3 Y, l Z)` for a
+    /// random non-zero `l`, which represents the same point.
+    fn blind<R: RngCore>(&self, rng: &mut R) -> G2Projective {
+        let l = loop {
+            let l = Fp2::random(rng);
+            if !l.is_zero() {
+                break l;
+            }
+        };
+        let l2 = l * l;
+        let l3 = l2 * l;
+
+        G2Projective(blst_p2 {
+            x: (Fp2::from(self.0.x) * l2).0,
+            y: (Fp2::from(self.0.y) * l3).0,
+            z: (Fp2::from(self.0.z) * l).0,
+        })
+    }
+
     /// Serializes this element into compressed form.
-    pub fn to_compressed(&self) -> [u8; 48] {
-        let mut out = [0u8; 48];
+    pub fn to_compressed(&self) -> [u8; 96] {
+        let mut out = [0u8; 96];
 
         unsafe {
             blst_p2_compress(out.as_mut_ptr(), &self.0);
@@ -483,8 +573,8 @@ impl G2Projective {
     }
 
     /// Serializes this element into uncompressed form.
-    pub fn to_uncompressed(&self) -> [u8; 96] {
-        let mut out = [0u8; 96];
+    pub fn to_uncompressed(&self) -> [u8; 192] {
+        let mut out = [0u8; 192];
 
         unsafe {
             blst_p2_serialize(out.as_mut_ptr(), &self.0);
@@ -494,7 +584,7 @@ impl G2Projective {
     }
 
     /// Attempts to deserialize an uncompressed element.
-    pub fn from_uncompressed(bytes: &[u8; 192]) -> Option<Self> {
+    pub fn from_uncompressed(bytes: &[u8; 192]) -> Result<Self, Error> {
         G2Affine::from_uncompressed(bytes).map(Into::into)
     }
 
@@ -503,12 +593,12 @@ impl G2Projective {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_uncompressed()` instead.
-    pub fn from_uncompressed_unchecked(bytes: &[u8; 192]) -> Option<Self> {
+    pub fn from_uncompressed_unchecked(bytes: &[u8; 192]) -> Result<Self, Error> {
         G2Affine::from_uncompressed_unchecked(bytes).map(Into::into)
     }
 
     /// Attempts to deserialize a compressed element.
-    pub fn from_compressed(bytes: &[u8; 96]) -> Option<Self> {
+    pub fn from_compressed(bytes: &[u8; 96]) -> Result<Self, Error> {
         G2Affine::from_compressed(bytes).map(Into::into)
     }
 
@@ -517,7 +607,7 @@ impl G2Projective {
     ///
     /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
     /// API invariants may be broken.** Please consider using `from_compressed()` instead.
-    pub fn from_compressed_unchecked(bytes: &[u8; 96]) -> Option<Self> {
+    pub fn from_compressed_unchecked(bytes: &[u8; 96]) -> Result<Self, Error> {
         G2Affine::from_compressed_unchecked(bytes).map(Into::into)
     }
 
@@ -648,14 +738,20 @@ impl groupy::CurveProjective for G2Projective {
     }
 
     fn batch_normalization<S: core::borrow::BorrowMut<Self>>(v: &mut [S]) {
-        for el in v {
-            let el = el.borrow_mut();
-            let mut tmp = blst_p2_affine::default();
+        // Jacobian coordinates, the affine point is (x / z^2, y / z^3).
+        let mut zs: Vec<Fp2> = v.iter_mut().map(|el| el.borrow_mut().z()).collect();
+        // The points may be secret, so neither the inversion nor the handling of the
+        // point at infinity may branch on their values.
+        Fp2::batch_invert_ct(&mut zs);
 
-            unsafe {
-                blst_p2_to_affine(&mut tmp, &el.0);
-                blst_p2_from_affine(&mut el.0, &tmp);
-            };
+        for (el, z_inv) in v.iter_mut().zip(zs) {
+            let el = el.borrow_mut();
+            let z_inv2 = z_inv * z_inv;
+            let x = el.x() * z_inv2;
+            let y = el.y() * z_inv2 * z_inv;
+            let normalized = Self::from_raw_unchecked(x, y, Fp2::one());
+            let is_zero = el.z().ct_eq(&Fp2::zero());
+            *el = Self::conditional_select(&normalized, el, is_zero);
         }
     }
 
@@ -716,7 +812,17 @@ impl groupy::CurveProjective for G2Projective {
     }
 }
 
-#[derive(Clone, Debug)]
+/// Version of the binary encoding written by `G2Prepared::write_raw`.
+const PREPARED_VERSION: u8 = 1;
+
+/// Number of line functions computed by `blst_precompute_lines`.
+const PREPARED_LINES: usize = 68;
+
+/// Size of a single encoded line function, six `Fp` elements.
+const PREPARED_LINE_SIZE: usize = 6 * 48;
+
+/// A $\mathbb{G}_2$ element with its Miller loop line functions precomputed.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct G2Prepared {
     pub(crate) lines: Vec<blst_fp6>,
     infinity: bool,
@@ -726,6 +832,171 @@ impl G2Prepared {
     pub fn is_zero(&self) -> bool {
         self.infinity
     }
+
+    /// Returns true if these lines are the ones computed when preparing `q`.
+    pub fn is_prepared_from(&self, q: &G2Affine) -> bool {
+        use crate::PairingCurveAffine;
+
+        self == &q.prepare()
+    }
+
+    /// Returns the point these lines were prepared from, or `None` if they are not
+    /// the preparation of a point of $\mathbb{G}_2$. This costs as much as preparing
+    /// the point.
+    pub fn point(&self) -> Option<G2Affine> {
+        if self.infinity {
+            return Some(G2Affine::zero());
+        }
+
+        // The first line is the tangent at q = (x, y), which blst's `line_dbl`
+        // computes from Z = 1 as (6 x^3 - 4 y^2, 3 x^2, 2 y).
+        let [l0, l1, l2] = self.lines.first()?.fp2;
+        let (l0, l1, l2) = (Fp2(l0), Fp2(l1), Fp2(l2));
+        let y = l2 * Fp2::from(2).inverse()?;
+        let x = (l0 + y * y * Fp2::from(4)) * (l1 * Fp2::from(2)).inverse()?;
+
+        let q = G2Affine(blst_p2_affine { x: x.0, y: y.0 });
+        if q.is_on_curve() && q.is_torsion_free() && self.is_prepared_from(&q) {
+            Some(q)
+        } else {
+            None
+        }
+    }
+
+    /// Size in bytes of the encoding written by `write_raw`.
+    pub fn raw_fmt_size(&self) -> usize {
+        2 + self.lines.len() * PREPARED_LINE_SIZE
+    }
+
+    /// Writes the versioned binary encoding of this element.
+    ///
+    /// The encoding is a version byte, an infinity flag byte and, for finite points,
+    /// the 68 line functions as canonical little-endian `Fp` coefficients.
+    pub fn write_raw<W: Write>(&self, mut writer: W) -> Result<usize, io::Error> {
+        writer.write_all(&[PREPARED_VERSION, self.infinity as u8])?;
+        for line in &self.lines {
+            for fp2 in &line.fp2 {
+                for fp in &fp2.fp {
+                    writer.write_all(&Fp(*fp).to_bytes_le())?;
+                }
+            }
+        }
+
+        Ok(self.raw_fmt_size())
+    }
+
+    /// Reads an element written by `write_raw`, not checking that the lines belong
+    /// to a point of $\mathbb{G}_2$.
+    ///
+    /// **This is dangerous to call unless you trust the bytes you are reading; otherwise,
+    /// pairings computed with the result are meaningless.** Please consider using
+    /// `read_raw_checked()` instead.
+    ///
+    /// Invalid data fails with an `io::Error` wrapping `Error::BadEncoding`.
+    pub fn read_raw_unchecked<R: Read>(mut reader: R) -> Result<Self, io::Error> {
+        let mut header = [0u8; 2];
+        reader.read_exact(&mut header)?;
+        if header[0] != PREPARED_VERSION {
+            return Err(Error::BadEncoding.into());
+        }
+        let infinity = match header[1] {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::BadEncoding.into()),
+        };
+
+        if infinity {
+            return Ok(G2Prepared {
+                lines: Vec::new(),
+                infinity,
+            });
+        }
+
+        let mut lines = vec![blst_fp6::default(); PREPARED_LINES];
+        let mut buf = [0u8; 48];
+        for line in lines.iter_mut() {
+            for fp2 in line.fp2.iter_mut() {
+                for fp in fp2.fp.iter_mut() {
+                    reader.read_exact(&mut buf)?;
+                    let mut repr = FpRepr::default();
+                    for (limb, chunk) in repr.0.iter_mut().zip(buf.chunks_exact(8)) {
+                        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+                    }
+                    *fp = Fp::from_repr(repr).map_err(|_| Error::BadEncoding)?.0;
+                }
+            }
+        }
+
+        Ok(G2Prepared { lines, infinity })
+    }
+
+    /// Reads an element written by `write_raw`, checking that it is the preparation
+    /// of `q`. This costs as much as preparing `q` directly.
+    ///
+    /// Invalid data and lines prepared from another point fail with an `io::Error`
+    /// wrapping `Error::BadEncoding`.
+    pub fn read_raw_checked<R: Read>(reader: R, q: &G2Affine) -> Result<Self, io::Error> {
+        let prepared = Self::read_raw_unchecked(reader)?;
+        if !prepared.is_prepared_from(q) {
+            return Err(Error::BadEncoding.into());
+        }
+
+        Ok(prepared)
+    }
+}
+
+/// Opt-in serde support for `G2Prepared`, as the encoding of `write_raw`, checking
+/// on deserialization that the lines are the preparation of a point of $\mathbb{G}_2$.
+///
+/// Use it with `#[serde(with = "blstrs::g2_prepared_serde_checked")]`.
+pub mod g2_prepared_serde_checked {
+    use serde::{de::Error as DeserializeError, Deserializer, Serializer};
+
+    use super::G2Prepared;
+
+    /// Serializes the lines as a byte string.
+    pub fn serialize<S: Serializer>(prepared: &G2Prepared, s: S) -> Result<S::Ok, S::Error> {
+        super::g2_prepared_serde_unchecked::serialize(prepared, s)
+    }
+
+    /// Deserializes lines from a byte string, failing unless they are the
+    /// preparation of a point of $\mathbb{G}_2$. This costs as much as preparing the
+    /// point.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<G2Prepared, D::Error> {
+        let prepared = super::g2_prepared_serde_unchecked::deserialize(d)?;
+        if prepared.point().is_none() {
+            return Err(D::Error::custom(
+                "G2Prepared lines are not the preparation of a point",
+            ));
+        }
+
+        Ok(prepared)
+    }
+}
+
+/// Opt-in serde support for `G2Prepared`, as the encoding of `write_raw`, without
+/// checking the lines on deserialization, see `G2Prepared::read_raw_unchecked`.
+///
+/// Use it with `#[serde(with = "blstrs::g2_prepared_serde_unchecked")]`, for trusted
+/// data only.
+pub mod g2_prepared_serde_unchecked {
+    use serde::{de::Error as DeserializeError, ser::Error as SerializeError};
+    use serde::{Deserializer, Serializer};
+
+    use super::G2Prepared;
+
+    /// Serializes the lines as a byte string.
+    pub fn serialize<S: Serializer>(prepared: &G2Prepared, s: S) -> Result<S::Ok, S::Error> {
+        let mut buf = Vec::with_capacity(prepared.raw_fmt_size());
+        prepared.write_raw(&mut buf).map_err(S::Error::custom)?;
+        s.serialize_bytes(&buf)
+    }
+
+    /// Deserializes lines from a byte string, without checking them.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<G2Prepared, D::Error> {
+        let bytes = crate::serde_impl::deserialize_bytes(d, "an encoded G2Prepared")?;
+        G2Prepared::read_raw_unchecked(&bytes[..]).map_err(D::Error::custom)
+    }
 }
 
 impl crate::PairingCurveAffine for G2Affine {
@@ -775,12 +1046,11 @@ impl groupy::EncodedPoint for G2Uncompressed {
         192
     }
     fn into_affine(&self) -> Result<G2Affine, groupy::GroupDecodingError> {
-        G2Affine::from_uncompressed(&self.0).ok_or(groupy::GroupDecodingError::NotInSubgroup)
+        G2Affine::from_uncompressed(&self.0).map_err(Into::into)
     }
 
     fn into_affine_unchecked(&self) -> Result<G2Affine, groupy::GroupDecodingError> {
-        G2Affine::from_uncompressed_unchecked(&self.0)
-            .ok_or(groupy::GroupDecodingError::UnexpectedInformation)
+        G2Affine::from_uncompressed_unchecked(&self.0).map_err(Into::into)
     }
 
     fn from_affine(affine: G2Affine) -> Self {
@@ -803,12 +1073,11 @@ impl groupy::EncodedPoint for G2Compressed {
         96
     }
     fn into_affine(&self) -> Result<G2Affine, groupy::GroupDecodingError> {
-        G2Affine::from_compressed(&self.0).ok_or(groupy::GroupDecodingError::NotInSubgroup)
+        G2Affine::from_compressed(&self.0).map_err(Into::into)
     }
 
     fn into_affine_unchecked(&self) -> Result<G2Affine, groupy::GroupDecodingError> {
-        G2Affine::from_compressed_unchecked(&self.0)
-            .ok_or(groupy::GroupDecodingError::UnexpectedInformation)
+        G2Affine::from_compressed_unchecked(&self.0).map_err(Into::into)
     }
 
     fn from_affine(affine: G2Affine) -> Self {
@@ -826,9 +1095,94 @@ impl fmt::Debug for G2Compressed {
 mod tests {
     #![allow(clippy::eq_op)]
 
-    use crate::{Fp, Fp2, FpRepr, G2Affine, G2Projective};
+    use crate::{Error, Fp, Fp2, FpRepr, G2Affine, G2Projective, Scalar};
     use fff::{Field, PrimeField};
     use groupy::CurveProjective;
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_g2_mul_secret() {
+        use rand_core::RngCore;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..20 {
+            let p = G2Projective::random(&mut rng);
+            let k = Scalar::random(&mut rng);
+            let expected = p * k;
+
+            assert_eq!(p.mul_vartime(&k), expected);
+            let small = Scalar::from(rng.next_u64());
+            assert_eq!(p.mul_vartime(&small), p * small);
+            assert_eq!(p.mul_secret(&k, &mut rng), expected);
+            assert_eq!(p.mul_secret(&k, &mut rng), expected);
+            assert_eq!(p.mul_secret(&-k, &mut rng), -expected);
+
+            let blinded = p.blind(&mut rng);
+            assert_eq!(blinded, p);
+            assert_ne!(blinded.0.z, p.0.z);
+
+            assert!(p.mul_secret(&Scalar::zero(), &mut rng).is_zero());
+            assert!(G2Projective::zero().mul_secret(&k, &mut rng).is_zero());
+            assert!(p.mul_vartime(&Scalar::zero()).is_zero());
+            assert_eq!(p.mul_vartime(&Scalar::one()), p);
+            assert_eq!(p.mul_vartime(&-Scalar::one()), -p);
+            assert!(G2Projective::zero().mul_vartime(&k).is_zero());
+        }
+    }
+
+    #[test]
+    fn test_g2_ct() {
+        use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..20 {
+            let a = G2Projective::random(&mut rng);
+            let b = G2Projective::random(&mut rng);
+
+            // Equal points with different Z coordinates.
+            let mut doubled = a;
+            doubled.double();
+            let added = a + a;
+            assert!(bool::from(doubled.ct_eq(&added)));
+            assert!(!bool::from(a.ct_eq(&b)));
+            assert!(!bool::from(a.ct_eq(&G2Projective::zero())));
+
+            assert_eq!(G2Projective::conditional_select(&a, &b, Choice::from(0)), a);
+            assert_eq!(G2Projective::conditional_select(&a, &b, Choice::from(1)), b);
+            let mut c = a;
+            c.conditional_negate(Choice::from(1));
+            assert_eq!(c, -a);
+
+            let a = a.into_affine();
+            let b = b.into_affine();
+            assert!(bool::from(a.ct_eq(&a)));
+            assert!(!bool::from(a.ct_eq(&b)));
+            assert_eq!(G2Affine::conditional_select(&a, &b, Choice::from(0)), a);
+            assert_eq!(G2Affine::conditional_select(&a, &b, Choice::from(1)), b);
+            let mut c = a;
+            c.conditional_negate(Choice::from(0));
+            assert_eq!(c, a);
+            c.conditional_negate(Choice::from(1));
+            assert_eq!(c, -a);
+        }
+
+        assert!(bool::from(
+            G2Projective::zero().ct_eq(&G2Projective::zero())
+        ));
+        let mut zero = G2Affine::zero();
+        zero.conditional_negate(Choice::from(1));
+        assert!(zero.is_zero());
+        assert!(bool::from(zero.ct_eq(&G2Affine::zero())));
+    }
 
     #[test]
     fn g2_test_is_valid() {
@@ -927,6 +1281,10 @@ mod tests {
             );
             assert!(!p.is_on_curve());
             assert!(!p.is_torsion_free());
+            assert_eq!(
+                G2Affine::from_uncompressed_unchecked(&p.to_uncompressed()),
+                Err(Error::PointNotOnCurve)
+            );
         }
 
         // Reject point in an invalid subgroup
@@ -977,7 +1335,24 @@ mod tests {
             );
             assert!(p.is_on_curve());
             assert!(!p.is_torsion_free());
+            assert_eq!(
+                G2Affine::from_compressed(&p.to_compressed()),
+                Err(Error::PointNotInGroup)
+            );
+            assert_eq!(
+                G2Affine::from_uncompressed(&p.to_uncompressed()),
+                Err(Error::PointNotInGroup)
+            );
+            assert_eq!(
+                G2Affine::from_compressed_unchecked(&p.to_compressed()),
+                Ok(p)
+            );
         }
+
+        assert_eq!(
+            G2Affine::from_compressed(&[0xff; 96]),
+            Err(Error::BadEncoding)
+        );
     }
 
     #[test]
@@ -1246,6 +1621,112 @@ mod tests {
         curve_tests::<G2Projective>();
     }
 
+    #[test]
+    fn test_projective_encodings() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let p = G2Projective::random(&mut rng);
+            let affine = p.into_affine();
+
+            // blst writes 96 compressed and 192 uncompressed bytes, which used to
+            // overflow buffers of 48 and 96 bytes.
+            let compressed = p.to_compressed();
+            assert_eq!(compressed, affine.to_compressed());
+            assert_eq!(G2Projective::from_compressed(&compressed), Ok(p));
+
+            let uncompressed = p.to_uncompressed();
+            assert_eq!(uncompressed, affine.to_uncompressed());
+            assert_eq!(G2Projective::from_uncompressed(&uncompressed), Ok(p));
+        }
+    }
+
+    #[test]
+    fn test_g2_prepared_raw() {
+        use crate::{G2Prepared, PairingCurveAffine};
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let q = G2Projective::random(&mut rng).into_affine();
+            let prepared = q.prepare();
+
+            let mut buf = Vec::new();
+            let len = prepared.write_raw(&mut buf).unwrap();
+            assert_eq!(len, buf.len());
+            assert_eq!(len, prepared.raw_fmt_size());
+
+            assert_eq!(G2Prepared::read_raw_unchecked(&buf[..]).unwrap(), prepared);
+            assert_eq!(
+                G2Prepared::read_raw_checked(&buf[..], &q).unwrap(),
+                prepared
+            );
+
+            let kind =
+                |err: std::io::Error| *err.into_inner().unwrap().downcast::<Error>().unwrap();
+            let other = G2Projective::random(&mut rng).into_affine();
+            let err = G2Prepared::read_raw_checked(&buf[..], &other).unwrap_err();
+            assert_eq!(kind(err), Error::BadEncoding);
+
+            // Unknown versions, bad flags and truncated data are rejected.
+            let mut bad = buf.clone();
+            bad[0] = 2;
+            let err = G2Prepared::read_raw_unchecked(&bad[..]).unwrap_err();
+            assert_eq!(kind(err), Error::BadEncoding);
+            let mut bad = buf.clone();
+            bad[1] = 2;
+            let err = G2Prepared::read_raw_unchecked(&bad[..]).unwrap_err();
+            assert_eq!(kind(err), Error::BadEncoding);
+            assert!(G2Prepared::read_raw_unchecked(&buf[..buf.len() - 1]).is_err());
+
+            // Non-canonical coefficients are rejected.
+            let mut bad = buf.clone();
+            for b in bad[2..50].iter_mut() {
+                *b = 0xff;
+            }
+            let err = G2Prepared::read_raw_unchecked(&bad[..]).unwrap_err();
+            assert_eq!(kind(err), Error::BadEncoding);
+        }
+
+        let zero = G2Affine::zero().prepare();
+        let mut buf = Vec::new();
+        zero.write_raw(&mut buf).unwrap();
+        assert_eq!(buf, vec![1, 1]);
+        let decoded = G2Prepared::read_raw_checked(&buf[..], &G2Affine::zero()).unwrap();
+        assert!(decoded.is_zero());
+    }
+
+    #[test]
+    fn test_g2_prepared_point() {
+        use crate::PairingCurveAffine;
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let q = G2Projective::random(&mut rng).into_affine();
+            let prepared = q.prepare();
+            assert_eq!(prepared.point(), Some(q));
+
+            // Tampering with the tangent or a later line is detected.
+            for i in &[0, 1, 67] {
+                let mut bad = prepared.clone();
+                bad.lines[*i].fp2[1] = (Fp2(bad.lines[*i].fp2[1]) + Fp2::one()).0;
+                assert_eq!(bad.point(), None);
+            }
+        }
+
+        assert_eq!(G2Affine::zero().prepare().point(), Some(G2Affine::zero()));
+    }
+
     #[test]
     fn test_g2_is_zero() {
         assert!(G2Projective::zero().is_zero());
@@ -1253,4 +1734,29 @@ mod tests {
         assert!(G2Affine::zero().is_zero());
         assert!(!G2Affine::one().is_zero());
     }
+
+    #[test]
+    fn test_g2_batch_normalization() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut v: Vec<G2Projective> = (0..20)
+            .map(|i| {
+                if i % 5 == 0 {
+                    G2Projective::zero()
+                } else {
+                    G2Projective::random(&mut rng)
+                }
+            })
+            .collect();
+        let expected: Vec<G2Affine> = v.iter().map(|p| p.into_affine()).collect();
+
+        G2Projective::batch_normalization(&mut v);
+        for (p, q) in v.iter().zip(expected) {
+            assert!(p.is_normalized());
+            assert_eq!(p.into_affine(), q);
+        }
+    }
 }
@@ -0,0 +1,481 @@
+//! Verification of Groth16 proofs, with keys and proofs in the encodings used by
+//! bellman and bellperson.
+//!
+//! A proof `(A, B, C)` for the public inputs `x_i` is valid if
+//! `e(A, B) = e(alpha, beta) e(IC_0 + sum x_i IC_(i + 1), gamma) e(C, delta)`. The
+//! verifier precomputes `e(alpha, beta)` and the line functions of `-gamma` and
+//! `-delta` in a `PreparedVerifyingKey`, so that checking a proof costs three
+//! Miller loops and a single final exponentiation.
+//!
+//! Many proofs for the same key are checked at once by `verify_proofs_batch`,
+//! which verifies a random linear combination of their equations.
+
+use core::fmt;
+use std::io::{self, Read, Write};
+
+use fff::{Field, PrimeField};
+use groupy::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+
+use crate::{
+    Bls12, Engine, Error, Fp12, G1Affine, G1Projective, G2Affine, G2Prepared, PairingCurveAffine,
+    Scalar,
+};
+
+/// The verifying key of a Groth16 circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g1: G1Affine,
+    pub delta_g2: G2Affine,
+    /// The points `IC_i` combined with the public inputs, one more than there are
+    /// inputs.
+    pub ic: Vec<G1Affine>,
+}
+
+impl VerifyingKey {
+    /// Reads a key of uncompressed points, followed by the number of `IC` points as
+    /// a big-endian `u32`. All points are checked to be in their subgroup and not
+    /// at infinity.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let alpha_g1 = read_g1_uncompressed(&mut reader)?;
+        let beta_g1 = read_g1_uncompressed(&mut reader)?;
+        let beta_g2 = read_g2_uncompressed(&mut reader)?;
+        let gamma_g2 = read_g2_uncompressed(&mut reader)?;
+        let delta_g1 = read_g1_uncompressed(&mut reader)?;
+        let delta_g2 = read_g2_uncompressed(&mut reader)?;
+
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len)?;
+        let ic = (0..u32::from_be_bytes(len))
+            .map(|_| read_g1_uncompressed(&mut reader))
+            .collect::<io::Result<_>>()?;
+
+        Ok(VerifyingKey {
+            alpha_g1,
+            beta_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g1,
+            delta_g2,
+            ic,
+        })
+    }
+
+    /// Writes the key in the encoding read by `read`.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.alpha_g1.to_uncompressed())?;
+        writer.write_all(&self.beta_g1.to_uncompressed())?;
+        writer.write_all(&self.beta_g2.to_uncompressed())?;
+        writer.write_all(&self.gamma_g2.to_uncompressed())?;
+        writer.write_all(&self.delta_g1.to_uncompressed())?;
+        writer.write_all(&self.delta_g2.to_uncompressed())?;
+        writer.write_all(&(self.ic.len() as u32).to_be_bytes())?;
+        for ic in &self.ic {
+            writer.write_all(&ic.to_uncompressed())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A verifying key with the pairing of `alpha` and `beta` and the line functions of
+/// `-gamma` and `-delta` precomputed.
+#[derive(Debug, Clone)]
+pub struct PreparedVerifyingKey {
+    /// `e(alpha, beta)`.
+    pub alpha_g1_beta_g2: Fp12,
+    /// `-gamma`, prepared for the Miller loop.
+    pub neg_gamma_g2: G2Prepared,
+    /// `-delta`, prepared for the Miller loop.
+    pub neg_delta_g2: G2Prepared,
+    pub ic: Vec<G1Affine>,
+}
+
+impl From<&VerifyingKey> for PreparedVerifyingKey {
+    fn from(vk: &VerifyingKey) -> Self {
+        prepare_verifying_key(vk)
+    }
+}
+
+/// Precomputes the parts of the verification equation that only depend on `vk`.
+pub fn prepare_verifying_key(vk: &VerifyingKey) -> PreparedVerifyingKey {
+    let mut neg_gamma = vk.gamma_g2;
+    neg_gamma.negate();
+    let mut neg_delta = vk.delta_g2;
+    neg_delta.negate();
+
+    PreparedVerifyingKey {
+        alpha_g1_beta_g2: vk.alpha_g1.pairing_with(&vk.beta_g2),
+        neg_gamma_g2: neg_gamma.prepare(),
+        neg_delta_g2: neg_delta.prepare(),
+        ic: vk.ic.clone(),
+    }
+}
+
+/// A Groth16 proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+impl Proof {
+    /// Size in bytes of the compressed encoding.
+    pub const SIZE: usize = 48 + 96 + 48;
+
+    /// Reads a proof of compressed points `A`, `B` and `C`, checking that they are
+    /// in their subgroup and not at infinity.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut g1 = [0u8; 48];
+        let mut g2 = [0u8; 96];
+
+        reader.read_exact(&mut g1)?;
+        let a = non_zero(G1Affine::from_compressed(&g1)?)?;
+        reader.read_exact(&mut g2)?;
+        let b = non_zero(G2Affine::from_compressed(&g2)?)?;
+        reader.read_exact(&mut g1)?;
+        let c = non_zero(G1Affine::from_compressed(&g1)?)?;
+
+        Ok(Proof { a, b, c })
+    }
+
+    /// Writes the proof as compressed points `A`, `B` and `C`.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.a.to_compressed())?;
+        writer.write_all(&self.b.to_compressed())?;
+        writer.write_all(&self.c.to_compressed())
+    }
+}
+
+/// Verifies a proof for the given public inputs.
+///
+/// Fails with `LengthMismatch` if the number of inputs does not match the key, and
+/// with `VerifyFailed` if the proof is invalid.
+pub fn verify_proof(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+) -> Result<(), Error> {
+    if public_inputs.len() + 1 != pvk.ic.len() {
+        return Err(Error::LengthMismatch);
+    }
+
+    let mut acc = G1Projective::multi_exp_vartime(&pvk.ic[1..], public_inputs);
+    acc.add_assign_mixed(&pvk.ic[0]);
+    let acc = acc.into_affine();
+
+    // e(A, B) e(acc, -gamma) e(C, -delta) = e(alpha, beta)
+    let b = proof.b.prepare();
+    let ml = Bls12::miller_loop(&[
+        (&proof.a, &b),
+        (&acc, &pvk.neg_gamma_g2),
+        (&proof.c, &pvk.neg_delta_g2),
+    ]);
+
+    match Bls12::final_exponentiation(&ml) {
+        Some(gt) if gt == pvk.alpha_g1_beta_g2 => Ok(()),
+        _ => Err(Error::VerifyFailed),
+    }
+}
+
+/// Errors returned by `verify_proofs_batch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchVerifyError {
+    /// There are not as many input vectors as proofs.
+    LengthMismatch,
+    /// The proofs at these indices, in increasing order, are invalid.
+    InvalidProofs(Vec<usize>),
+}
+
+impl fmt::Display for BatchVerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchVerifyError::LengthMismatch => write!(f, "{}", Error::LengthMismatch),
+            BatchVerifyError::InvalidProofs(indices) => {
+                write!(f, "invalid proofs at indices {:?}", indices)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchVerifyError {}
+
+/// Verifies many proofs against the same key, with `public_inputs[i]` the inputs
+/// of `proofs[i]`.
+///
+/// With random `r_i`, the batch is accepted if
+/// `prod e(r_i A_i, B_i) = e(alpha, beta)^(sum r_i) e(sum r_i acc_i, gamma)
+/// e(sum r_i C_i, delta)`, which costs a Miller loop per proof, two more and a
+/// single final exponentiation. If the check fails, the proofs are verified one by
+/// one to find the invalid ones, which include those with the wrong number of
+/// inputs.
+pub fn verify_proofs_batch<R: RngCore>(
+    pvk: &PreparedVerifyingKey,
+    proofs: &[Proof],
+    public_inputs: &[&[Scalar]],
+    rng: &mut R,
+) -> Result<(), BatchVerifyError> {
+    if proofs.len() != public_inputs.len() {
+        return Err(BatchVerifyError::LengthMismatch);
+    }
+
+    let well_formed = public_inputs
+        .iter()
+        .all(|inputs| inputs.len() + 1 == pvk.ic.len());
+    if well_formed && batch_holds(pvk, proofs, public_inputs, rng) {
+        return Ok(());
+    }
+
+    let invalid = proofs
+        .iter()
+        .zip(public_inputs)
+        .enumerate()
+        .filter(|(_, (proof, inputs))| verify_proof(pvk, proof, inputs).is_err())
+        .map(|(i, _)| i)
+        .collect();
+    Err(BatchVerifyError::InvalidProofs(invalid))
+}
+
+fn batch_holds<R: RngCore>(
+    pvk: &PreparedVerifyingKey,
+    proofs: &[Proof],
+    public_inputs: &[&[Scalar]],
+    rng: &mut R,
+) -> bool {
+    if proofs.is_empty() {
+        return true;
+    }
+
+    let r: Vec<Scalar> = proofs.iter().map(|_| Scalar::random(rng)).collect();
+
+    // The coefficients of IC in sum r_i acc_i.
+    let mut coeffs = vec![Scalar::zero(); pvk.ic.len()];
+    for (r, inputs) in r.iter().zip(public_inputs) {
+        coeffs[0].add_assign(r);
+        for (coeff, input) in coeffs[1..].iter_mut().zip(inputs.iter()) {
+            let mut t = *input;
+            t.mul_assign(r);
+            coeff.add_assign(&t);
+        }
+    }
+    let acc = G1Projective::multi_exp_vartime(&pvk.ic, &coeffs).into_affine();
+
+    let c: Vec<G1Affine> = proofs.iter().map(|proof| proof.c).collect();
+    let c = G1Projective::multi_exp_vartime(&c, &r).into_affine();
+
+    let mut a: Vec<G1Projective> = proofs
+        .iter()
+        .zip(&r)
+        .map(|(proof, r)| proof.a.into_projective().mul_vartime(r))
+        .collect();
+    G1Projective::batch_normalization(&mut a);
+    let a: Vec<G1Affine> = a.iter().map(|a| a.into_affine()).collect();
+    let b: Vec<G2Prepared> = proofs.iter().map(|proof| proof.b.prepare()).collect();
+
+    let mut pairs: Vec<(&G1Affine, &G2Prepared)> = a.iter().zip(&b).collect();
+    pairs.push((&acc, &pvk.neg_gamma_g2));
+    pairs.push((&c, &pvk.neg_delta_g2));
+    let ml = Bls12::miller_loop(&pairs);
+
+    let expected = pvk.alpha_g1_beta_g2.pow(coeffs[0].into_repr());
+    Bls12::final_exponentiation(&ml) == Some(expected)
+}
+
+fn non_zero<G: CurveAffine>(p: G) -> io::Result<G> {
+    if p.is_zero() {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "point at infinity",
+        ))
+    } else {
+        Ok(p)
+    }
+}
+
+fn read_g1_uncompressed<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+    let mut bytes = [0u8; 96];
+    reader.read_exact(&mut bytes)?;
+    non_zero(G1Affine::from_uncompressed(&bytes)?)
+}
+
+fn read_g2_uncompressed<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+    let mut bytes = [0u8; 192];
+    reader.read_exact(&mut bytes)?;
+    non_zero(G2Affine::from_uncompressed(&bytes)?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// The trapdoor of a verifying key, which allows simulating proofs.
+    pub(crate) struct Trapdoor {
+        alpha: Scalar,
+        beta: Scalar,
+        gamma: Scalar,
+        delta: Scalar,
+        ic: Vec<Scalar>,
+    }
+
+    pub(crate) fn setup<R: RngCore>(num_inputs: usize, rng: &mut R) -> (VerifyingKey, Trapdoor) {
+        let td = Trapdoor {
+            alpha: Scalar::random(rng),
+            beta: Scalar::random(rng),
+            gamma: Scalar::random(rng),
+            delta: Scalar::random(rng),
+            ic: (0..=num_inputs).map(|_| Scalar::random(rng)).collect(),
+        };
+        let g1 = G1Affine::one();
+        let g2 = G2Affine::one();
+        let vk = VerifyingKey {
+            alpha_g1: g1.mul(td.alpha).into_affine(),
+            beta_g1: g1.mul(td.beta).into_affine(),
+            beta_g2: g2.mul(td.beta).into_affine(),
+            gamma_g2: g2.mul(td.gamma).into_affine(),
+            delta_g1: g1.mul(td.delta).into_affine(),
+            delta_g2: g2.mul(td.delta).into_affine(),
+            ic: td.ic.iter().map(|s| g1.mul(*s).into_affine()).collect(),
+        };
+
+        (vk, td)
+    }
+
+    /// Simulates a valid proof for the inputs with random `A` and `B`.
+    pub(crate) fn simulate<R: RngCore>(td: &Trapdoor, inputs: &[Scalar], rng: &mut R) -> Proof {
+        let a = Scalar::random(rng);
+        let b = Scalar::random(rng);
+
+        // a b = alpha beta + gamma (ic_0 + sum x_i ic_(i + 1)) + delta c
+        let mut x = td.ic[0];
+        for (ic, input) in td.ic[1..].iter().zip(inputs) {
+            let mut t = *ic;
+            t.mul_assign(input);
+            x.add_assign(&t);
+        }
+        x.mul_assign(&td.gamma);
+        let mut c = a;
+        c.mul_assign(&b);
+        let mut alpha_beta = td.alpha;
+        alpha_beta.mul_assign(&td.beta);
+        c.sub_assign(&alpha_beta);
+        c.sub_assign(&x);
+        c.mul_assign(&td.delta.inverse().unwrap());
+
+        Proof {
+            a: G1Affine::one().mul(a).into_affine(),
+            b: G2Affine::one().mul(b).into_affine(),
+            c: G1Affine::one().mul(c).into_affine(),
+        }
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for num_inputs in &[0, 1, 5] {
+            let (vk, td) = setup(*num_inputs, &mut rng);
+            let pvk = prepare_verifying_key(&vk);
+            let inputs: Vec<_> = (0..*num_inputs).map(|_| Scalar::random(&mut rng)).collect();
+            let proof = simulate(&td, &inputs, &mut rng);
+            assert_eq!(verify_proof(&pvk, &proof, &inputs), Ok(()));
+
+            let mut bad = proof;
+            bad.c = bad.a;
+            assert_eq!(verify_proof(&pvk, &bad, &inputs), Err(Error::VerifyFailed));
+
+            if *num_inputs > 0 {
+                let mut wrong = inputs.clone();
+                wrong[0].add_assign(&Scalar::one());
+                assert_eq!(verify_proof(&pvk, &proof, &wrong), Err(Error::VerifyFailed));
+            }
+
+            let mut extra = inputs.clone();
+            extra.push(Scalar::one());
+            assert_eq!(
+                verify_proof(&pvk, &proof, &extra),
+                Err(Error::LengthMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_proofs_batch() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let (vk, td) = setup(2, &mut rng);
+        let pvk = prepare_verifying_key(&vk);
+        let inputs: Vec<Vec<Scalar>> = (0..5)
+            .map(|_| (0..2).map(|_| Scalar::random(&mut rng)).collect())
+            .collect();
+        let mut proofs: Vec<Proof> = inputs
+            .iter()
+            .map(|inputs| simulate(&td, inputs, &mut rng))
+            .collect();
+        let mut inputs: Vec<&[Scalar]> = inputs.iter().map(|inputs| &inputs[..]).collect();
+
+        assert_eq!(verify_proofs_batch(&pvk, &[], &[], &mut rng), Ok(()));
+        assert_eq!(
+            verify_proofs_batch(&pvk, &proofs, &inputs, &mut rng),
+            Ok(())
+        );
+        assert_eq!(
+            verify_proofs_batch(&pvk, &proofs[1..], &inputs, &mut rng),
+            Err(BatchVerifyError::LengthMismatch)
+        );
+
+        proofs.swap(1, 3);
+        assert_eq!(
+            verify_proofs_batch(&pvk, &proofs, &inputs, &mut rng),
+            Err(BatchVerifyError::InvalidProofs(vec![1, 3]))
+        );
+        proofs.swap(1, 3);
+
+        let short = [Scalar::one()];
+        inputs[4] = &short;
+        assert_eq!(
+            verify_proofs_batch(&pvk, &proofs, &inputs, &mut rng),
+            Err(BatchVerifyError::InvalidProofs(vec![4]))
+        );
+    }
+
+    #[test]
+    fn test_encodings() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let (vk, td) = setup(3, &mut rng);
+        let mut bytes = Vec::new();
+        vk.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 3 * 96 + 3 * 192 + 4 + 4 * 96);
+        assert_eq!(VerifyingKey::read(&bytes[..]).unwrap(), vk);
+        assert!(VerifyingKey::read(&bytes[..bytes.len() - 1]).is_err());
+
+        let inputs = [Scalar::one(), Scalar::zero(), Scalar::random(&mut rng)];
+        let proof = simulate(&td, &inputs, &mut rng);
+        let mut bytes = Vec::new();
+        proof.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), Proof::SIZE);
+        let read = Proof::read(&bytes[..]).unwrap();
+        assert_eq!(read, proof);
+        assert_eq!(verify_proof(&(&vk).into(), &read, &inputs), Ok(()));
+
+        bytes[..48].copy_from_slice(&G1Affine::zero().to_compressed());
+        assert!(Proof::read(&bytes[..]).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! Hexadecimal strings, as used by the text formats of trusted setups.
+
+/// Decodes `hex` into `out`, failing unless it has exactly `2 * out.len()`
+/// hexadecimal digits.
+pub(crate) fn decode(hex: &str, out: &mut [u8]) -> Option<()> {
+    let hex = hex.as_bytes();
+    if hex.len() != 2 * out.len() {
+        return None;
+    }
+
+    let nibble = |c: u8| match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    };
+    for (byte, pair) in out.iter_mut().zip(hex.chunks(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+
+    Some(())
+}
+
+/// Encodes `bytes` as lowercase hexadecimal digits.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut res = String::with_capacity(2 * bytes.len());
+    for b in bytes {
+        res.push(DIGITS[(b >> 4) as usize] as char);
+        res.push(DIGITS[(b & 0xf) as usize] as char);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hexstr() {
+        let mut out = [0; 4];
+        assert_eq!(decode("00ff7A1b", &mut out), Some(()));
+        assert_eq!(out, [0x00, 0xff, 0x7a, 0x1b]);
+        assert_eq!(encode(&out), "00ff7a1b");
+        assert_eq!(encode(&[]), "");
+
+        assert_eq!(decode("00ff7a", &mut out), None);
+        assert_eq!(decode("00ff7a1b00", &mut out), None);
+        assert_eq!(decode("00ff7g1b", &mut out), None);
+        assert_eq!(decode("0x00ff7a", &mut out), None);
+    }
+}
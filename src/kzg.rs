@@ -0,0 +1,345 @@
+//! KZG polynomial commitments over $\mathbb{G}_1$, with the secret point in
+//! $\mathbb{G}_2$.
+
+use fff::Field;
+use groupy::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+
+use crate::domain::bit_reverse;
+use crate::{
+    multi_pairing, Error, EvaluationDomain, Fp12, G1Affine, G1Projective, G2Affine, Polynomial,
+    Scalar,
+};
+
+/// A structured reference string for polynomials of degree at most `max_degree`:
+/// the powers `[tau^i]_1` for `i` in `0..=max_degree`, and `[tau]_2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KzgSrs {
+    g1_powers: Vec<G1Affine>,
+    g2_tau: G2Affine,
+}
+
+impl KzgSrs {
+    /// Creates a reference string from the powers of `tau` in $\mathbb{G}_1$,
+    /// starting from the generator, and `[tau]_2`.
+    ///
+    /// The points are not checked to be consistent, they should come from a trusted
+    /// setup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `g1_powers` is empty.
+    pub fn new(g1_powers: Vec<G1Affine>, g2_tau: G2Affine) -> Self {
+        assert!(
+            !g1_powers.is_empty(),
+            "the reference string must not be empty"
+        );
+
+        KzgSrs { g1_powers, g2_tau }
+    }
+
+    /// Creates a reference string from a random `tau`, which is then discarded.
+    ///
+    /// Whoever observes `tau` can forge openings, so this is for testing only.
+    pub fn setup_insecure<R: RngCore>(max_degree: usize, rng: &mut R) -> Self {
+        let tau = Scalar::random(rng);
+        let mut powers = Vec::with_capacity(max_degree + 1);
+        let mut cur = G1Projective::one();
+        for _ in 0..=max_degree {
+            powers.push(cur);
+            cur *= tau;
+        }
+        G1Projective::batch_normalization(&mut powers);
+
+        KzgSrs {
+            g1_powers: powers.iter().map(|p| p.into_affine()).collect(),
+            g2_tau: (G2Affine::one() * tau).into_affine(),
+        }
+    }
+
+    /// Returns the maximum degree of the polynomials that can be committed to.
+    pub fn max_degree(&self) -> usize {
+        self.g1_powers.len() - 1
+    }
+
+    /// Returns the powers `[tau^i]_1`.
+    pub fn g1_powers(&self) -> &[G1Affine] {
+        &self.g1_powers
+    }
+
+    /// Returns `[tau]_2`.
+    pub fn g2_tau(&self) -> &G2Affine {
+        &self.g2_tau
+    }
+
+    /// Returns the Lagrange basis `[L_i(tau)]_1` of the domain of at least
+    /// `min_size` elements, computed with an inverse FFT of the powers of `tau`.
+    ///
+    /// Committing to evaluations over the domain with this basis gives the
+    /// commitment to the interpolated polynomial. With `bit_reversed`, the points
+    /// are permuted to bit-reversed order, as in EIP-4844 setups.
+    ///
+    /// Fails with `PolynomialDegreeTooLarge` if the domain is larger than the
+    /// reference string.
+    pub fn lagrange_basis(
+        &self,
+        min_size: usize,
+        bit_reversed: bool,
+    ) -> Result<Vec<G1Affine>, Error> {
+        let domain = EvaluationDomain::new(min_size)?;
+        if domain.size() > self.g1_powers.len() {
+            return Err(Error::PolynomialDegreeTooLarge);
+        }
+
+        let mut points: Vec<G1Projective> = self.g1_powers[..domain.size()]
+            .iter()
+            .map(|p| p.into_projective())
+            .collect();
+        domain.ifft_group(&mut points);
+        if bit_reversed {
+            bit_reverse(&mut points);
+        }
+        G1Projective::batch_normalization(&mut points);
+
+        Ok(points.iter().map(|p| p.into_affine()).collect())
+    }
+
+    /// Commits to `p`, computing `[p(tau)]_1`.
+    ///
+    /// Fails with `PolynomialDegreeTooLarge` if the degree of `p` exceeds
+    /// `max_degree`.
+    pub fn commit(&self, p: &Polynomial) -> Result<G1Affine, Error> {
+        let coeffs = p.coeffs();
+        if coeffs.len() > self.g1_powers.len() {
+            return Err(Error::PolynomialDegreeTooLarge);
+        }
+
+        Ok(G1Projective::multi_exp_vartime(&self.g1_powers[..coeffs.len()], coeffs).into_affine())
+    }
+
+    /// Opens `p` at `z`, returning the value `p(z)` and the proof, a commitment to
+    /// `(p - p(z)) / (X - z)`.
+    pub fn open(&self, p: &Polynomial, z: &Scalar) -> Result<(Scalar, G1Affine), Error> {
+        let (quotient, value) = p.divide_by_linear(z);
+        Ok((value, self.commit(&quotient)?))
+    }
+
+    /// Verifies that the polynomial committed to by `commitment` takes the value
+    /// `value` at `z`.
+    ///
+    /// This checks `e(C - [v]_1 + z proof, [1]_2) = e(proof, [tau]_2)`.
+    pub fn verify(
+        &self,
+        commitment: &G1Affine,
+        z: &Scalar,
+        value: &Scalar,
+        proof: &G1Affine,
+    ) -> Result<(), Error> {
+        self.check_opening(commitment.into_projective(), z, value, proof)
+    }
+
+    /// Opens several polynomials at the same point `z` with a single proof for their
+    /// linear combination with the powers of `challenge`, returning the values and
+    /// the proof.
+    ///
+    /// The challenge must be chosen after the commitments, typically by hashing
+    /// them.
+    pub fn open_batch(
+        &self,
+        polys: &[Polynomial],
+        z: &Scalar,
+        challenge: &Scalar,
+    ) -> Result<(Vec<Scalar>, G1Affine), Error> {
+        let values = polys.iter().map(|p| p.evaluate(z)).collect();
+
+        let mut combined = Polynomial::zero();
+        for p in polys.iter().rev() {
+            combined = &(&combined * challenge) + p;
+        }
+        let (_, proof) = self.open(&combined, z)?;
+
+        Ok((values, proof))
+    }
+
+    /// Verifies a proof returned by `open_batch` that the polynomials committed to by
+    /// `commitments` take the values `values` at `z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `commitments` and `values` have different lengths.
+    pub fn verify_batch(
+        &self,
+        commitments: &[G1Affine],
+        z: &Scalar,
+        values: &[Scalar],
+        proof: &G1Affine,
+        challenge: &Scalar,
+    ) -> Result<(), Error> {
+        assert_eq!(commitments.len(), values.len(), "lengths must match");
+
+        let powers: Vec<Scalar> =
+            core::iter::successors(Some(Scalar::one()), |p| Some(p * challenge))
+                .take(values.len())
+                .collect();
+        let commitment = G1Projective::multi_exp_vartime(commitments, &powers);
+        let value = values
+            .iter()
+            .zip(&powers)
+            .fold(Scalar::zero(), |acc, (v, p)| acc + v * p);
+
+        self.check_opening(commitment, z, &value, proof)
+    }
+
+    fn check_opening(
+        &self,
+        commitment: G1Projective,
+        z: &Scalar,
+        value: &Scalar,
+        proof: &G1Affine,
+    ) -> Result<(), Error> {
+        // e(C - [v]_1 + z proof, -[1]_2) e(proof, [tau]_2) = 1
+        let lhs = G1Projective::multi_exp_vartime(
+            &[commitment.into_affine(), G1Affine::one(), *proof],
+            &[Scalar::one(), -value, *z],
+        )
+        .into_affine();
+        let mut neg_g2 = G2Affine::one();
+        neg_g2.negate();
+
+        if multi_pairing(&[(&lhs, &neg_g2), (proof, &self.g2_tau)]) == Fp12::one() {
+            Ok(())
+        } else {
+            Err(Error::VerifyFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_kzg_open() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(31, &mut rng);
+        assert_eq!(srs.max_degree(), 31);
+
+        for degree in &[0, 1, 10, 31] {
+            let p = Polynomial::random(*degree, &mut rng);
+            let commitment = srs.commit(&p).unwrap();
+            let z = Scalar::random(&mut rng);
+            let (value, proof) = srs.open(&p, &z).unwrap();
+            assert_eq!(value, p.evaluate(&z));
+            assert_eq!(srs.verify(&commitment, &z, &value, &proof), Ok(()));
+
+            let wrong = value + Scalar::one();
+            assert_eq!(
+                srs.verify(&commitment, &z, &wrong, &proof),
+                Err(Error::VerifyFailed)
+            );
+            // Constant polynomials open to their value anywhere.
+            if *degree > 0 {
+                let other = Scalar::random(&mut rng);
+                assert_eq!(
+                    srs.verify(&commitment, &other, &value, &proof),
+                    Err(Error::VerifyFailed)
+                );
+            }
+        }
+
+        // Zero polynomial and too large degrees.
+        let (value, proof) = srs.open(&Polynomial::zero(), &Scalar::one()).unwrap();
+        assert!(value.is_zero());
+        assert!(proof.is_zero());
+        assert_eq!(srs.commit(&Polynomial::zero()), Ok(G1Affine::zero()));
+        assert_eq!(
+            srs.commit(&Polynomial::random(32, &mut rng)),
+            Err(Error::PolynomialDegreeTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_kzg_open_batch() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(15, &mut rng);
+        let polys: Vec<Polynomial> = (0..4)
+            .map(|i| Polynomial::random(5 * i, &mut rng))
+            .collect();
+        let commitments: Vec<G1Affine> = polys.iter().map(|p| srs.commit(p).unwrap()).collect();
+        let z = Scalar::random(&mut rng);
+        let challenge = Scalar::random(&mut rng);
+
+        let (values, proof) = srs.open_batch(&polys, &z, &challenge).unwrap();
+        for (p, v) in polys.iter().zip(&values) {
+            assert_eq!(p.evaluate(&z), *v);
+        }
+        assert_eq!(
+            srs.verify_batch(&commitments, &z, &values, &proof, &challenge),
+            Ok(())
+        );
+
+        let mut wrong = values.clone();
+        wrong[2] += Scalar::one();
+        assert_eq!(
+            srs.verify_batch(&commitments, &z, &wrong, &proof, &challenge),
+            Err(Error::VerifyFailed)
+        );
+        let other = Scalar::random(&mut rng);
+        assert_eq!(
+            srs.verify_batch(&commitments, &z, &values, &proof, &other),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_kzg_lagrange_basis() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(31, &mut rng);
+        let domain = EvaluationDomain::new(32).unwrap();
+        let lagrange = srs.lagrange_basis(32, false).unwrap();
+
+        // Committing to evaluations is committing to the interpolated polynomial.
+        let p = Polynomial::random(31, &mut rng);
+        let mut evals = p.coeffs().to_vec();
+        domain.fft(&mut evals);
+        assert_eq!(
+            G1Projective::multi_exp_vartime(&lagrange, &evals).into_affine(),
+            srs.commit(&p).unwrap()
+        );
+
+        let mut reversed = lagrange.clone();
+        bit_reverse(&mut reversed);
+        assert_eq!(srs.lagrange_basis(32, true).unwrap(), reversed);
+
+        // Smaller domains use a prefix of the powers.
+        let lagrange = srs.lagrange_basis(5, false).unwrap();
+        assert_eq!(lagrange.len(), 8);
+        let p = Polynomial::random(7, &mut rng);
+        let mut evals = p.coeffs().to_vec();
+        EvaluationDomain::new(8).unwrap().fft(&mut evals);
+        assert_eq!(
+            G1Projective::multi_exp_vartime(&lagrange, &evals).into_affine(),
+            srs.commit(&p).unwrap()
+        );
+
+        assert_eq!(
+            srs.lagrange_basis(64, false),
+            Err(Error::PolynomialDegreeTooLarge)
+        );
+    }
+}
@@ -8,26 +8,52 @@
 #[macro_use]
 mod macros;
 
+pub mod aggregation;
+mod batch;
+pub mod bbs;
+pub mod ceremony;
+mod domain;
+pub mod eip4844;
+mod error;
+mod fk20;
 mod fp;
 mod fp12;
 mod fp2;
 mod fp6;
 mod g1;
 mod g2;
+pub mod groth16;
+mod hexstr;
+mod kzg;
 mod pairing;
+pub mod plonk;
+mod polynomial;
+pub mod powers_of_tau;
 mod scalar;
+mod secret;
+pub mod sigma;
 mod traits;
+mod transcript;
+mod vartime;
 
+pub use batch::BatchInvert;
+pub use domain::EvaluationDomain;
+pub use error::Error;
 pub use fff::*;
+pub use fk20::Fk20;
 pub use fp::{Fp, FpRepr};
 pub use fp12::Fp12;
 pub use fp2::Fp2;
 pub use fp6::Fp6;
 pub use g1::*;
 pub use g2::*;
+pub use kzg::KzgSrs;
 pub use pairing::*;
+pub use polynomial::Polynomial;
 pub use scalar::{Scalar, ScalarRepr, S as SCALAR_S};
+pub use secret::{secret_scalar_serde, SecretScalar};
 pub use traits::*;
+pub use transcript::Transcript;
 
 mod serde_impl;
 
@@ -87,10 +113,14 @@ impl Engine for Bls12 {
     }
 
     fn final_exponentiation(r: &Fp12) -> Option<Fp12> {
+        // Zero is not invertible, so the easy part of the exponentiation is undefined.
+        if r.is_zero() {
+            return None;
+        }
+
         let mut out = blst::blst_fp12::default();
         unsafe { blst::blst_final_exp(&mut out, &r.0) };
 
-        // TODO: What about the None case?
         Some(out.into())
     }
 }
@@ -1,15 +1,15 @@
 macro_rules! impl_add_binop_specify_output {
     ($lhs:ident, $rhs:ident, $output:ident) => {
-        impl<'b> Add<&'b $rhs> for $lhs {
+        impl Add<&$rhs> for $lhs {
             type Output = $output;
 
             #[inline]
-            fn add(self, rhs: &'b $rhs) -> $output {
+            fn add(self, rhs: &$rhs) -> $output {
                 &self + rhs
             }
         }
 
-        impl<'a> Add<$rhs> for &'a $lhs {
+        impl Add<$rhs> for &$lhs {
             type Output = $output;
 
             #[inline]
@@ -31,16 +31,16 @@ macro_rules! impl_add_binop_specify_output {
 
 macro_rules! impl_sub_binop_specify_output {
     ($lhs:ident, $rhs:ident, $output:ident) => {
-        impl<'b> Sub<&'b $rhs> for $lhs {
+        impl Sub<&$rhs> for $lhs {
             type Output = $output;
 
             #[inline]
-            fn sub(self, rhs: &'b $rhs) -> $output {
+            fn sub(self, rhs: &$rhs) -> $output {
                 &self - rhs
             }
         }
 
-        impl<'a> Sub<$rhs> for &'a $lhs {
+        impl Sub<$rhs> for &$lhs {
             type Output = $output;
 
             #[inline]
@@ -69,16 +69,16 @@ macro_rules! impl_binops_additive_specify_output {
 
 macro_rules! impl_binops_multiplicative_mixed {
     ($lhs:ident, $rhs:ident, $output:ident) => {
-        impl<'b> Mul<&'b $rhs> for $lhs {
+        impl Mul<&$rhs> for $lhs {
             type Output = $output;
 
             #[inline]
-            fn mul(self, rhs: &'b $rhs) -> $output {
+            fn mul(self, rhs: &$rhs) -> $output {
                 &self * rhs
             }
         }
 
-        impl<'a> Mul<$rhs> for &'a $lhs {
+        impl Mul<$rhs> for &$lhs {
             type Output = $output;
 
             #[inline]
@@ -116,16 +116,16 @@ macro_rules! impl_binops_additive {
             }
         }
 
-        impl<'b> SubAssign<&'b $rhs> for $lhs {
+        impl SubAssign<&$rhs> for $lhs {
             #[inline]
-            fn sub_assign(&mut self, rhs: &'b $rhs) {
+            fn sub_assign(&mut self, rhs: &$rhs) {
                 <Self as $source>::sub_assign(self, rhs);
             }
         }
 
-        impl<'b> AddAssign<&'b $rhs> for $lhs {
+        impl AddAssign<&$rhs> for $lhs {
             #[inline]
-            fn add_assign(&mut self, rhs: &'b $rhs) {
+            fn add_assign(&mut self, rhs: &$rhs) {
                 <Self as $source>::add_assign(self, rhs);
             }
         }
@@ -150,16 +150,16 @@ macro_rules! impl_binops_additive_mixed {
             }
         }
 
-        impl<'b> SubAssign<&'b $rhs> for $lhs {
+        impl SubAssign<&$rhs> for $lhs {
             #[inline]
-            fn sub_assign(&mut self, rhs: &'b $rhs) {
+            fn sub_assign(&mut self, rhs: &$rhs) {
                 *self = &*self - rhs;
             }
         }
 
-        impl<'b> AddAssign<&'b $rhs> for $lhs {
+        impl AddAssign<&$rhs> for $lhs {
             #[inline]
-            fn add_assign(&mut self, rhs: &'b $rhs) {
+            fn add_assign(&mut self, rhs: &$rhs) {
                 <Self as $source>::add_assign_mixed(self, rhs);
             }
         }
@@ -177,9 +177,9 @@ macro_rules! impl_binops_multiplicative {
             }
         }
 
-        impl<'b> MulAssign<&'b $rhs> for $lhs {
+        impl MulAssign<&$rhs> for $lhs {
             #[inline]
-            fn mul_assign(&mut self, rhs: &'b $rhs) {
+            fn mul_assign(&mut self, rhs: &$rhs) {
                 <Self as $source>::mul_assign(self, rhs);
             }
         }
@@ -207,7 +207,7 @@ macro_rules! encoded_point_delegations {
         impl Eq for $t {}
         impl PartialOrd for $t {
             fn partial_cmp(&self, other: &$t) -> Option<::core::cmp::Ordering> {
-                PartialOrd::partial_cmp(&self.0[..], &other.0[..])
+                Some(self.cmp(other))
             }
         }
         impl Ord for $t {
@@ -1,6 +1,12 @@
-use crate::{Fp12, G1Affine, G2Affine};
+use core::{convert::TryInto, marker::PhantomData};
+use std::io::{self, Read, Write};
+
+use crate::{Error, Fp, Fp12, G1Affine, G1Projective, G2Affine, G2Projective};
 
 use blst::*;
+use fff::Field;
+use groupy::CurveAffine;
+use sha2::{Digest, Sha256};
 
 /// Execute a complete pairing operation `(p, q)`.
 pub fn pairing(p: G1Affine, q: G2Affine) -> Fp12 {
@@ -13,28 +19,316 @@ pub fn pairing(p: G1Affine, q: G2Affine) -> Fp12 {
     out.into()
 }
 
+/// Computes the product of the pairings `(p_i, q_i)`, sharing a single final
+/// exponentiation.
+///
+/// Pairs where either element is the point at infinity contribute one.
+pub fn multi_pairing(pairs: &[(&G1Affine, &G2Affine)]) -> Fp12 {
+    let mut acc = Fp12::one().0;
+    for (p, q) in pairs {
+        if p.is_zero() || q.is_zero() {
+            continue;
+        }
+
+        let mut tmp = blst_fp12::default();
+        unsafe {
+            blst_miller_loop(&mut tmp, &q.0, &p.0);
+            blst_fp12_mul(&mut acc, &acc, &tmp);
+        }
+    }
+
+    let mut out = blst_fp12::default();
+    unsafe { blst_final_exp(&mut out, &acc) };
+
+    out.into()
+}
+
+/// Maximum length of a domain separation tag, as required by hash-to-curve.
+const MAX_DST_LEN: usize = 255;
+
+/// How messages are mapped to the curve by the aggregate pairing contexts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashMode {
+    /// Hash to the curve, producing a uniformly distributed point.
+    Hash,
+    /// Encode to the curve, faster but not uniformly distributed.
+    Encode,
+}
+
+impl Default for HashMode {
+    fn default() -> Self {
+        HashMode::Hash
+    }
+}
+
+/// Version of the binary encoding of pairing context snapshots.
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// Control flags of a blst pairing context, see `aggregate.c`.
+const AGGR_MIN_SIG: u32 = 0x01;
+const AGGR_MIN_PK: u32 = 0x02;
+const AGGR_SIGN_SET: u32 = 0x10;
+const AGGR_GT_SET: u32 = 0x20;
+const AGGR_HASH_OR_ENCODE: u32 = 0x40;
+
+/// Flags of the snapshot encoding.
+const SNAPSHOT_ENCODE: u8 = 0x01;
+const SNAPSHOT_GT: u8 = 0x02;
+const SNAPSHOT_SIG: u8 = 0x04;
+
+/// Leading fields of the blst `PAIRING` structure, which are followed by the
+/// aggregated signature as a Jacobian point.
+///
+/// blst does not export this structure, so the layout is copied from
+/// `aggregate.c` of blst 0.3.3. That is why `Cargo.toml` pins blst to `=0.3.3`,
+/// and `test_pairing_snapshot_layout` checks the offsets against that release so
+/// that a blst upgrade changing them fails loudly.
+#[repr(C)]
+struct PairingHeader {
+    ctrl: u32,
+    nelems: u32,
+    dst: *const u8,
+    dst_len: usize,
+    gt: blst_fp12,
+}
+
+pub(crate) fn write_fp12<W: Write>(gt: &Fp12, writer: &mut W) -> io::Result<()> {
+    for fp6 in &gt.0.fp6 {
+        for fp2 in &fp6.fp2 {
+            for fp in &fp2.fp {
+                writer.write_all(&Fp(*fp).to_bytes_le())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_fp12<R: Read>(reader: &mut R) -> io::Result<Fp12> {
+    let mut gt = blst_fp12::default();
+    let mut buf = [0u8; 48];
+    for fp6 in gt.fp6.iter_mut() {
+        for fp2 in fp6.fp2.iter_mut() {
+            for fp in fp2.fp.iter_mut() {
+                reader.read_exact(&mut buf)?;
+                let mut repr = crate::FpRepr::default();
+                for (limb, chunk) in repr.0.iter_mut().zip(buf.chunks_exact(8)) {
+                    *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+                }
+                *fp = fff::PrimeField::from_repr(repr)
+                    .map(|fp: Fp| fp.0)
+                    .map_err(|_| Error::BadEncoding)?;
+            }
+        }
+    }
+    Ok(Fp12(gt))
+}
+
+/// State of an aggregate pairing context that still accepts aggregations.
+#[derive(Debug)]
+pub enum Aggregating {}
+
+/// State of an aggregate pairing context that can be merged and verified.
+#[derive(Debug)]
+pub enum Committed {}
+
 macro_rules! impl_pairing {
-    ($name:ident, $p:ty, $q:ty, $aggregate:ident, $aggregated:ident) => {
+    (
+        $name:ident,
+        $builder:ident,
+        $snapshot:ident,
+        $p:ty,
+        $q:ident,
+        $q_proj:ident,
+        $q_raw:ty,
+        $q_size:expr,
+        $kind:expr,
+        $aggregate:ident,
+        $aggregated:ident
+    ) => {
         /// Aggregate pairings efficiently.
+        ///
+        /// Contexts are created with `builder`, accept aggregations until they are
+        /// committed, and can only be merged and verified once committed.
         #[derive(Debug)]
-        pub struct $name {
+        pub struct $name<S = Aggregating> {
+            /// The blst context, followed by a copy of the domain separation tag.
             v: Box<[u64]>,
+            dst_len: usize,
+            _state: PhantomData<S>,
+        }
+
+        /// Builder for a new aggregate pairing context.
+        #[derive(Debug, Clone)]
+        pub struct $builder<'a> {
+            mode: HashMode,
+            dst: &'a [u8],
+        }
+
+        /// The accumulated state of a committed context: the product of its Miller
+        /// loops, the aggregated signature and the hash of its domain separation tag.
+        ///
+        /// Snapshots are taken with `snapshot`, and restored with `restore` in order to
+        /// be merged with contexts of other processes.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $snapshot {
+            mode: HashMode,
+            dst_hash: [u8; 32],
+            gt: Option<Fp12>,
+            sig: Option<$q>,
         }
 
-        impl $name {
-            pub fn new(hash_or_encode: bool, dst: &[u8]) -> Self {
-                let v: Vec<u64> = vec![0; unsafe { blst_pairing_sizeof() } / 8];
-                let mut obj = Self {
+        impl $snapshot {
+            /// Returns how messages were mapped to the curve.
+            pub fn hash_mode(&self) -> HashMode {
+                self.mode
+            }
+
+            /// Returns the SHA-256 hash of the domain separation tag.
+            pub fn dst_hash(&self) -> &[u8; 32] {
+                &self.dst_hash
+            }
+
+            /// Returns the product of the Miller loops, if any pairing was aggregated.
+            pub fn miller_loop(&self) -> Option<&Fp12> {
+                self.gt.as_ref()
+            }
+
+            /// Returns the aggregated signature, if any signature was aggregated.
+            pub fn signature(&self) -> Option<&$q> {
+                self.sig.as_ref()
+            }
+
+            /// Size of the binary encoding in bytes.
+            pub fn raw_fmt_size(&self) -> usize {
+                let mut size = 2 + 32;
+                if self.gt.is_some() {
+                    size += 12 * 48;
+                }
+                if self.sig.is_some() {
+                    size += $q_size;
+                }
+                size
+            }
+
+            /// Writes the versioned binary encoding of this snapshot.
+            ///
+            /// The encoding is a version byte, a flags byte, the hash of the domain
+            /// separation tag, the Miller loop product as canonical little-endian `Fp`
+            /// coefficients if present, and the compressed signature if present.
+            pub fn write_raw<W: Write>(&self, mut writer: W) -> Result<usize, io::Error> {
+                let mut flags = 0;
+                if self.mode == HashMode::Encode {
+                    flags |= SNAPSHOT_ENCODE;
+                }
+                if self.gt.is_some() {
+                    flags |= SNAPSHOT_GT;
+                }
+                if self.sig.is_some() {
+                    flags |= SNAPSHOT_SIG;
+                }
+                writer.write_all(&[SNAPSHOT_VERSION, flags])?;
+                writer.write_all(&self.dst_hash)?;
+                if let Some(ref gt) = self.gt {
+                    write_fp12(gt, &mut writer)?;
+                }
+                if let Some(ref sig) = self.sig {
+                    writer.write_all(&sig.to_compressed())?;
+                }
+
+                Ok(self.raw_fmt_size())
+            }
+
+            /// Reads a snapshot written by `write_raw`, checking that the signature is
+            /// in the prime order subgroup.
+            ///
+            /// Invalid data fails with an `io::Error` wrapping `Error::BadEncoding`, and
+            /// a signature outside the subgroup with one wrapping `Error::PointNotInGroup`.
+            pub fn read_raw<R: Read>(mut reader: R) -> Result<Self, io::Error> {
+                let mut header = [0u8; 2];
+                reader.read_exact(&mut header)?;
+                if header[0] != SNAPSHOT_VERSION {
+                    return Err(Error::BadEncoding.into());
+                }
+                let flags = header[1];
+                if flags & !(SNAPSHOT_ENCODE | SNAPSHOT_GT | SNAPSHOT_SIG) != 0 {
+                    return Err(Error::BadEncoding.into());
+                }
+
+                let mut dst_hash = [0u8; 32];
+                reader.read_exact(&mut dst_hash)?;
+                let gt = if flags & SNAPSHOT_GT != 0 {
+                    Some(read_fp12(&mut reader)?)
+                } else {
+                    None
+                };
+                let sig = if flags & SNAPSHOT_SIG != 0 {
+                    let mut buf = [0u8; $q_size];
+                    reader.read_exact(&mut buf)?;
+                    Some($q::from_compressed(&buf)?)
+                } else {
+                    None
+                };
+
+                Ok($snapshot {
+                    mode: if flags & SNAPSHOT_ENCODE != 0 {
+                        HashMode::Encode
+                    } else {
+                        HashMode::Hash
+                    },
+                    dst_hash,
+                    gt,
+                    sig,
+                })
+            }
+        }
+
+        impl<'a> $builder<'a> {
+            /// Sets how messages are mapped to the curve, `HashMode::Hash` by default.
+            pub fn hash_mode(mut self, mode: HashMode) -> Self {
+                self.mode = mode;
+                self
+            }
+
+            /// Creates the context, failing if the domain separation tag is empty or
+            /// longer than 255 bytes.
+            pub fn build(self) -> Result<$name<Aggregating>, Error> {
+                if self.dst.is_empty() || self.dst.len() > MAX_DST_LEN {
+                    return Err(Error::InvalidDst);
+                }
+
+                let size = unsafe { blst_pairing_sizeof() };
+                let v: Vec<u64> = vec![0; (size + self.dst.len() + 7) / 8];
+                let mut obj = $name {
                     v: v.into_boxed_slice(),
+                    dst_len: self.dst.len(),
+                    _state: PhantomData,
                 };
-                obj.init(hash_or_encode, dst);
-                obj
+
+                // The tag is stored right after the context, which blst records as a
+                // relative location, so the context stays valid when cloned.
+                let dst = unsafe {
+                    let dst = (obj.v.as_mut_ptr() as *mut u8).add(size);
+                    std::ptr::copy_nonoverlapping(self.dst.as_ptr(), dst, self.dst.len());
+                    dst
+                };
+                let hash_or_encode = self.mode == HashMode::Hash;
+                unsafe { blst_pairing_init(obj.ctx(), hash_or_encode, dst, self.dst.len()) };
+
+                Ok(obj)
             }
+        }
 
-            pub fn init(&mut self, hash_or_encode: bool, dst: &[u8]) {
-                unsafe { blst_pairing_init(self.ctx(), hash_or_encode, dst.as_ptr(), dst.len()) }
+        impl<S> Clone for $name<S> {
+            fn clone(&self) -> Self {
+                $name {
+                    v: self.v.clone(),
+                    dst_len: self.dst_len,
+                    _state: PhantomData,
+                }
             }
+        }
 
+        impl<S> $name<S> {
             fn ctx(&mut self) -> *mut blst_pairing {
                 self.v.as_mut_ptr() as *mut blst_pairing
             }
@@ -43,14 +337,31 @@ macro_rules! impl_pairing {
                 self.v.as_ptr() as *const blst_pairing
             }
 
+            /// Returns the domain separation tag of this context.
+            pub fn dst(&self) -> &[u8] {
+                unsafe {
+                    std::slice::from_raw_parts(blst_pairing_get_dst(self.const_ctx()), self.dst_len)
+                }
+            }
+        }
+
+        impl $name<Aggregating> {
+            /// Starts building a new context using the domain separation tag `dst`.
+            pub fn builder(dst: &[u8]) -> $builder<'_> {
+                $builder {
+                    mode: HashMode::default(),
+                    dst,
+                }
+            }
+
             pub fn aggregate(
                 &mut self,
                 pk: &$p,
                 sig: Option<&$q>,
                 msg: &[u8],
                 aug: &[u8],
-            ) -> Result<(), BLST_ERROR> {
-                let res = unsafe {
+            ) -> Result<(), Error> {
+                Error::from_blst(unsafe {
                     $aggregate(
                         self.ctx(),
                         &pk.0,
@@ -63,33 +374,93 @@ macro_rules! impl_pairing {
                         aug.as_ptr(),
                         aug.len(),
                     )
-                };
-                if res == BLST_ERROR::BLST_SUCCESS {
-                    Ok(())
-                } else {
-                    Err(res)
-                }
+                })
             }
 
             pub fn aggregated(gtsig: &mut Fp12, sig: &$q) {
                 unsafe { $aggregated(&mut gtsig.0, &sig.0) }
             }
 
-            pub fn commit(&mut self) {
-                unsafe { blst_pairing_commit(self.ctx()) }
+            /// Finishes the aggregations, after which the context can be merged and verified.
+            pub fn commit(mut self) -> $name<Committed> {
+                unsafe { blst_pairing_commit(self.ctx()) };
+
+                $name {
+                    v: self.v,
+                    dst_len: self.dst_len,
+                    _state: PhantomData,
+                }
             }
+        }
 
-            pub fn merge(&mut self, ctx1: &Self) -> Result<(), BLST_ERROR> {
-                let res = unsafe { blst_pairing_merge(self.ctx(), ctx1.const_ctx()) };
-                if res == BLST_ERROR::BLST_SUCCESS {
-                    Ok(())
-                } else {
-                    Err(res)
+        impl $name<Committed> {
+            /// Exports the accumulated state, so that it can be restored and merged in
+            /// another process.
+            pub fn snapshot(&self) -> $snapshot {
+                let header = unsafe { &*(self.const_ctx() as *const PairingHeader) };
+                let sig = unsafe {
+                    let sig = (self.v.as_ptr() as *const u8)
+                        .add(core::mem::size_of::<PairingHeader>())
+                        as *const $q_raw;
+                    $q_proj(*sig)
+                };
+
+                $snapshot {
+                    mode: if header.ctrl & AGGR_HASH_OR_ENCODE != 0 {
+                        HashMode::Hash
+                    } else {
+                        HashMode::Encode
+                    },
+                    dst_hash: Sha256::digest(self.dst()).into(),
+                    gt: if header.ctrl & AGGR_GT_SET != 0 {
+                        Some(Fp12(header.gt))
+                    } else {
+                        None
+                    },
+                    sig: if header.ctrl & AGGR_SIGN_SET != 0 {
+                        Some(sig.into())
+                    } else {
+                        None
+                    },
                 }
             }
 
-            pub fn finalverify(&self, gtsig: Option<&Fp12>) -> bool {
-                unsafe {
+            /// Recreates a committed context from a snapshot taken with the domain
+            /// separation tag `dst`, failing with `Error::DstMismatch` for any other tag.
+            pub fn restore(snapshot: &$snapshot, dst: &[u8]) -> Result<Self, Error> {
+                let ctx = $name::builder(dst).hash_mode(snapshot.mode).build()?;
+                let dst_hash: [u8; 32] = Sha256::digest(dst).into();
+                if dst_hash != snapshot.dst_hash {
+                    return Err(Error::DstMismatch);
+                }
+
+                let mut ctx = ctx.commit();
+                let header = unsafe { &mut *(ctx.ctx() as *mut PairingHeader) };
+                if let Some(gt) = snapshot.gt {
+                    header.ctrl |= $kind | AGGR_GT_SET;
+                    header.gt = gt.0;
+                }
+                if let Some(sig) = snapshot.sig {
+                    header.ctrl |= $kind | AGGR_SIGN_SET;
+                    unsafe {
+                        let ptr = (ctx.v.as_mut_ptr() as *mut u8)
+                            .add(core::mem::size_of::<PairingHeader>())
+                            as *mut $q_raw;
+                        *ptr = $q_proj::from(sig).0;
+                    }
+                }
+
+                Ok(ctx)
+            }
+
+            pub fn merge(&mut self, ctx1: &Self) -> Result<(), Error> {
+                Error::from_blst(unsafe { blst_pairing_merge(self.ctx(), ctx1.const_ctx()) })
+            }
+
+            /// Checks the aggregated pairings, returning `Error::VerifyFailed` if they
+            /// do not match the aggregated signature.
+            pub fn finalverify(&self, gtsig: Option<&Fp12>) -> Result<(), Error> {
+                let valid = unsafe {
                     blst_pairing_finalverify(
                         self.const_ctx(),
                         match gtsig {
@@ -97,6 +468,11 @@ macro_rules! impl_pairing {
                             None => std::ptr::null(),
                         },
                     )
+                };
+                if valid {
+                    Ok(())
+                } else {
+                    Err(Error::VerifyFailed)
                 }
             }
         }
@@ -105,15 +481,27 @@ macro_rules! impl_pairing {
 
 impl_pairing!(
     PairingG1G2,
+    PairingG1G2Builder,
+    PairingG1G2Snapshot,
     G1Affine,
     G2Affine,
+    G2Projective,
+    blst_p2,
+    96,
+    AGGR_MIN_PK,
     blst_pairing_aggregate_pk_in_g1,
     blst_aggregated_in_g2
 );
 impl_pairing!(
     PairingG2G1,
+    PairingG2G1Builder,
+    PairingG2G1Snapshot,
     G2Affine,
     G1Affine,
+    G1Projective,
+    blst_p1,
+    48,
+    AGGR_MIN_SIG,
     blst_pairing_aggregate_pk_in_g2,
     blst_aggregated_in_g1
 );
@@ -141,3 +529,336 @@ pub fn unique_messages(msgs: &[&[u8]]) -> bool {
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{G1Projective, G2Projective, Scalar};
+
+    use fff::Field;
+    use groupy::{CurveAffine, CurveProjective};
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    #[test]
+    fn test_multi_pairing() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let ps: Vec<G1Affine> = (0..3)
+            .map(|_| G1Projective::random(&mut rng).into_affine())
+            .collect();
+        let qs: Vec<G2Affine> = (0..3)
+            .map(|_| G2Projective::random(&mut rng).into_affine())
+            .collect();
+
+        let mut expected = Fp12::one();
+        for (p, q) in ps.iter().zip(&qs) {
+            expected.mul_assign(&pairing(*p, *q));
+        }
+        let pairs: Vec<(&G1Affine, &G2Affine)> = ps.iter().zip(&qs).collect();
+        assert_eq!(multi_pairing(&pairs), expected);
+
+        assert_eq!(multi_pairing(&[]), Fp12::one());
+        assert_eq!(
+            multi_pairing(&[(&G1Affine::zero(), &qs[0]), (&ps[0], &G2Affine::zero())]),
+            Fp12::one()
+        );
+    }
+
+    #[test]
+    fn test_pairing_g1g2_aggregate() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let msgs: [&[u8]; 3] = [b"hello", b"world", b"!"];
+        let sks: Vec<Scalar> = msgs.iter().map(|_| Scalar::random(&mut rng)).collect();
+        let pks: Vec<G1Affine> = sks
+            .iter()
+            .map(|sk| (G1Projective::one() * sk).into_affine())
+            .collect();
+        let sigs: Vec<G2Affine> = sks
+            .iter()
+            .zip(msgs.iter())
+            .map(|(sk, msg)| (G2Projective::hash_to_curve(msg, DST, &[]) * sk).into_affine())
+            .collect();
+
+        let mut ctx = PairingG1G2::builder(DST).build().unwrap();
+        assert_eq!(ctx.dst(), DST);
+        for (i, (pk, msg)) in pks.iter().zip(msgs.iter()).enumerate() {
+            ctx.aggregate(pk, Some(&sigs[i]), msg, &[]).unwrap();
+        }
+        let ctx = ctx.commit();
+        assert_eq!(ctx.dst(), DST);
+        assert_eq!(ctx.finalverify(None), Ok(()));
+
+        // A wrong message fails verification.
+        let mut ctx = PairingG1G2::builder(DST).build().unwrap();
+        ctx.aggregate(&pks[0], Some(&sigs[0]), msgs[1], &[])
+            .unwrap();
+        assert_eq!(ctx.commit().finalverify(None), Err(Error::VerifyFailed));
+
+        // Signatures made by hashing do not verify when encoding.
+        let mut ctx = PairingG1G2::builder(DST)
+            .hash_mode(HashMode::Encode)
+            .build()
+            .unwrap();
+        ctx.aggregate(&pks[0], Some(&sigs[0]), msgs[0], &[])
+            .unwrap();
+        assert_eq!(ctx.commit().finalverify(None), Err(Error::VerifyFailed));
+
+        // Public keys at infinity are rejected.
+        let mut ctx = PairingG1G2::builder(DST).build().unwrap();
+        assert_eq!(
+            ctx.aggregate(&G1Affine::zero(), Some(&sigs[0]), msgs[0], &[]),
+            Err(Error::PublicKeyIsInfinity)
+        );
+    }
+
+    #[test]
+    fn test_pairing_g2g1_aggregate() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let dst = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+        let sk = Scalar::random(&mut rng);
+        let pk = (G2Projective::one() * sk).into_affine();
+        let sig = (G1Projective::hash_to_curve(b"hello", dst, &[]) * sk).into_affine();
+
+        let mut ctx = PairingG2G1::builder(dst).build().unwrap();
+        ctx.aggregate(&pk, Some(&sig), b"hello", &[]).unwrap();
+        assert_eq!(ctx.commit().finalverify(None), Ok(()));
+    }
+
+    #[test]
+    fn test_pairing_builder_dst() {
+        assert_eq!(
+            PairingG1G2::builder(&[]).build().unwrap_err(),
+            Error::InvalidDst
+        );
+        assert_eq!(
+            PairingG2G1::builder(&[0u8; 256]).build().unwrap_err(),
+            Error::InvalidDst
+        );
+        assert!(PairingG1G2::builder(&[0u8; 255]).build().is_ok());
+    }
+
+    #[test]
+    fn test_pairing_g1g2_merge() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let msgs: [&[u8]; 3] = [b"hello", b"world", b"!"];
+        let sks: Vec<Scalar> = msgs.iter().map(|_| Scalar::random(&mut rng)).collect();
+        let mut aggregate_sig = G2Projective::zero();
+        for (sk, msg) in sks.iter().zip(msgs.iter()) {
+            aggregate_sig += G2Projective::hash_to_curve(msg, DST, &[]) * sk;
+        }
+        let mut gtsig = Fp12::one();
+        PairingG1G2::aggregated(&mut gtsig, &aggregate_sig.into_affine());
+
+        // Aggregate the first message once, then fork the partial context.
+        let mut base = PairingG1G2::builder(DST).build().unwrap();
+        let pk = (G1Projective::one() * sks[0]).into_affine();
+        base.aggregate(&pk, None, msgs[0], &[]).unwrap();
+
+        let mut forked = base.clone();
+        let mut rest = PairingG1G2::builder(DST).build().unwrap();
+        for (sk, msg) in sks.iter().zip(msgs.iter()).skip(1) {
+            let pk = (G1Projective::one() * sk).into_affine();
+            rest.aggregate(&pk, None, msg, &[]).unwrap();
+        }
+        drop(base);
+
+        let mut forked = {
+            let pk = (G1Projective::one() * sks[1]).into_affine();
+            forked.aggregate(&pk, None, msgs[1], &[]).unwrap();
+            forked.commit()
+        };
+        let mut last = PairingG1G2::builder(DST).build().unwrap();
+        let pk = (G1Projective::one() * sks[2]).into_affine();
+        last.aggregate(&pk, None, msgs[2], &[]).unwrap();
+
+        forked.merge(&last.commit()).unwrap();
+        assert_eq!(forked.dst(), DST);
+        assert_eq!(forked.finalverify(Some(&gtsig)), Ok(()));
+        assert_eq!(
+            forked.finalverify(Some(&Fp12::one())),
+            Err(Error::VerifyFailed)
+        );
+
+        // The context missing the first message does not verify.
+        assert_eq!(
+            rest.commit().finalverify(Some(&gtsig)),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_pairing_snapshot_layout() {
+        // Offsets of the `PAIRING` fields in blst 0.3.3 on 64-bit targets.
+        #[cfg(target_pointer_width = "64")]
+        {
+            let header = core::mem::MaybeUninit::<PairingHeader>::uninit();
+            let base = header.as_ptr() as usize;
+            let offset = |field: *const u8| field as usize - base;
+            let ptr = header.as_ptr();
+            unsafe {
+                assert_eq!(offset(core::ptr::addr_of!((*ptr).ctrl) as *const u8), 0);
+                assert_eq!(offset(core::ptr::addr_of!((*ptr).nelems) as *const u8), 4);
+                assert_eq!(offset(core::ptr::addr_of!((*ptr).dst) as *const u8), 8);
+                assert_eq!(offset(core::ptr::addr_of!((*ptr).dst_len) as *const u8), 16);
+                assert_eq!(offset(core::ptr::addr_of!((*ptr).gt) as *const u8), 24);
+            }
+            assert_eq!(core::mem::size_of::<PairingHeader>(), 600);
+            // Header, aggregated signature and N_MAX = 8 affine pairs of points.
+            assert_eq!(unsafe { blst_pairing_sizeof() }, 600 + 288 + 8 * (192 + 96));
+        }
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let sk = Scalar::random(&mut rng);
+        let pk = (G1Projective::one() * sk).into_affine();
+        let sig = (G2Projective::hash_to_curve(b"hello", DST, &[]) * sk).into_affine();
+
+        let mut ctx = PairingG1G2::builder(DST).build().unwrap();
+        {
+            let header = unsafe { &*(ctx.const_ctx() as *const PairingHeader) };
+            assert_eq!(header.ctrl, AGGR_HASH_OR_ENCODE);
+            assert_eq!(header.nelems, 0);
+            assert_eq!(header.dst_len, DST.len());
+        }
+
+        ctx.aggregate(&pk, Some(&sig), b"hello", &[]).unwrap();
+        {
+            let header = unsafe { &*(ctx.const_ctx() as *const PairingHeader) };
+            assert_eq!(
+                header.ctrl,
+                AGGR_HASH_OR_ENCODE | AGGR_MIN_PK | AGGR_SIGN_SET
+            );
+            assert_eq!(header.nelems, 1);
+        }
+
+        // The aggregated signature directly follows the header.
+        let ctx = ctx.commit();
+        let header = unsafe { &*(ctx.const_ctx() as *const PairingHeader) };
+        assert_eq!(
+            header.ctrl,
+            AGGR_HASH_OR_ENCODE | AGGR_MIN_PK | AGGR_SIGN_SET | AGGR_GT_SET
+        );
+        assert_eq!(header.nelems, 0);
+        let aggregated = unsafe {
+            *((ctx.const_ctx() as *const u8).add(core::mem::size_of::<PairingHeader>())
+                as *const blst_p2)
+        };
+        assert_eq!(G2Projective(aggregated).into_affine(), sig);
+        assert_eq!(ctx.snapshot().signature(), Some(&sig));
+    }
+
+    #[test]
+    fn test_pairing_g1g2_snapshot() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let msgs: [&[u8]; 3] = [b"hello", b"world", b"!"];
+        let sks: Vec<Scalar> = msgs.iter().map(|_| Scalar::random(&mut rng)).collect();
+
+        // Each "process" aggregates one signed message and ships its state as bytes.
+        let mut encoded = Vec::new();
+        for (sk, msg) in sks.iter().zip(msgs.iter()) {
+            let pk = (G1Projective::one() * sk).into_affine();
+            let sig = (G2Projective::hash_to_curve(msg, DST, &[]) * sk).into_affine();
+            let mut ctx = PairingG1G2::builder(DST).build().unwrap();
+            ctx.aggregate(&pk, Some(&sig), msg, &[]).unwrap();
+
+            let snapshot = ctx.commit().snapshot();
+            assert_eq!(snapshot.hash_mode(), HashMode::Hash);
+            assert!(snapshot.miller_loop().is_some());
+            assert_eq!(snapshot.signature(), Some(&sig));
+
+            let mut buf = Vec::new();
+            assert_eq!(snapshot.write_raw(&mut buf).unwrap(), buf.len());
+            assert_eq!(buf.len(), snapshot.raw_fmt_size());
+            encoded.push(buf);
+        }
+
+        let mut acc = PairingG1G2::builder(DST).build().unwrap().commit();
+        for buf in &encoded {
+            let snapshot = PairingG1G2Snapshot::read_raw(&buf[..]).unwrap();
+            let ctx = PairingG1G2::restore(&snapshot, DST).unwrap();
+            acc.merge(&ctx).unwrap();
+        }
+        assert_eq!(acc.finalverify(None), Ok(()));
+
+        // Snapshots of merged contexts round trip as well.
+        let snapshot = acc.snapshot();
+        let restored = PairingG1G2::restore(&snapshot, DST).unwrap();
+        assert_eq!(restored.snapshot(), snapshot);
+        assert_eq!(restored.finalverify(None), Ok(()));
+
+        // Dropping the signature of a process fails verification.
+        let mut partial = PairingG1G2::builder(DST).build().unwrap().commit();
+        for (i, buf) in encoded.iter().enumerate() {
+            let mut snapshot = PairingG1G2Snapshot::read_raw(&buf[..]).unwrap();
+            if i == 0 {
+                snapshot.sig = None;
+            }
+            partial
+                .merge(&PairingG1G2::restore(&snapshot, DST).unwrap())
+                .unwrap();
+        }
+        assert_eq!(partial.finalverify(None), Err(Error::VerifyFailed));
+
+        // The tag must match.
+        let snapshot = PairingG1G2Snapshot::read_raw(&encoded[0][..]).unwrap();
+        assert_eq!(
+            PairingG1G2::restore(&snapshot, b"another tag").unwrap_err(),
+            Error::DstMismatch
+        );
+
+        // Contexts with public keys in G2 restore the same way.
+        let sk = Scalar::random(&mut rng);
+        let pk = (G2Projective::one() * sk).into_affine();
+        let sig = (G1Projective::hash_to_curve(b"hello", DST, &[]) * sk).into_affine();
+        let mut ctx = PairingG2G1::builder(DST).build().unwrap();
+        ctx.aggregate(&pk, Some(&sig), b"hello", &[]).unwrap();
+        let snapshot = ctx.commit().snapshot();
+        let mut buf = Vec::new();
+        snapshot.write_raw(&mut buf).unwrap();
+        let snapshot = PairingG2G1Snapshot::read_raw(&buf[..]).unwrap();
+        let restored = PairingG2G1::restore(&snapshot, DST).unwrap();
+        assert_eq!(restored.finalverify(None), Ok(()));
+
+        // Corrupted encodings are rejected.
+        let kind = |buf: &[u8]| {
+            let err = PairingG1G2Snapshot::read_raw(buf).unwrap_err();
+            *err.into_inner().unwrap().downcast::<Error>().unwrap()
+        };
+        let mut buf = encoded[0].clone();
+        buf[0] = 2;
+        assert_eq!(kind(&buf), Error::BadEncoding);
+        let mut buf = encoded[0].clone();
+        buf[1] |= 0x80;
+        assert_eq!(kind(&buf), Error::BadEncoding);
+        let mut buf = encoded[0].clone();
+        let len = buf.len();
+        buf[len - 1] ^= 1;
+        assert!(PairingG1G2Snapshot::read_raw(&buf[..]).is_err());
+        assert!(PairingG1G2Snapshot::read_raw(&encoded[0][..40]).is_err());
+    }
+}
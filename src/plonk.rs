@@ -0,0 +1,1114 @@
+//! Verification of PLONK proofs with KZG commitments, as in the protocol of
+//! Gabizon, Williamson and Ciobotaru with the linearized final check.
+//!
+//! The circuit has the gates `q_M a b + q_L a + q_R b + q_O c + q_C + PI = 0` on the
+//! domain of size `n`, with the public inputs on the first rows, and its copy
+//! constraints are the permutation of the wires given by the polynomials `S_sigma`,
+//! on the cosets `H`, `k_1 H` and `k_2 H`. The verifier derives the challenges from
+//! a transcript of the key, the public inputs and the proof, and checks all
+//! openings with two pairings.
+//!
+//! Only the native flavour of this crate is supported: the SHA-256 `Transcript`, the
+//! `NativeEncoding` and the linearization above. Proofs of other provers differ in
+//! their transcripts, encodings and often their linearizations, and none is checked
+//! against external vectors. The `PlonkTranscript` and `PlonkEncoding` traits only
+//! let callers replace the transcript and the encoding of this protocol.
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+use fff::Field;
+use groupy::{CurveAffine, CurveProjective};
+
+use crate::transcript::Transcript;
+use crate::{
+    multi_pairing, BatchInvert, Error, EvaluationDomain, Fp12, G1Affine, G1Projective, G2Affine,
+    Scalar,
+};
+
+/// Domain separation label of the native Fiat-Shamir transcript.
+const TRANSCRIPT_LABEL: &[u8] = b"BLSTRS_PLONK_KZG_BLS12381";
+
+/// The Fiat-Shamir transcript of a PLONK flavour, which fixes how the key, the public
+/// inputs and the proof are absorbed and how the challenges are derived.
+///
+/// The verifier absorbs the commitments and evaluations of the proof in the order
+/// of the rounds of the protocol, and derives `beta`, `gamma`, `alpha`, `zeta`, `v`
+/// and `u` with these labels.
+pub trait PlonkTranscript {
+    /// Starts a transcript bound to the verifying key and the public inputs.
+    fn start(vk: &VerifyingKey, public_inputs: &[Scalar]) -> Self;
+
+    /// Absorbs a commitment of the proof.
+    fn append_g1(&mut self, label: &'static [u8], p: &G1Affine);
+
+    /// Absorbs an evaluation of the proof.
+    fn append_scalar(&mut self, label: &'static [u8], s: &Scalar);
+
+    /// Derives the next challenge.
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> Scalar;
+}
+
+/// The native transcript, labelled `BLSTRS_PLONK_KZG_BLS12381`, which starts with
+/// the domain size as a little-endian `u64`, the shifts, the commitments of the key,
+/// `[x]_2` and the public inputs.
+impl PlonkTranscript for Transcript {
+    fn start(vk: &VerifyingKey, public_inputs: &[Scalar]) -> Self {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_message(b"n", &(vk.domain.size() as u64).to_le_bytes());
+        Transcript::append_scalar(&mut transcript, b"k1", &vk.k1);
+        Transcript::append_scalar(&mut transcript, b"k2", &vk.k2);
+        for p in &vk.commitments() {
+            Transcript::append_g1(&mut transcript, b"vk", p);
+        }
+        transcript.append_g2(b"x_g2", &vk.x_g2);
+        for input in public_inputs {
+            Transcript::append_scalar(&mut transcript, b"public_input", input);
+        }
+        transcript
+    }
+
+    fn append_g1(&mut self, label: &'static [u8], p: &G1Affine) {
+        Transcript::append_g1(self, label, p);
+    }
+
+    fn append_scalar(&mut self, label: &'static [u8], s: &Scalar) {
+        Transcript::append_scalar(self, label, s);
+    }
+
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> Scalar {
+        Transcript::challenge_scalar(self, label)
+    }
+}
+
+/// The byte encoding of the sizes, scalars and points of keys and proofs.
+///
+/// Decoding must reject non-canonical scalars and points outside of their subgroup.
+pub trait PlonkEncoding {
+    fn read_size<R: Read>(reader: &mut R) -> io::Result<usize>;
+    fn read_scalar<R: Read>(reader: &mut R) -> io::Result<Scalar>;
+    fn read_g1<R: Read>(reader: &mut R) -> io::Result<G1Affine>;
+    fn read_g2<R: Read>(reader: &mut R) -> io::Result<G2Affine>;
+
+    fn write_size<W: Write>(size: usize, writer: &mut W) -> io::Result<()>;
+    fn write_scalar<W: Write>(s: &Scalar, writer: &mut W) -> io::Result<()>;
+    fn write_g1<W: Write>(p: &G1Affine, writer: &mut W) -> io::Result<()>;
+    fn write_g2<W: Write>(p: &G2Affine, writer: &mut W) -> io::Result<()>;
+}
+
+/// The native encoding: sizes as big-endian `u32`, scalars as canonical
+/// little-endian bytes and points compressed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeEncoding;
+
+impl PlonkEncoding for NativeEncoding {
+    fn read_size<R: Read>(reader: &mut R) -> io::Result<usize> {
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(u32::from_be_bytes(bytes) as usize)
+    }
+
+    fn read_scalar<R: Read>(reader: &mut R) -> io::Result<Scalar> {
+        let mut bytes = [0u8; 32];
+        reader.read_exact(&mut bytes)?;
+        Scalar::from_bytes_le(&bytes).ok_or_else(|| Error::InvalidScalar.into())
+    }
+
+    fn read_g1<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+        let mut bytes = [0u8; 48];
+        reader.read_exact(&mut bytes)?;
+        Ok(G1Affine::from_compressed(&bytes)?)
+    }
+
+    fn read_g2<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+        let mut bytes = [0u8; 96];
+        reader.read_exact(&mut bytes)?;
+        Ok(G2Affine::from_compressed(&bytes)?)
+    }
+
+    fn write_size<W: Write>(size: usize, writer: &mut W) -> io::Result<()> {
+        let size = u32::try_from(size).map_err(|_| invalid_data("size does not fit a u32"))?;
+        writer.write_all(&size.to_be_bytes())
+    }
+
+    fn write_scalar<W: Write>(s: &Scalar, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&s.to_bytes_le())
+    }
+
+    fn write_g1<W: Write>(p: &G1Affine, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&p.to_compressed())
+    }
+
+    fn write_g2<W: Write>(p: &G2Affine, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&p.to_compressed())
+    }
+}
+
+/// The verifying key of a PLONK circuit, with the commitments to its selector and
+/// permutation polynomials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub domain: EvaluationDomain,
+    pub num_public_inputs: usize,
+    /// The shifts of the cosets of the `b` and `c` wires.
+    pub k1: Scalar,
+    pub k2: Scalar,
+    pub q_m: G1Affine,
+    pub q_l: G1Affine,
+    pub q_r: G1Affine,
+    pub q_o: G1Affine,
+    pub q_c: G1Affine,
+    pub s_sigma1: G1Affine,
+    pub s_sigma2: G1Affine,
+    pub s_sigma3: G1Affine,
+    /// `[x]_2` of the KZG reference string.
+    pub x_g2: G2Affine,
+}
+
+impl VerifyingKey {
+    /// Reads a key in the native encoding, see `read_with`.
+    pub fn read<R: Read>(reader: R) -> io::Result<Self> {
+        Self::read_with::<NativeEncoding, R>(reader)
+    }
+
+    /// Reads a key encoded as the domain size, the number of public inputs, the
+    /// shifts, the commitments and `[x]_2`.
+    pub fn read_with<E: PlonkEncoding, R: Read>(mut reader: R) -> io::Result<Self> {
+        let size = E::read_size(&mut reader)?;
+        let domain = EvaluationDomain::new(size)?;
+        if domain.size() != size {
+            return Err(invalid_data("domain size is not a power of two"));
+        }
+        let num_public_inputs = E::read_size(&mut reader)?;
+        if num_public_inputs > size {
+            return Err(invalid_data("more public inputs than rows"));
+        }
+
+        Ok(VerifyingKey {
+            domain,
+            num_public_inputs,
+            k1: E::read_scalar(&mut reader)?,
+            k2: E::read_scalar(&mut reader)?,
+            q_m: E::read_g1(&mut reader)?,
+            q_l: E::read_g1(&mut reader)?,
+            q_r: E::read_g1(&mut reader)?,
+            q_o: E::read_g1(&mut reader)?,
+            q_c: E::read_g1(&mut reader)?,
+            s_sigma1: E::read_g1(&mut reader)?,
+            s_sigma2: E::read_g1(&mut reader)?,
+            s_sigma3: E::read_g1(&mut reader)?,
+            x_g2: E::read_g2(&mut reader)?,
+        })
+    }
+
+    /// Writes the key in the native encoding read by `read`.
+    pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_with::<NativeEncoding, W>(writer)
+    }
+
+    /// Writes the key in the encoding read by `read_with`.
+    pub fn write_with<E: PlonkEncoding, W: Write>(&self, mut writer: W) -> io::Result<()> {
+        E::write_size(self.domain.size(), &mut writer)?;
+        E::write_size(self.num_public_inputs, &mut writer)?;
+        E::write_scalar(&self.k1, &mut writer)?;
+        E::write_scalar(&self.k2, &mut writer)?;
+        for p in &self.commitments() {
+            E::write_g1(p, &mut writer)?;
+        }
+        E::write_g2(&self.x_g2, &mut writer)
+    }
+
+    fn commitments(&self) -> [G1Affine; 8] {
+        [
+            self.q_m,
+            self.q_l,
+            self.q_r,
+            self.q_o,
+            self.q_c,
+            self.s_sigma1,
+            self.s_sigma2,
+            self.s_sigma3,
+        ]
+    }
+}
+
+/// A PLONK proof: the commitments to the wires, the permutation accumulator `z`,
+/// the three parts of the quotient and the two openings, and the evaluations at
+/// the challenge `zeta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proof {
+    pub a: G1Affine,
+    pub b: G1Affine,
+    pub c: G1Affine,
+    pub z: G1Affine,
+    pub t_lo: G1Affine,
+    pub t_mid: G1Affine,
+    pub t_hi: G1Affine,
+    /// The opening at `zeta` of the linearization and the batched polynomials.
+    pub w_zeta: G1Affine,
+    /// The opening of `z` at `zeta omega`.
+    pub w_zeta_omega: G1Affine,
+    pub a_eval: Scalar,
+    pub b_eval: Scalar,
+    pub c_eval: Scalar,
+    pub s_sigma1_eval: Scalar,
+    pub s_sigma2_eval: Scalar,
+    /// The evaluation of `z` at `zeta omega`.
+    pub z_omega_eval: Scalar,
+}
+
+impl Proof {
+    /// Size in bytes of the native encoding.
+    pub const SIZE: usize = 9 * 48 + 6 * 32;
+
+    /// Reads a proof in the native encoding, see `read_with`.
+    pub fn read<R: Read>(reader: R) -> io::Result<Self> {
+        Self::read_with::<NativeEncoding, R>(reader)
+    }
+
+    /// Reads a proof encoded as its commitments followed by its evaluations.
+    pub fn read_with<E: PlonkEncoding, R: Read>(mut reader: R) -> io::Result<Self> {
+        Ok(Proof {
+            a: E::read_g1(&mut reader)?,
+            b: E::read_g1(&mut reader)?,
+            c: E::read_g1(&mut reader)?,
+            z: E::read_g1(&mut reader)?,
+            t_lo: E::read_g1(&mut reader)?,
+            t_mid: E::read_g1(&mut reader)?,
+            t_hi: E::read_g1(&mut reader)?,
+            w_zeta: E::read_g1(&mut reader)?,
+            w_zeta_omega: E::read_g1(&mut reader)?,
+            a_eval: E::read_scalar(&mut reader)?,
+            b_eval: E::read_scalar(&mut reader)?,
+            c_eval: E::read_scalar(&mut reader)?,
+            s_sigma1_eval: E::read_scalar(&mut reader)?,
+            s_sigma2_eval: E::read_scalar(&mut reader)?,
+            z_omega_eval: E::read_scalar(&mut reader)?,
+        })
+    }
+
+    /// Writes the proof in the native encoding read by `read`.
+    pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_with::<NativeEncoding, W>(writer)
+    }
+
+    /// Writes the proof in the encoding read by `read_with`.
+    pub fn write_with<E: PlonkEncoding, W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for p in &[
+            self.a,
+            self.b,
+            self.c,
+            self.z,
+            self.t_lo,
+            self.t_mid,
+            self.t_hi,
+            self.w_zeta,
+            self.w_zeta_omega,
+        ] {
+            E::write_g1(p, &mut writer)?;
+        }
+        for s in &self.evaluations() {
+            E::write_scalar(s, &mut writer)?;
+        }
+        Ok(())
+    }
+
+    fn evaluations(&self) -> [Scalar; 6] {
+        [
+            self.a_eval,
+            self.b_eval,
+            self.c_eval,
+            self.s_sigma1_eval,
+            self.s_sigma2_eval,
+            self.z_omega_eval,
+        ]
+    }
+}
+
+/// The Fiat-Shamir challenges of a proof.
+#[derive(Debug, Clone, Copy)]
+struct Challenges {
+    beta: Scalar,
+    gamma: Scalar,
+    alpha: Scalar,
+    zeta: Scalar,
+    v: Scalar,
+    u: Scalar,
+}
+
+impl Challenges {
+    fn derive<T: PlonkTranscript>(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[Scalar],
+    ) -> Self {
+        let mut transcript = T::start(vk, public_inputs);
+        transcript.append_g1(b"a", &proof.a);
+        transcript.append_g1(b"b", &proof.b);
+        transcript.append_g1(b"c", &proof.c);
+        let beta = transcript.challenge_scalar(b"beta");
+        let gamma = transcript.challenge_scalar(b"gamma");
+
+        transcript.append_g1(b"z", &proof.z);
+        let alpha = transcript.challenge_scalar(b"alpha");
+
+        transcript.append_g1(b"t_lo", &proof.t_lo);
+        transcript.append_g1(b"t_mid", &proof.t_mid);
+        transcript.append_g1(b"t_hi", &proof.t_hi);
+        let zeta = transcript.challenge_scalar(b"zeta");
+
+        for eval in &proof.evaluations() {
+            transcript.append_scalar(b"eval", eval);
+        }
+        let v = transcript.challenge_scalar(b"v");
+
+        transcript.append_g1(b"w_zeta", &proof.w_zeta);
+        transcript.append_g1(b"w_zeta_omega", &proof.w_zeta_omega);
+        let u = transcript.challenge_scalar(b"u");
+
+        Challenges {
+            beta,
+            gamma,
+            alpha,
+            zeta,
+            v,
+            u,
+        }
+    }
+}
+
+/// Verifies a proof of the native flavour for the given public inputs.
+///
+/// Fails with `LengthMismatch` if the number of inputs does not match the key, and
+/// with `VerifyFailed` if the proof is invalid.
+pub fn verify_proof(
+    vk: &VerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+) -> Result<(), Error> {
+    verify_proof_with::<Transcript>(vk, proof, public_inputs)
+}
+
+/// Verifies a proof whose challenges are derived with the transcript `T`, see
+/// `verify_proof`.
+pub fn verify_proof_with<T: PlonkTranscript>(
+    vk: &VerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+) -> Result<(), Error> {
+    if public_inputs.len() != vk.num_public_inputs {
+        return Err(Error::LengthMismatch);
+    }
+
+    let Challenges {
+        beta,
+        gamma,
+        alpha,
+        zeta,
+        v,
+        u,
+    } = Challenges::derive::<T>(vk, proof, public_inputs);
+
+    let n = vk.domain.size();
+    let zeta_n = zeta.pow([n as u64]);
+    let z_h = zeta_n - Scalar::one();
+    if z_h.is_zero() {
+        // The evaluations of the Lagrange polynomials are not defined by the
+        // formula below, which only happens with negligible probability.
+        return Err(Error::VerifyFailed);
+    }
+
+    // L_i(zeta) = omega^i (zeta^n - 1) / (n (zeta - omega^i)) for the rows of the
+    // public inputs and the first row.
+    let rows = public_inputs.len().max(1);
+    let mut omega_i = Scalar::one();
+    let mut numerators = Vec::with_capacity(rows);
+    let mut lagrange = Vec::with_capacity(rows);
+    for _ in 0..rows {
+        numerators.push(omega_i * z_h * vk.domain.size_inv());
+        lagrange.push(zeta - omega_i);
+        omega_i *= vk.domain.omega();
+    }
+    Scalar::batch_invert(&mut lagrange);
+    for (l, numerator) in lagrange.iter_mut().zip(&numerators) {
+        *l *= numerator;
+    }
+    let l_0 = lagrange[0];
+    let pi = public_inputs
+        .iter()
+        .zip(&lagrange)
+        .fold(Scalar::zero(), |acc, (x, l)| acc - *x * l);
+
+    let alpha_sq = alpha * alpha;
+    let a = proof.a_eval;
+    let b = proof.b_eval;
+    let c = proof.c_eval;
+    let s1 = proof.s_sigma1_eval;
+    let s2 = proof.s_sigma2_eval;
+    let z_omega = proof.z_omega_eval;
+
+    // The products of the permutation argument at zeta, without the last factor of
+    // the sigma side.
+    let id_product = (a + beta * zeta + gamma)
+        * (b + beta * vk.k1 * zeta + gamma)
+        * (c + beta * vk.k2 * zeta + gamma);
+    let sigma_product = (a + beta * s1 + gamma) * (b + beta * s2 + gamma);
+
+    // The constant part of the linearization.
+    let r0 = pi - l_0 * alpha_sq - alpha * sigma_product * (c + gamma) * z_omega;
+
+    // [F] = [D] + v [a] + v^2 [b] + v^3 [c] + v^4 [s_sigma1] + v^5 [s_sigma2], and
+    // the right side of the pairing check adds zeta [W_zeta] + u zeta omega
+    // [W_zeta_omega] - [E].
+    let v2 = v * v;
+    let v3 = v2 * v;
+    let v4 = v3 * v;
+    let v5 = v4 * v;
+    let e = -r0 + v * a + v2 * b + v3 * c + v4 * s1 + v5 * s2 + u * z_omega;
+    let bases = [
+        vk.q_m,
+        vk.q_l,
+        vk.q_r,
+        vk.q_o,
+        vk.q_c,
+        proof.z,
+        vk.s_sigma3,
+        proof.t_lo,
+        proof.t_mid,
+        proof.t_hi,
+        proof.a,
+        proof.b,
+        proof.c,
+        vk.s_sigma1,
+        vk.s_sigma2,
+        proof.w_zeta,
+        proof.w_zeta_omega,
+        G1Affine::one(),
+    ];
+    let scalars = [
+        a * b,
+        a,
+        b,
+        c,
+        Scalar::one(),
+        id_product * alpha + l_0 * alpha_sq + u,
+        -(sigma_product * alpha * beta * z_omega),
+        -z_h,
+        -(z_h * zeta_n),
+        -(z_h * zeta_n * zeta_n),
+        v,
+        v2,
+        v3,
+        v4,
+        v5,
+        zeta,
+        u * zeta * vk.domain.omega(),
+        -e,
+    ];
+    let right = G1Projective::multi_exp_vartime(&bases, &scalars);
+    let mut left = proof.w_zeta_omega.into_projective().mul_vartime(&u);
+    left.add_assign_mixed(&proof.w_zeta);
+
+    // e([W_zeta] + u [W_zeta_omega], [x]_2) = e(right, [1]_2)
+    let mut neg_right = right.into_affine();
+    neg_right.negate();
+    let check = multi_pairing(&[
+        (&left.into_affine(), &vk.x_g2),
+        (&neg_right, &G2Affine::one()),
+    ]);
+    if check != Fp12::one() {
+        return Err(Error::VerifyFailed);
+    }
+
+    Ok(())
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use fff::PrimeField;
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use crate::{KzgSrs, Polynomial};
+
+    /// A circuit with its gates and wiring, as evaluations over the domain.
+    struct Circuit {
+        domain: EvaluationDomain,
+        num_public_inputs: usize,
+        /// `q_M`, `q_L`, `q_R`, `q_O` and `q_C`.
+        selectors: [Vec<Scalar>; 5],
+        /// The permutation of the wire positions `col n + row`.
+        sigma: Vec<usize>,
+    }
+
+    /// The preprocessed polynomials of a circuit.
+    struct ProvingKey {
+        vk: VerifyingKey,
+        selectors: [Polynomial; 5],
+        s_sigma: [Polynomial; 3],
+        s_sigma_evals: [Vec<Scalar>; 3],
+    }
+
+    fn interpolate(domain: &EvaluationDomain, evals: &[Scalar]) -> Polynomial {
+        let mut coeffs = evals.to_vec();
+        domain.ifft(&mut coeffs);
+        Polynomial::from_coeffs(coeffs)
+    }
+
+    fn coset_evals(domain: &EvaluationDomain, p: &Polynomial) -> Vec<Scalar> {
+        let mut evals = p.coeffs().to_vec();
+        evals.resize(domain.size(), Scalar::zero());
+        domain.coset_fft(&mut evals);
+        evals
+    }
+
+    fn setup(srs: &KzgSrs, circuit: &Circuit) -> ProvingKey {
+        let n = circuit.domain.size();
+        let k1 = Scalar::multiplicative_generator();
+        let k2 = k1 * k1;
+        let omega = circuit.domain.elements();
+        let id = |p: usize| [Scalar::one(), k1, k2][p / n] * omega[p % n];
+
+        let sigma = |col: usize| -> Vec<Scalar> {
+            (0..n).map(|row| id(circuit.sigma[col * n + row])).collect()
+        };
+        let s_sigma_evals = [sigma(0), sigma(1), sigma(2)];
+        let interp = |evals: &[Scalar]| interpolate(&circuit.domain, evals);
+        let q = &circuit.selectors;
+        let selectors = [
+            interp(&q[0]),
+            interp(&q[1]),
+            interp(&q[2]),
+            interp(&q[3]),
+            interp(&q[4]),
+        ];
+        let s = &s_sigma_evals;
+        let s_sigma = [interp(&s[0]), interp(&s[1]), interp(&s[2])];
+        let commit = |p: &Polynomial| srs.commit(p).unwrap();
+
+        let vk = VerifyingKey {
+            domain: circuit.domain.clone(),
+            num_public_inputs: circuit.num_public_inputs,
+            k1,
+            k2,
+            q_m: commit(&selectors[0]),
+            q_l: commit(&selectors[1]),
+            q_r: commit(&selectors[2]),
+            q_o: commit(&selectors[3]),
+            q_c: commit(&selectors[4]),
+            s_sigma1: commit(&s_sigma[0]),
+            s_sigma2: commit(&s_sigma[1]),
+            s_sigma3: commit(&s_sigma[2]),
+            x_g2: *srs.g2_tau(),
+        };
+
+        ProvingKey {
+            vk,
+            selectors,
+            s_sigma,
+            s_sigma_evals,
+        }
+    }
+
+    /// Proves the wire values `wires` without blinding, following the rounds of the
+    /// protocol and the transcript `T` of the verifier.
+    fn prove<T: PlonkTranscript>(
+        srs: &KzgSrs,
+        pk: &ProvingKey,
+        wires: &[Vec<Scalar>; 3],
+        public_inputs: &[Scalar],
+    ) -> Proof {
+        let vk = &pk.vk;
+        let domain = &vk.domain;
+        let n = domain.size();
+        let omega = domain.omega();
+        let commit = |p: &Polynomial| srs.commit(p).unwrap();
+        let mut transcript = T::start(vk, public_inputs);
+
+        // Round 1, the wires.
+        let a = interpolate(domain, &wires[0]);
+        let b = interpolate(domain, &wires[1]);
+        let c = interpolate(domain, &wires[2]);
+        let (a_c, b_c, c_c) = (commit(&a), commit(&b), commit(&c));
+        transcript.append_g1(b"a", &a_c);
+        transcript.append_g1(b"b", &b_c);
+        transcript.append_g1(b"c", &c_c);
+        let beta = transcript.challenge_scalar(b"beta");
+        let gamma = transcript.challenge_scalar(b"gamma");
+
+        // Round 2, the permutation accumulator.
+        let ks = [Scalar::one(), vk.k1, vk.k2];
+        let elements = domain.elements();
+        let mut z_evals = vec![Scalar::one(); n];
+        for i in 0..n - 1 {
+            let mut ratio = Scalar::one();
+            for col in 0..3 {
+                ratio *= (wires[col][i] + beta * ks[col] * elements[i] + gamma)
+                    * (wires[col][i] + beta * pk.s_sigma_evals[col][i] + gamma)
+                        .inverse()
+                        .unwrap();
+            }
+            z_evals[i + 1] = z_evals[i] * ratio;
+        }
+        let z = interpolate(domain, &z_evals);
+        let z_c = commit(&z);
+        transcript.append_g1(b"z", &z_c);
+        let alpha = transcript.challenge_scalar(b"alpha");
+
+        // Round 3, the quotient, over a coset of the domain of size 4 n.
+        let big = EvaluationDomain::new(4 * n).unwrap();
+        let mut pi_evals = vec![Scalar::zero(); n];
+        for (pi, x) in pi_evals.iter_mut().zip(public_inputs) {
+            *pi = -*x;
+        }
+        let pi = interpolate(domain, &pi_evals);
+        let mut l0_evals = vec![Scalar::zero(); n];
+        l0_evals[0] = Scalar::one();
+        let l0 = interpolate(domain, &l0_evals);
+        let z_shifted = Polynomial::from_coeffs(
+            z.coeffs()
+                .iter()
+                .zip(&elements)
+                .map(|(z, w)| *z * w)
+                .collect(),
+        );
+
+        let evals = |p: &Polynomial| coset_evals(&big, p);
+        let q = &pk.selectors;
+        let (qm, ql, qr, qo, qc) = (
+            evals(&q[0]),
+            evals(&q[1]),
+            evals(&q[2]),
+            evals(&q[3]),
+            evals(&q[4]),
+        );
+        let s = &pk.s_sigma;
+        let (s1, s2, s3) = (evals(&s[0]), evals(&s[1]), evals(&s[2]));
+        let (ae, be, ce) = (evals(&a), evals(&b), evals(&c));
+        let (ze, zwe, pie, l0e) = (evals(&z), evals(&z_shifted), evals(&pi), evals(&l0));
+        let g = Scalar::multiplicative_generator();
+        let xs: Vec<Scalar> = big.elements().iter().map(|w| g * w).collect();
+        let mut z_h: Vec<Scalar> = xs
+            .iter()
+            .map(|x| x.pow([n as u64]) - Scalar::one())
+            .collect();
+        Scalar::batch_invert(&mut z_h);
+        let mut t_evals: Vec<Scalar> = (0..4 * n)
+            .map(|i| {
+                let x = xs[i];
+                let gate = ae[i] * be[i] * qm[i]
+                    + ae[i] * ql[i]
+                    + be[i] * qr[i]
+                    + ce[i] * qo[i]
+                    + pie[i]
+                    + qc[i];
+                let perm = (ae[i] + beta * x + gamma)
+                    * (be[i] + beta * vk.k1 * x + gamma)
+                    * (ce[i] + beta * vk.k2 * x + gamma)
+                    * ze[i]
+                    - (ae[i] + beta * s1[i] + gamma)
+                        * (be[i] + beta * s2[i] + gamma)
+                        * (ce[i] + beta * s3[i] + gamma)
+                        * zwe[i];
+                let first = (ze[i] - Scalar::one()) * l0e[i];
+                (gate + alpha * perm + (alpha * alpha) * first) * z_h[i]
+            })
+            .collect();
+        big.coset_ifft(&mut t_evals);
+        assert!(
+            t_evals[3 * n..].iter().all(|t| t.is_zero()),
+            "the witness does not satisfy the circuit"
+        );
+        let t_lo = Polynomial::from_coeffs(t_evals[..n].to_vec());
+        let t_mid = Polynomial::from_coeffs(t_evals[n..2 * n].to_vec());
+        let t_hi = Polynomial::from_coeffs(t_evals[2 * n..3 * n].to_vec());
+        let (t_lo_c, t_mid_c, t_hi_c) = (commit(&t_lo), commit(&t_mid), commit(&t_hi));
+        transcript.append_g1(b"t_lo", &t_lo_c);
+        transcript.append_g1(b"t_mid", &t_mid_c);
+        transcript.append_g1(b"t_hi", &t_hi_c);
+        let zeta = transcript.challenge_scalar(b"zeta");
+
+        // Round 4, the evaluations.
+        let a_eval = a.evaluate(&zeta);
+        let b_eval = b.evaluate(&zeta);
+        let c_eval = c.evaluate(&zeta);
+        let s_sigma1_eval = pk.s_sigma[0].evaluate(&zeta);
+        let s_sigma2_eval = pk.s_sigma[1].evaluate(&zeta);
+        let z_omega_eval = z.evaluate(&(zeta * omega));
+        let evals = [
+            a_eval,
+            b_eval,
+            c_eval,
+            s_sigma1_eval,
+            s_sigma2_eval,
+            z_omega_eval,
+        ];
+        for eval in &evals {
+            transcript.append_scalar(b"eval", eval);
+        }
+        let v = transcript.challenge_scalar(b"v");
+
+        // Round 5, the linearization and the openings.
+        let zeta_n = zeta.pow([n as u64]);
+        let z_h_zeta = zeta_n - Scalar::one();
+        let [qm, ql, qr, qo, qc] = &pk.selectors;
+        let r = qm * (a_eval * b_eval)
+            + ql * a_eval
+            + qr * b_eval
+            + qo * c_eval
+            + Polynomial::constant(pi.evaluate(&zeta))
+            + qc
+            + &z * (alpha
+                * (a_eval + beta * zeta + gamma)
+                * (b_eval + beta * vk.k1 * zeta + gamma)
+                * (c_eval + beta * vk.k2 * zeta + gamma))
+            - (&pk.s_sigma[2] * beta + Polynomial::constant(c_eval + gamma))
+                * (alpha
+                    * (a_eval + beta * s_sigma1_eval + gamma)
+                    * (b_eval + beta * s_sigma2_eval + gamma)
+                    * z_omega_eval)
+            + (&z - Polynomial::constant(Scalar::one())) * ((alpha * alpha) * l0.evaluate(&zeta))
+            - (t_lo + t_mid * zeta_n + t_hi * (zeta_n * zeta_n)) * z_h_zeta;
+        assert!(r.evaluate(&zeta).is_zero());
+
+        let mut batched = r;
+        let mut v_i = Scalar::one();
+        for (p, eval) in [&a, &b, &c, &pk.s_sigma[0], &pk.s_sigma[1]]
+            .iter()
+            .zip(&evals)
+        {
+            v_i *= v;
+            batched = batched + (*p - Polynomial::constant(*eval)) * v_i;
+        }
+        let (w_zeta, _) = batched.divide_by_linear(&zeta);
+        let (w_zeta_omega, _) = z.divide_by_linear(&(zeta * omega));
+
+        Proof {
+            a: a_c,
+            b: b_c,
+            c: c_c,
+            z: z_c,
+            t_lo: t_lo_c,
+            t_mid: t_mid_c,
+            t_hi: t_hi_c,
+            w_zeta: commit(&w_zeta),
+            w_zeta_omega: commit(&w_zeta_omega),
+            a_eval,
+            b_eval,
+            c_eval,
+            s_sigma1_eval,
+            s_sigma2_eval,
+            z_omega_eval,
+        }
+    }
+
+    /// The circuit of `w^2 + w + 5 = x` with the public input `x`, padded to eight
+    /// rows, and the wires for the witness `w`.
+    fn example(w: u64) -> (Circuit, [Vec<Scalar>; 3], Scalar) {
+        let n = 8;
+        let w = Scalar::from(w);
+        let x = w * w + w + Scalar::from(5);
+        let one = Scalar::one();
+        let zero = Scalar::zero();
+
+        // Row 0 is the public input, then w * w = w^2, w^2 + w = y and y + 5 = x.
+        let mut selectors = [
+            vec![zero; n],
+            vec![zero; n],
+            vec![zero; n],
+            vec![zero; n],
+            vec![zero; n],
+        ];
+        selectors[1][0] = one;
+        selectors[0][1] = one;
+        selectors[3][1] = -one;
+        selectors[1][2] = one;
+        selectors[2][2] = one;
+        selectors[3][2] = -one;
+        selectors[1][3] = one;
+        selectors[4][3] = Scalar::from(5);
+        selectors[3][3] = -one;
+
+        let mut wires = [vec![zero; n], vec![zero; n], vec![zero; n]];
+        wires[0][0] = x;
+        wires[0][1] = w;
+        wires[1][1] = w;
+        wires[2][1] = w * w;
+        wires[0][2] = w * w;
+        wires[1][2] = w;
+        wires[2][2] = w * w + w;
+        wires[0][3] = w * w + w;
+        wires[2][3] = x;
+
+        // The wire in column col and row row is at position col n + row.
+        let mut sigma: Vec<usize> = (0..3 * n).collect();
+        for cycle in &[
+            vec![1, n + 1, n + 2],
+            vec![2 * n + 1, 2],
+            vec![2 * n + 2, 3],
+            vec![2 * n + 3, 0],
+        ] {
+            for (i, p) in cycle.iter().enumerate() {
+                sigma[*p] = cycle[(i + 1) % cycle.len()];
+            }
+        }
+
+        let circuit = Circuit {
+            domain: EvaluationDomain::new(n).unwrap(),
+            num_public_inputs: 1,
+            selectors,
+            sigma,
+        };
+        (circuit, wires, x)
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(15, &mut rng);
+        let (circuit, wires, x) = example(3);
+        let pk = setup(&srs, &circuit);
+        let vk = &pk.vk;
+        let proof = prove::<Transcript>(&srs, &pk, &wires, &[x]);
+        assert_eq!(verify_proof(vk, &proof, &[x]), Ok(()));
+
+        assert_eq!(
+            verify_proof(vk, &proof, &[x + Scalar::one()]),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(verify_proof(vk, &proof, &[]), Err(Error::LengthMismatch));
+
+        let mut bad = proof;
+        bad.a_eval += Scalar::one();
+        assert_eq!(verify_proof(vk, &bad, &[x]), Err(Error::VerifyFailed));
+        let mut bad = proof;
+        bad.t_hi = bad.t_lo;
+        assert_eq!(verify_proof(vk, &bad, &[x]), Err(Error::VerifyFailed));
+
+        // A proof for another witness is valid for its own public input.
+        let (_, wires, y) = example(11);
+        let other = prove::<Transcript>(&srs, &pk, &wires, &[y]);
+        assert_eq!(verify_proof(vk, &other, &[y]), Ok(()));
+        assert_eq!(verify_proof(vk, &other, &[x]), Err(Error::VerifyFailed));
+    }
+
+    #[test]
+    #[should_panic(expected = "the witness does not satisfy the circuit")]
+    fn test_unsatisfied_circuit() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(15, &mut rng);
+        let (circuit, mut wires, x) = example(3);
+        wires[2][1] += Scalar::one();
+        let pk = setup(&srs, &circuit);
+        prove::<Transcript>(&srs, &pk, &wires, &[x]);
+    }
+
+    #[test]
+    fn test_encodings() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(15, &mut rng);
+        let (circuit, wires, x) = example(5);
+        let pk = setup(&srs, &circuit);
+
+        let mut bytes = Vec::new();
+        pk.vk.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 4 + 4 + 2 * 32 + 8 * 48 + 96);
+        let vk = VerifyingKey::read(&bytes[..]).unwrap();
+        assert_eq!(vk, pk.vk);
+
+        // The domain size must be a power of two.
+        bytes[3] = 7;
+        assert!(VerifyingKey::read(&bytes[..]).is_err());
+
+        let proof = prove::<Transcript>(&srs, &pk, &wires, &[x]);
+        let mut bytes = Vec::new();
+        proof.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), Proof::SIZE);
+        let read = Proof::read(&bytes[..]).unwrap();
+        assert_eq!(read, proof);
+        assert_eq!(verify_proof(&vk, &read, &[x]), Ok(()));
+
+        // Evaluations must be canonical scalars.
+        bytes[Proof::SIZE - 32..].copy_from_slice(&[0xff; 32]);
+        assert!(Proof::read(&bytes[..]).is_err());
+    }
+
+    /// A flavour with another transcript, which binds only the public inputs and
+    /// absorbs scalars big-endian, for the tests.
+    struct OtherTranscript(Transcript);
+
+    impl PlonkTranscript for OtherTranscript {
+        fn start(_vk: &VerifyingKey, public_inputs: &[Scalar]) -> Self {
+            let mut transcript = Transcript::new(b"OTHER_PLONK");
+            for input in public_inputs {
+                transcript.append_message(b"pi", &input.to_bytes_be());
+            }
+            OtherTranscript(transcript)
+        }
+
+        fn append_g1(&mut self, label: &'static [u8], p: &G1Affine) {
+            self.0.append_message(label, &p.to_uncompressed());
+        }
+
+        fn append_scalar(&mut self, label: &'static [u8], s: &Scalar) {
+            self.0.append_message(label, &s.to_bytes_be());
+        }
+
+        fn challenge_scalar(&mut self, label: &'static [u8]) -> Scalar {
+            self.0.challenge_scalar(label)
+        }
+    }
+
+    /// Sizes as little-endian `u64`, big-endian scalars and uncompressed points.
+    struct OtherEncoding;
+
+    impl PlonkEncoding for OtherEncoding {
+        fn read_size<R: Read>(reader: &mut R) -> io::Result<usize> {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes) as usize)
+        }
+
+        fn read_scalar<R: Read>(reader: &mut R) -> io::Result<Scalar> {
+            let mut bytes = [0u8; 32];
+            reader.read_exact(&mut bytes)?;
+            Scalar::from_bytes_be(&bytes).ok_or_else(|| Error::InvalidScalar.into())
+        }
+
+        fn read_g1<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+            let mut bytes = [0u8; 96];
+            reader.read_exact(&mut bytes)?;
+            Ok(G1Affine::from_uncompressed(&bytes)?)
+        }
+
+        fn read_g2<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+            let mut bytes = [0u8; 192];
+            reader.read_exact(&mut bytes)?;
+            Ok(G2Affine::from_uncompressed(&bytes)?)
+        }
+
+        fn write_size<W: Write>(size: usize, writer: &mut W) -> io::Result<()> {
+            writer.write_all(&(size as u64).to_le_bytes())
+        }
+
+        fn write_scalar<W: Write>(s: &Scalar, writer: &mut W) -> io::Result<()> {
+            writer.write_all(&s.to_bytes_be())
+        }
+
+        fn write_g1<W: Write>(p: &G1Affine, writer: &mut W) -> io::Result<()> {
+            writer.write_all(&p.to_uncompressed())
+        }
+
+        fn write_g2<W: Write>(p: &G2Affine, writer: &mut W) -> io::Result<()> {
+            writer.write_all(&p.to_uncompressed())
+        }
+    }
+
+    #[test]
+    fn test_other_flavour() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let srs = KzgSrs::setup_insecure(15, &mut rng);
+        let (circuit, wires, x) = example(7);
+        let pk = setup(&srs, &circuit);
+
+        let mut bytes = Vec::new();
+        pk.vk.write_with::<OtherEncoding, _>(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 8 + 8 + 2 * 32 + 8 * 96 + 192);
+        let vk = VerifyingKey::read_with::<OtherEncoding, _>(&bytes[..]).unwrap();
+        assert_eq!(vk, pk.vk);
+        assert!(VerifyingKey::read(&bytes[..]).is_err());
+
+        let proof = prove::<OtherTranscript>(&srs, &pk, &wires, &[x]);
+        let mut bytes = Vec::new();
+        proof.write_with::<OtherEncoding, _>(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 9 * 96 + 6 * 32);
+        let read = Proof::read_with::<OtherEncoding, _>(&bytes[..]).unwrap();
+        assert_eq!(read, proof);
+
+        assert_eq!(
+            verify_proof_with::<OtherTranscript>(&vk, &read, &[x]),
+            Ok(())
+        );
+        assert_eq!(
+            verify_proof_with::<OtherTranscript>(&vk, &read, &[x + Scalar::one()]),
+            Err(Error::VerifyFailed)
+        );
+        // The challenges of the native transcript differ.
+        assert_eq!(verify_proof(&vk, &read, &[x]), Err(Error::VerifyFailed));
+    }
+
+    /// A key and proof of `example(3)` in the native flavour, which pin its transcript
+    /// and encoding.
+    const NATIVE_VK: &str = concat!(
+        "00000008000000010700000000000000000000000000000000000000000000000000000000000000",
+        "31000000000000000000000000000000000000000000000000000000000000008a6b7b4ce0daca0f",
+        "7f096b321561e2047004bca37aa10436f598df03bf5d76994abaf5170b3b6b80d9a1151caa1bc549",
+        "a20558fd15b0e8827efc46b36622d6b4087c3eafa4e21fc0d3cbc24e540b4ba8ee34b5da15792efc",
+        "aed5f2c9420e56d080e5dcad1d2154abbe62c222d62ac3cf985be5d03aa9b7539f1a5a7067ec3e5d",
+        "84d40afe2340ef84b6b7240ebd8fc0dfa85ba7c4fd141f15cb528bdd3985ba3ff231ac1a14c59c8d",
+        "960d2321fa8e950b023915fbb4436dd485d29d8acbc71b5399261890a8671413b2ead60678db0d7c",
+        "8cc52c3b2ee5c5e4af0c1c4bc721e274834f65401de89e4e09ca60449b073271b2827a3f68259039",
+        "68c919ac00b19a6f43aea87ce246df8738e56105c0ef52545e43c8b9c647500c8ed3a045649e3de1",
+        "8c8ba6245653605a3a9e973095af92af8886c937f41e356d197fd7fa0337ffcfe2e988fe9d2675ff",
+        "fa1b1acf57ada15ead34ddebee8a3e75fcfe605ce4ea71f7f793b82c2f3ca56ee452d19f0587a084",
+        "6303a1a8953a97b4001b7b1114e033eb8abe8b46913fb9b5a2b43ec90ebe5a680dceee088f291b73",
+        "0cdaf8824c11ed63a4baca0d31a6ea807be7982c63de2a1d078e35f06bc602c762513b9905dc0e5e",
+        "1ad303600731735cf209798d1349ae3dea38df9ce980765436791fded915b738",
+    );
+    const NATIVE_PROOF: &str = concat!(
+        "8354ad1cb301bf3e0f48692d53e2a11006733360c02627d1e302736b8733d9347a7c1feaaaa6474e",
+        "c40599cbfff2a12cb6f9198fcd3ae836b3584f9721051594f077a22dfb668901d736709add377bb5",
+        "25b73052dca2812053a32c04a767e01fa65802229e14a3ea3112afcdc1d03b703426c906a34cdea9",
+        "4cca8ebd7b7c8e4387f929af16eac5674017e7ee3a355958a07f44fec20cb2b19e25fa57f5fc99fd",
+        "0530f4f71540a1dd7bf112331a3cfadab3b157224340b177779914471f1395ef837aa3149b366a65",
+        "5e4d86b96fe7cabb898a7c6994f2a5a4294b6575096f2d89f3363ccff9b310aeb97cc2487d344500",
+        "955e26424961c6216eef600035a4050243033426d956b9341e98e5749f99ffd3d6cdeaa14b3a9e3d",
+        "338c7587251834ae996f7c8ac94ec3c3343087d51a6ba33b5d44aa88b180a0e8e35e1c4972b205cd",
+        "f64cfc49ce22e2adac2ea8f6390b0f9d958b59dcffb6d140d826ae1173d88e7dea91b20d74dbb385",
+        "9b8865ce5846d89418782a136ff7003b143487afea55ea42a9c60553d01d1b0aeea7f4a6845148f5",
+        "d914cd4711ff2249301860116a13b335674927d1b92f90a2fdc6d601a3487fc8cc6834199ab1f621",
+        "456ab07327ca1c3d56868e42db9c7a12ad6836b200be8b1dc1c7f7fcb274c8fedd171b666d770dfe",
+        "4a2f0b911a33405a7c18b98698dde26721eff0d6f4510e1fbd10bc9043993231886e87e6021d37d5",
+        "2a870034656a2025d6c0d86611486cdbbe97f61b5b1066987a16bb2575128c85e799c756bc990f23",
+        "035c1036d9eb3db6bbe4562208c31180743d7e507c0269f81ee4bc28cf07392ff3e5dfdda1fafa93",
+        "f57b9b52ed40b6c51f43c816de7a7d303ceac0f2441e2247",
+    );
+
+    #[test]
+    fn test_native_vector() {
+        let vk = VerifyingKey::read(&::hex::decode(NATIVE_VK).unwrap()[..]).unwrap();
+        let proof = Proof::read(&::hex::decode(NATIVE_PROOF).unwrap()[..]).unwrap();
+        let x = Scalar::from(17);
+        assert_eq!(verify_proof(&vk, &proof, &[x]), Ok(()));
+        assert_eq!(
+            verify_proof(&vk, &proof, &[x + Scalar::one()]),
+            Err(Error::VerifyFailed)
+        );
+        assert_eq!(
+            verify_proof_with::<OtherTranscript>(&vk, &proof, &[x]),
+            Err(Error::VerifyFailed)
+        );
+    }
+}
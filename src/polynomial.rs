@@ -0,0 +1,556 @@
+//! Dense univariate polynomials over the scalar field.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use fff::Field;
+use rand_core::RngCore;
+
+use crate::{BatchInvert, Error, EvaluationDomain, Scalar};
+
+/// Below this many coefficients, multiplication uses the schoolbook method.
+const MUL_FFT_THRESHOLD: usize = 32;
+
+/// Below this divisor degree, division uses long division.
+const DIV_NEWTON_THRESHOLD: usize = 64;
+
+/// Below this many points, evaluation uses Horner's rule at each point.
+const TREE_EVALUATE_THRESHOLD: usize = 1024;
+
+/// Below this many points, interpolation uses the quadratic Lagrange formula.
+const TREE_INTERPOLATE_THRESHOLD: usize = 128;
+
+/// A polynomial with coefficients in the scalar field, stored from the constant term
+/// up, without trailing zeros.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    coeffs: Vec<Scalar>,
+}
+
+impl Polynomial {
+    /// Returns the zero polynomial.
+    pub fn zero() -> Self {
+        Polynomial { coeffs: Vec::new() }
+    }
+
+    /// Returns the constant polynomial `c`.
+    pub fn constant(c: Scalar) -> Self {
+        Self::from_coeffs(vec![c])
+    }
+
+    /// Creates a polynomial from its coefficients, from the constant term up.
+    pub fn from_coeffs(coeffs: Vec<Scalar>) -> Self {
+        let mut p = Polynomial { coeffs };
+        p.truncate();
+        p
+    }
+
+    /// Samples a polynomial of degree at most `degree` with uniformly random
+    /// coefficients.
+    pub fn random<R: RngCore>(degree: usize, rng: &mut R) -> Self {
+        Self::from_coeffs((0..=degree).map(|_| Scalar::random(rng)).collect())
+    }
+
+    /// Returns the coefficients, from the constant term up, without trailing zeros.
+    pub fn coeffs(&self) -> &[Scalar] {
+        &self.coeffs
+    }
+
+    /// Consumes the polynomial and returns its coefficients.
+    pub fn into_coeffs(self) -> Vec<Scalar> {
+        self.coeffs
+    }
+
+    /// Returns whether this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Returns the degree, or zero for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: &Scalar) -> Scalar {
+        let mut res = Scalar::zero();
+        for c in self.coeffs.iter().rev() {
+            res.mul_assign(x);
+            res.add_assign(c);
+        }
+        res
+    }
+
+    /// Evaluates the polynomial at each of `xs`, with a subproduct tree when there are
+    /// many points.
+    pub fn evaluate_many(&self, xs: &[Scalar]) -> Vec<Scalar> {
+        if xs.len() < TREE_EVALUATE_THRESHOLD {
+            return xs.iter().map(|x| self.evaluate(x)).collect();
+        }
+
+        SubproductTree::new(xs).evaluate(self)
+    }
+
+    /// Divides by `divisor`, returning the quotient and the remainder, or `None` if
+    /// the divisor is zero.
+    pub fn div_rem(&self, divisor: &Polynomial) -> Option<(Polynomial, Polynomial)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return Some((Polynomial::zero(), self.clone()));
+        }
+
+        let q = if divisor.degree() < DIV_NEWTON_THRESHOLD {
+            self.long_div(divisor)
+        } else {
+            self.newton_div(divisor)
+        };
+        let r = self - &(&q * divisor);
+
+        Some((q, r))
+    }
+
+    /// Divides by `X - z`, returning the quotient and the remainder, which is the
+    /// value at `z`.
+    pub fn divide_by_linear(&self, z: &Scalar) -> (Polynomial, Scalar) {
+        if self.is_zero() {
+            return (Polynomial::zero(), Scalar::zero());
+        }
+
+        let mut quotient = vec![Scalar::zero(); self.coeffs.len() - 1];
+        let mut acc = Scalar::zero();
+        for (i, c) in self.coeffs.iter().enumerate().rev() {
+            acc.mul_assign(z);
+            acc.add_assign(c);
+            if i > 0 {
+                quotient[i - 1] = acc;
+            }
+        }
+
+        (Polynomial::from_coeffs(quotient), acc)
+    }
+
+    /// Interpolates the polynomial of degree less than `points.len()` through
+    /// `points`, given as `(x, y)` pairs, using a subproduct tree when there are many
+    /// points.
+    ///
+    /// Fails with `DuplicateInterpolationPoint` if two points share an x coordinate.
+    pub fn interpolate(points: &[(Scalar, Scalar)]) -> Result<Polynomial, Error> {
+        let xs: Vec<Scalar> = points.iter().map(|(x, _)| *x).collect();
+        if points.len() >= TREE_INTERPOLATE_THRESHOLD {
+            return SubproductTree::new(&xs).interpolate(points);
+        }
+
+        // p = sum_i y_i / M'(x_i) * M / (X - x_i), with M the product of the X - x_i.
+        let m = xs.iter().fold(Polynomial::constant(Scalar::one()), |m, x| {
+            &m * &Polynomial::linear(x)
+        });
+        let dm = m.derivative();
+        let mut weights: Vec<Scalar> = xs.iter().map(|x| dm.evaluate(x)).collect();
+        if Scalar::batch_invert(&mut weights) != 0 {
+            return Err(Error::DuplicateInterpolationPoint);
+        }
+
+        let mut coeffs = vec![Scalar::zero(); points.len()];
+        for (w, (x, y)) in weights.iter().zip(points) {
+            let c = w * y;
+            let q = m.divide_by_linear(x).0;
+            for (acc, qc) in coeffs.iter_mut().zip(&q.coeffs) {
+                acc.add_assign(&(c * qc));
+            }
+        }
+
+        Ok(Polynomial::from_coeffs(coeffs))
+    }
+
+    /// Returns the formal derivative.
+    fn derivative(&self) -> Polynomial {
+        Polynomial::from_coeffs(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, c)| Scalar::from(i as u64) * c)
+                .collect(),
+        )
+    }
+
+    /// Returns the monic polynomial `X - x`.
+    fn linear(x: &Scalar) -> Polynomial {
+        Polynomial::from_coeffs(vec![-x, Scalar::one()])
+    }
+
+    fn truncate(&mut self) {
+        while self.coeffs.last().map_or(false, |c| c.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+
+    /// Returns the quotient by `divisor` with schoolbook long division.
+    fn long_div(&self, divisor: &Polynomial) -> Polynomial {
+        let lead_inv = divisor.coeffs.last().unwrap().inverse().unwrap();
+        let mut rem = self.coeffs.clone();
+        let mut quotient = vec![Scalar::zero(); self.coeffs.len() - divisor.coeffs.len() + 1];
+        for i in (0..quotient.len()).rev() {
+            let c = rem[i + divisor.degree()] * lead_inv;
+            quotient[i] = c;
+            for (r, d) in rem[i..].iter_mut().zip(&divisor.coeffs) {
+                r.sub_assign(&(c * d));
+            }
+        }
+
+        Polynomial::from_coeffs(quotient)
+    }
+
+    /// Returns the quotient by `divisor` from the inverse of its reversal modulo a
+    /// power of `X`, computed with Newton iteration.
+    fn newton_div(&self, divisor: &Polynomial) -> Polynomial {
+        let k = self.coeffs.len() - divisor.coeffs.len() + 1;
+        let rev_divisor = divisor.reverse();
+        let inv = rev_divisor.inverse_mod_xk(k);
+
+        let mut rev_quotient = &self.reverse() * &inv;
+        rev_quotient.coeffs.truncate(k);
+        rev_quotient.coeffs.resize(k, Scalar::zero());
+        rev_quotient.coeffs.reverse();
+        rev_quotient.truncate();
+
+        rev_quotient
+    }
+
+    fn reverse(&self) -> Polynomial {
+        let mut coeffs = self.coeffs.clone();
+        coeffs.reverse();
+        Polynomial::from_coeffs(coeffs)
+    }
+
+    /// Returns the inverse modulo `X^k`, for a polynomial with a non-zero constant
+    /// term.
+    fn inverse_mod_xk(&self, k: usize) -> Polynomial {
+        // h <- h * (2 - f * h) doubles the precision of h at each step.
+        let mut h = Polynomial::constant(self.coeffs[0].inverse().unwrap());
+        let mut precision = 1;
+        while precision < k {
+            precision = (2 * precision).min(k);
+            let mut f = self.clone();
+            f.coeffs.truncate(precision);
+            let mut fh = &f * &h;
+            fh.coeffs.truncate(precision);
+            // f * h is one modulo X, so its negation has a constant term.
+            fh = -fh;
+            fh.coeffs[0].add_assign(&Scalar::from(2));
+            fh.truncate();
+            h = &h * &fh;
+            h.coeffs.truncate(precision);
+            h.truncate();
+        }
+
+        h
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        Polynomial {
+            coeffs: self.coeffs.iter().map(|c| -c).collect(),
+        }
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        -&self
+    }
+}
+
+impl Add<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: &Polynomial) -> Polynomial {
+        let (long, short) = if self.coeffs.len() >= rhs.coeffs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut coeffs = long.coeffs.clone();
+        for (a, b) in coeffs.iter_mut().zip(&short.coeffs) {
+            a.add_assign(b);
+        }
+
+        Polynomial::from_coeffs(coeffs)
+    }
+}
+
+impl Sub<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: &Polynomial) -> Polynomial {
+        self + &(-rhs)
+    }
+}
+
+impl Mul<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: &Polynomial) -> Polynomial {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let len = self.coeffs.len() + rhs.coeffs.len() - 1;
+        if self.coeffs.len().min(rhs.coeffs.len()) < MUL_FFT_THRESHOLD {
+            let mut coeffs = vec![Scalar::zero(); len];
+            for (i, a) in self.coeffs.iter().enumerate() {
+                for (c, b) in coeffs[i..].iter_mut().zip(&rhs.coeffs) {
+                    c.add_assign(&(a * b));
+                }
+            }
+            return Polynomial::from_coeffs(coeffs);
+        }
+
+        let domain = EvaluationDomain::new(len).expect("the product fits in a domain");
+        let mut a = self.coeffs.clone();
+        a.resize(domain.size(), Scalar::zero());
+        let mut b = rhs.coeffs.clone();
+        b.resize(domain.size(), Scalar::zero());
+        domain.fft(&mut a);
+        domain.fft(&mut b);
+        for (x, y) in a.iter_mut().zip(&b) {
+            x.mul_assign(y);
+        }
+        domain.ifft(&mut a);
+
+        Polynomial::from_coeffs(a)
+    }
+}
+
+impl Mul<&Scalar> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: &Scalar) -> Polynomial {
+        Polynomial::from_coeffs(self.coeffs.iter().map(|c| c * rhs).collect())
+    }
+}
+
+impl_binops_additive_specify_output!(Polynomial, Polynomial, Polynomial);
+impl_binops_multiplicative_mixed!(Polynomial, Polynomial, Polynomial);
+impl_binops_multiplicative_mixed!(Polynomial, Scalar, Polynomial);
+
+/// The products of `X - x_i` over a binary tree of the points `x_i`.
+struct SubproductTree {
+    xs: Vec<Scalar>,
+    /// Levels from the leaves up, each node being the product of its children.
+    levels: Vec<Vec<Polynomial>>,
+}
+
+/// The level whose nodes, of `2^EVALUATE_LEVEL` points, are evaluated with Horner's
+/// rule rather than reduced further.
+const EVALUATE_LEVEL: usize = 7;
+
+impl SubproductTree {
+    fn new(xs: &[Scalar]) -> Self {
+        let mut levels = vec![xs.iter().map(Polynomial::linear).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a * b,
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        SubproductTree {
+            xs: xs.to_vec(),
+            levels,
+        }
+    }
+
+    fn root(&self) -> &Polynomial {
+        &self.levels.last().unwrap()[0]
+    }
+
+    /// Evaluates `p` at the leaves by reducing it modulo each node, going down.
+    fn evaluate(&self, p: &Polynomial) -> Vec<Scalar> {
+        let mut rems = vec![p.div_rem(self.root()).unwrap().1];
+        let bottom = EVALUATE_LEVEL.min(self.levels.len() - 1);
+        for level in self.levels[bottom..].iter().rev().skip(1) {
+            rems = level
+                .iter()
+                .enumerate()
+                .map(|(i, node)| rems[i / 2].div_rem(node).unwrap().1)
+                .collect();
+        }
+
+        // The remainders are small enough to evaluate directly at their points.
+        self.xs
+            .chunks(1 << bottom)
+            .zip(&rems)
+            .flat_map(|(xs, r)| xs.iter().map(move |x| r.evaluate(x)))
+            .collect()
+    }
+
+    /// Interpolates through `points`, whose x coordinates are the leaves, by
+    /// combining the weighted leaves going up.
+    fn interpolate(&self, points: &[(Scalar, Scalar)]) -> Result<Polynomial, Error> {
+        // The weights are y_i / M'(x_i), with M the root.
+        let derivative = self.root().derivative();
+        let mut weights = self.evaluate(&derivative);
+        if Scalar::batch_invert(&mut weights) != 0 {
+            return Err(Error::DuplicateInterpolationPoint);
+        }
+
+        let mut acc: Vec<Polynomial> = weights
+            .iter()
+            .zip(points)
+            .map(|(w, (_, y))| Polynomial::constant(w * y))
+            .collect();
+        for level in &self.levels[..self.levels.len() - 1] {
+            acc = acc
+                .chunks(2)
+                .zip(level.chunks(2))
+                .map(|(a, m)| match (a, m) {
+                    ([a0, a1], [m0, m1]) => &(a0 * m1) + &(a1 * m0),
+                    ([a0], [_]) => a0.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        Ok(acc.pop().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ])
+    }
+
+    #[test]
+    fn test_polynomial_arithmetic() {
+        let mut rng = rng();
+
+        let zero = Polynomial::zero();
+        assert!(zero.is_zero());
+        assert_eq!(zero.degree(), 0);
+        assert_eq!(
+            Polynomial::from_coeffs(vec![Scalar::one(), Scalar::zero()]),
+            Polynomial::constant(Scalar::one())
+        );
+        assert!(Polynomial::constant(Scalar::zero()).is_zero());
+
+        for _ in 0..10 {
+            let a = Polynomial::random(10, &mut rng);
+            let b = Polynomial::random(5, &mut rng);
+            let c = Scalar::random(&mut rng);
+            let x = Scalar::random(&mut rng);
+
+            assert_eq!((&a + &b).evaluate(&x), a.evaluate(&x) + b.evaluate(&x));
+            assert_eq!((&a - &b).evaluate(&x), a.evaluate(&x) - b.evaluate(&x));
+            assert_eq!((-&a).evaluate(&x), -a.evaluate(&x));
+            assert_eq!((&a * c).evaluate(&x), a.evaluate(&x) * c);
+            assert_eq!((&a * &b).evaluate(&x), a.evaluate(&x) * b.evaluate(&x));
+            assert!((&a - &a).is_zero());
+            assert!((&a * &zero).is_zero());
+        }
+    }
+
+    #[test]
+    fn test_polynomial_mul_fft() {
+        let mut rng = rng();
+
+        for &(da, db) in &[(40, 40), (100, 31), (255, 256)] {
+            let a = Polynomial::random(da, &mut rng);
+            let b = Polynomial::random(db, &mut rng);
+
+            // Schoolbook product.
+            let mut coeffs = vec![Scalar::zero(); da + db + 1];
+            for (i, x) in a.coeffs().iter().enumerate() {
+                for (j, y) in b.coeffs().iter().enumerate() {
+                    coeffs[i + j].add_assign(&(x * y));
+                }
+            }
+            assert_eq!(&a * &b, Polynomial::from_coeffs(coeffs));
+        }
+    }
+
+    #[test]
+    fn test_polynomial_division() {
+        let mut rng = rng();
+
+        for &(da, db) in &[(10, 3), (3, 10), (200, 100), (300, 70), (100, 100)] {
+            let a = Polynomial::random(da, &mut rng);
+            let b = Polynomial::random(db, &mut rng);
+            let (q, r) = a.div_rem(&b).unwrap();
+            assert!(r.is_zero() || r.degree() < b.degree());
+            assert_eq!(&(&q * &b) + &r, a);
+
+            // Exact division.
+            let (q, r) = (&a * &b).div_rem(&b).unwrap();
+            assert!(r.is_zero());
+            assert_eq!(q, a);
+        }
+
+        assert!(Polynomial::random(5, &mut rng)
+            .div_rem(&Polynomial::zero())
+            .is_none());
+
+        let a = Polynomial::random(20, &mut rng);
+        let z = Scalar::random(&mut rng);
+        let (q, r) = a.divide_by_linear(&z);
+        assert_eq!(r, a.evaluate(&z));
+        assert_eq!(
+            &(&q * &Polynomial::linear(&z)) + &Polynomial::constant(r),
+            a
+        );
+        assert_eq!(
+            Polynomial::zero().divide_by_linear(&z),
+            (Polynomial::zero(), Scalar::zero())
+        );
+    }
+
+    #[test]
+    fn test_polynomial_interpolate() {
+        let mut rng = rng();
+
+        for &n in &[1, 2, 10, 127, 128, 257, 1100] {
+            let p = Polynomial::random(n - 1, &mut rng);
+            let xs: Vec<Scalar> = (0..n).map(|_| Scalar::random(&mut rng)).collect();
+            let ys = p.evaluate_many(&xs);
+            for (x, y) in xs.iter().zip(&ys) {
+                assert_eq!(p.evaluate(x), *y);
+            }
+
+            let points: Vec<(Scalar, Scalar)> = xs.iter().copied().zip(ys).collect();
+            assert_eq!(Polynomial::interpolate(&points).unwrap(), p);
+
+            if n > 1 {
+                let mut points = points.clone();
+                points[n - 1].0 = points[0].0;
+                assert_eq!(
+                    Polynomial::interpolate(&points),
+                    Err(Error::DuplicateInterpolationPoint)
+                );
+            }
+        }
+
+        assert!(Polynomial::interpolate(&[]).unwrap().is_zero());
+    }
+}
@@ -0,0 +1,803 @@
+//! Readers and writers for the files of powers of tau ceremonies.
+//!
+//! Two formats are supported:
+//!
+//! - The phase 1 files of the Zcash ceremony, used to generate Groth16 parameters.
+//!   A challenge file holds the hash of the previous response and an `Accumulator`
+//!   of uncompressed points. A response file holds the hash of the challenge, the
+//!   accumulator with compressed points and the `PublicKey` of the contribution.
+//! - The JSON transcript of the Ethereum KZG ceremony, a `CeremonyTranscript`, which
+//!   implements the serde traits with field names and hexadecimal points as in the
+//!   published files, for use with `serde_json`.
+//!
+//! Points are only checked to be on the curve when read. Subgroup checks are
+//! expensive and optional, with `check_subgroups`, and the pairing checks that
+//! successive points are powers of the same `tau` with `verify_consistency`.
+
+use std::io::{self, Read, Write};
+
+use fff::Field;
+use groupy::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{multi_pairing, Error, Fp12, G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+
+/// The size of the BLAKE2b hashes at the start of challenge and response files.
+pub const HASH_SIZE: usize = 64;
+
+/// The encoding of the points of an accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointEncoding {
+    /// Compressed points, as in response files.
+    Compressed,
+    /// Uncompressed points, as in challenge files.
+    Uncompressed,
+}
+
+/// The powers of `tau`, and of `tau` times `alpha` and `beta`, of phase 1.
+///
+/// For `n` the number of powers, there are `2 n - 1` powers of `tau` in
+/// $\mathbb{G}_1$, and `n` of all others.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accumulator {
+    /// `[tau^i]_1` for `i` in `0..2 n - 1`.
+    pub tau_powers_g1: Vec<G1Affine>,
+    /// `[tau^i]_2` for `i` in `0..n`.
+    pub tau_powers_g2: Vec<G2Affine>,
+    /// `[alpha tau^i]_1` for `i` in `0..n`.
+    pub alpha_tau_powers_g1: Vec<G1Affine>,
+    /// `[beta tau^i]_1` for `i` in `0..n`.
+    pub beta_tau_powers_g1: Vec<G1Affine>,
+    /// `[beta]_2`.
+    pub beta_g2: G2Affine,
+}
+
+impl Accumulator {
+    /// Creates the accumulator of `n` powers that starts a ceremony, where `tau`,
+    /// `alpha` and `beta` are all one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "the accumulator must not be empty");
+
+        Accumulator {
+            tau_powers_g1: vec![G1Affine::one(); 2 * n - 1],
+            tau_powers_g2: vec![G2Affine::one(); n],
+            alpha_tau_powers_g1: vec![G1Affine::one(); n],
+            beta_tau_powers_g1: vec![G1Affine::one(); n],
+            beta_g2: G2Affine::one(),
+        }
+    }
+
+    /// Returns the number of powers `n`.
+    pub fn len(&self) -> usize {
+        self.tau_powers_g2.len()
+    }
+
+    /// Returns true if there are no powers, which is never the case for an
+    /// accumulator that was read or created with `new`.
+    pub fn is_empty(&self) -> bool {
+        self.tau_powers_g2.is_empty()
+    }
+
+    /// Reads an accumulator of `n` powers with the given point encoding.
+    pub fn read<R: Read>(mut reader: R, n: usize, encoding: PointEncoding) -> io::Result<Self> {
+        Ok(Accumulator {
+            tau_powers_g1: read_g1_vec(&mut reader, (2 * n).saturating_sub(1), encoding)?,
+            tau_powers_g2: read_g2_vec(&mut reader, n, encoding)?,
+            alpha_tau_powers_g1: read_g1_vec(&mut reader, n, encoding)?,
+            beta_tau_powers_g1: read_g1_vec(&mut reader, n, encoding)?,
+            beta_g2: read_g2(&mut reader, encoding)?,
+        })
+    }
+
+    /// Writes the accumulator with the given point encoding.
+    pub fn write<W: Write>(&self, mut writer: W, encoding: PointEncoding) -> io::Result<()> {
+        for p in &self.tau_powers_g1 {
+            write_g1(&mut writer, p, encoding)?;
+        }
+        for p in &self.tau_powers_g2 {
+            write_g2(&mut writer, p, encoding)?;
+        }
+        for p in self
+            .alpha_tau_powers_g1
+            .iter()
+            .chain(&self.beta_tau_powers_g1)
+        {
+            write_g1(&mut writer, p, encoding)?;
+        }
+        write_g2(&mut writer, &self.beta_g2, encoding)
+    }
+
+    /// Checks that all points are in the prime order subgroups.
+    pub fn check_subgroups(&self) -> Result<(), Error> {
+        check_g1_subgroup(&self.tau_powers_g1)?;
+        check_g2_subgroup(&self.tau_powers_g2)?;
+        check_g1_subgroup(&self.alpha_tau_powers_g1)?;
+        check_g1_subgroup(&self.beta_tau_powers_g1)?;
+        check_g2_subgroup(&[self.beta_g2])
+    }
+
+    /// Like `check_subgroups`, checking the points in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn par_check_subgroups(&self) -> Result<(), Error> {
+        par_check_g1_subgroup(&self.tau_powers_g1)?;
+        par_check_g2_subgroup(&self.tau_powers_g2)?;
+        par_check_g1_subgroup(&self.alpha_tau_powers_g1)?;
+        par_check_g1_subgroup(&self.beta_tau_powers_g1)?;
+        par_check_g2_subgroup(&[self.beta_g2])
+    }
+
+    /// Checks with `verify_powers` that the points are powers of the same `tau`,
+    /// and that the `alpha` and `beta` points are consistent with them.
+    ///
+    /// Fails with `LengthMismatch` if the vectors do not have the lengths of an
+    /// accumulator of at least two powers.
+    pub fn verify_consistency<R: RngCore>(&self, rng: &mut R) -> Result<(), Error> {
+        let n = self.len();
+        if n < 2
+            || self.tau_powers_g1.len() != 2 * n - 1
+            || self.alpha_tau_powers_g1.len() != n
+            || self.beta_tau_powers_g1.len() != n
+        {
+            return Err(Error::LengthMismatch);
+        }
+        verify_powers(&self.tau_powers_g1, &self.tau_powers_g2, rng)?;
+
+        let g2 = G2Affine::one();
+        let tau_g2 = self.tau_powers_g2[1];
+        if self.alpha_tau_powers_g1[0].is_zero() || self.beta_tau_powers_g1[0].is_zero() {
+            return Err(Error::VerifyFailed);
+        }
+        same_ratio_g1(&self.alpha_tau_powers_g1, (&g2, &tau_g2), rng)?;
+        same_ratio_g1(&self.beta_tau_powers_g1, (&g2, &tau_g2), rng)?;
+        // e([1]_1, [beta]_2) = e([beta]_1, [1]_2)
+        same_ratio(
+            (&G1Affine::one(), &self.beta_tau_powers_g1[0]),
+            (&g2, &self.beta_g2),
+        )
+    }
+}
+
+/// The public key of a contribution to phase 1, proving knowledge of its secrets.
+///
+/// Each pair in $\mathbb{G}_1$ is a random point `s` and `s * x`, for `x` the secret
+/// `tau`, `alpha` or `beta`, and the point in $\mathbb{G}_2$ is `x` times a point
+/// derived from `s`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub tau_g1: (G1Affine, G1Affine),
+    pub alpha_g1: (G1Affine, G1Affine),
+    pub beta_g1: (G1Affine, G1Affine),
+    pub tau_g2: G2Affine,
+    pub alpha_g2: G2Affine,
+    pub beta_g2: G2Affine,
+}
+
+impl PublicKey {
+    /// Reads a public key of uncompressed points.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let encoding = PointEncoding::Uncompressed;
+        Ok(PublicKey {
+            tau_g1: (
+                read_g1(&mut reader, encoding)?,
+                read_g1(&mut reader, encoding)?,
+            ),
+            alpha_g1: (
+                read_g1(&mut reader, encoding)?,
+                read_g1(&mut reader, encoding)?,
+            ),
+            beta_g1: (
+                read_g1(&mut reader, encoding)?,
+                read_g1(&mut reader, encoding)?,
+            ),
+            tau_g2: read_g2(&mut reader, encoding)?,
+            alpha_g2: read_g2(&mut reader, encoding)?,
+            beta_g2: read_g2(&mut reader, encoding)?,
+        })
+    }
+
+    /// Writes the public key with uncompressed points.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let encoding = PointEncoding::Uncompressed;
+        for (s, sx) in &[self.tau_g1, self.alpha_g1, self.beta_g1] {
+            write_g1(&mut writer, s, encoding)?;
+            write_g1(&mut writer, sx, encoding)?;
+        }
+        for p in &[self.tau_g2, self.alpha_g2, self.beta_g2] {
+            write_g2(&mut writer, p, encoding)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a challenge file of `n` powers, returning the hash it starts with and the
+/// accumulator.
+pub fn read_challenge<R: Read>(
+    mut reader: R,
+    n: usize,
+) -> io::Result<([u8; HASH_SIZE], Accumulator)> {
+    let mut hash = [0; HASH_SIZE];
+    reader.read_exact(&mut hash)?;
+    let accumulator = Accumulator::read(reader, n, PointEncoding::Uncompressed)?;
+
+    Ok((hash, accumulator))
+}
+
+/// Writes a challenge file.
+pub fn write_challenge<W: Write>(
+    mut writer: W,
+    hash: &[u8; HASH_SIZE],
+    accumulator: &Accumulator,
+) -> io::Result<()> {
+    writer.write_all(hash)?;
+    accumulator.write(writer, PointEncoding::Uncompressed)
+}
+
+/// Reads a response file of `n` powers, returning the hash it starts with, the
+/// accumulator and the public key of the contribution.
+pub fn read_response<R: Read>(
+    mut reader: R,
+    n: usize,
+) -> io::Result<([u8; HASH_SIZE], Accumulator, PublicKey)> {
+    let mut hash = [0; HASH_SIZE];
+    reader.read_exact(&mut hash)?;
+    let accumulator = Accumulator::read(&mut reader, n, PointEncoding::Compressed)?;
+    let public_key = PublicKey::read(reader)?;
+
+    Ok((hash, accumulator, public_key))
+}
+
+/// Writes a response file.
+pub fn write_response<W: Write>(
+    mut writer: W,
+    hash: &[u8; HASH_SIZE],
+    accumulator: &Accumulator,
+    public_key: &PublicKey,
+) -> io::Result<()> {
+    writer.write_all(hash)?;
+    accumulator.write(&mut writer, PointEncoding::Compressed)?;
+    public_key.write(writer)
+}
+
+/// The transcript of the Ethereum KZG ceremony, with one sub-transcript per
+/// setup size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CeremonyTranscript {
+    pub transcripts: Vec<Transcript>,
+    #[serde(default)]
+    pub participant_ids: Vec<String>,
+    #[serde(default)]
+    pub participant_ecdsa_signatures: Vec<String>,
+}
+
+/// The setup of a given size of the Ethereum KZG ceremony, with the witness of the
+/// contributions to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub num_g1_powers: usize,
+    pub num_g2_powers: usize,
+    pub powers_of_tau: PowersOfTau,
+    pub witness: Witness,
+}
+
+/// The powers `[tau^i]_1` and `[tau^i]_2`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowersOfTau {
+    #[serde(rename = "G1Powers", with = "hex_g1")]
+    pub g1_powers: Vec<G1Affine>,
+    #[serde(rename = "G2Powers", with = "hex_g2")]
+    pub g2_powers: Vec<G2Affine>,
+}
+
+/// The contributions to a setup: after contribution `i` with secret `x_i`, the
+/// running product `[x_1 ... x_i]_1`, the public key `[x_i]_2` and a signature of
+/// the participant, which is empty if they did not sign.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Witness {
+    #[serde(with = "hex_g1")]
+    pub running_products: Vec<G1Affine>,
+    #[serde(with = "hex_g2")]
+    pub pot_pubkeys: Vec<G2Affine>,
+    pub bls_signatures: Vec<String>,
+}
+
+impl Transcript {
+    /// Checks that all points are in the prime order subgroups.
+    pub fn check_subgroups(&self) -> Result<(), Error> {
+        check_g1_subgroup(&self.powers_of_tau.g1_powers)?;
+        check_g2_subgroup(&self.powers_of_tau.g2_powers)?;
+        check_g1_subgroup(&self.witness.running_products)?;
+        check_g2_subgroup(&self.witness.pot_pubkeys)
+    }
+
+    /// Like `check_subgroups`, checking the points in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn par_check_subgroups(&self) -> Result<(), Error> {
+        par_check_g1_subgroup(&self.powers_of_tau.g1_powers)?;
+        par_check_g2_subgroup(&self.powers_of_tau.g2_powers)?;
+        par_check_g1_subgroup(&self.witness.running_products)?;
+        par_check_g2_subgroup(&self.witness.pot_pubkeys)
+    }
+
+    /// Checks the numbers of powers and, with `verify_powers`, that they are powers
+    /// of the same `tau`.
+    ///
+    /// Fails with `LengthMismatch` if the numbers of powers do not match the
+    /// declared ones or are less than two.
+    pub fn verify_consistency<R: RngCore>(&self, rng: &mut R) -> Result<(), Error> {
+        let powers = &self.powers_of_tau;
+        if powers.g1_powers.len() != self.num_g1_powers
+            || powers.g2_powers.len() != self.num_g2_powers
+            || self.num_g1_powers < 2
+            || self.num_g2_powers < 2
+        {
+            return Err(Error::LengthMismatch);
+        }
+
+        verify_powers(&powers.g1_powers, &powers.g2_powers, rng)
+    }
+}
+
+/// Checks that `g1_powers` and `g2_powers` are `[tau^i]_1` and `[tau^i]_2` for
+/// the same non-zero `tau`, with a random linear combination of the pairing checks
+/// `e([tau^(i + 1)]_1, [1]_2) = e([tau^i]_1, [tau]_2)`, and similarly in
+/// $\mathbb{G}_2$.
+///
+/// Fails with `LengthMismatch` if there are fewer than two powers in either group.
+pub fn verify_powers<R: RngCore>(
+    g1_powers: &[G1Affine],
+    g2_powers: &[G2Affine],
+    rng: &mut R,
+) -> Result<(), Error> {
+    if g1_powers.len() < 2 || g2_powers.len() < 2 {
+        return Err(Error::LengthMismatch);
+    }
+    if g1_powers[0] != G1Affine::one() || g2_powers[0] != G2Affine::one() || g1_powers[1].is_zero()
+    {
+        return Err(Error::VerifyFailed);
+    }
+
+    same_ratio_g1(g1_powers, (&g2_powers[0], &g2_powers[1]), rng)?;
+    same_ratio_g2(g2_powers, (&g1_powers[0], &g1_powers[1]), rng)
+}
+
+/// Checks `e(a.0, b.1) = e(a.1, b.0)`, that is that `a.1 / a.0 = b.1 / b.0`.
+pub(crate) fn same_ratio(
+    a: (&G1Affine, &G1Affine),
+    b: (&G2Affine, &G2Affine),
+) -> Result<(), Error> {
+    let mut neg = *a.1;
+    neg.negate();
+
+    if multi_pairing(&[(a.0, b.1), (&neg, b.0)]) == Fp12::one() {
+        Ok(())
+    } else {
+        Err(Error::VerifyFailed)
+    }
+}
+
+/// Checks that each point of `points` is the previous one times the ratio
+/// `ratio.1 / ratio.0`, with a random linear combination of the checks.
+pub(crate) fn same_ratio_g1<R: RngCore>(
+    points: &[G1Affine],
+    ratio: (&G2Affine, &G2Affine),
+    rng: &mut R,
+) -> Result<(), Error> {
+    let coeffs: Vec<Scalar> = (1..points.len()).map(|_| Scalar::random(rng)).collect();
+    let lo = G1Projective::multi_exp_vartime(&points[..points.len() - 1], &coeffs).into_affine();
+    let hi = G1Projective::multi_exp_vartime(&points[1..], &coeffs).into_affine();
+
+    same_ratio((&lo, &hi), ratio)
+}
+
+/// Like `same_ratio_g1` for points of $\mathbb{G}_2$.
+pub(crate) fn same_ratio_g2<R: RngCore>(
+    points: &[G2Affine],
+    ratio: (&G1Affine, &G1Affine),
+    rng: &mut R,
+) -> Result<(), Error> {
+    let coeffs: Vec<Scalar> = (1..points.len()).map(|_| Scalar::random(rng)).collect();
+    let lo = G2Projective::multi_exp_vartime(&points[..points.len() - 1], &coeffs).into_affine();
+    let hi = G2Projective::multi_exp_vartime(&points[1..], &coeffs).into_affine();
+
+    same_ratio(ratio, (&lo, &hi))
+}
+
+fn check_g1_subgroup(points: &[G1Affine]) -> Result<(), Error> {
+    if points.iter().all(|p| p.is_zero() || p.is_torsion_free()) {
+        Ok(())
+    } else {
+        Err(Error::PointNotInGroup)
+    }
+}
+
+fn check_g2_subgroup(points: &[G2Affine]) -> Result<(), Error> {
+    if points.iter().all(|p| p.is_zero() || p.is_torsion_free()) {
+        Ok(())
+    } else {
+        Err(Error::PointNotInGroup)
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn par_check_g1_subgroup(points: &[G1Affine]) -> Result<(), Error> {
+    use rayon::prelude::*;
+
+    if points
+        .par_iter()
+        .all(|p| p.is_zero() || p.is_torsion_free())
+    {
+        Ok(())
+    } else {
+        Err(Error::PointNotInGroup)
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn par_check_g2_subgroup(points: &[G2Affine]) -> Result<(), Error> {
+    use rayon::prelude::*;
+
+    if points
+        .par_iter()
+        .all(|p| p.is_zero() || p.is_torsion_free())
+    {
+        Ok(())
+    } else {
+        Err(Error::PointNotInGroup)
+    }
+}
+
+fn read_g1<R: Read>(reader: &mut R, encoding: PointEncoding) -> io::Result<G1Affine> {
+    match encoding {
+        PointEncoding::Compressed => {
+            let mut bytes = [0; 48];
+            reader.read_exact(&mut bytes)?;
+            Ok(G1Affine::from_compressed_unchecked(&bytes)?)
+        }
+        PointEncoding::Uncompressed => {
+            let mut bytes = [0; 96];
+            reader.read_exact(&mut bytes)?;
+            Ok(G1Affine::from_uncompressed_unchecked(&bytes)?)
+        }
+    }
+}
+
+fn read_g2<R: Read>(reader: &mut R, encoding: PointEncoding) -> io::Result<G2Affine> {
+    match encoding {
+        PointEncoding::Compressed => {
+            let mut bytes = [0; 96];
+            reader.read_exact(&mut bytes)?;
+            Ok(G2Affine::from_compressed_unchecked(&bytes)?)
+        }
+        PointEncoding::Uncompressed => {
+            let mut bytes = [0; 192];
+            reader.read_exact(&mut bytes)?;
+            Ok(G2Affine::from_uncompressed_unchecked(&bytes)?)
+        }
+    }
+}
+
+fn read_g1_vec<R: Read>(
+    reader: &mut R,
+    n: usize,
+    encoding: PointEncoding,
+) -> io::Result<Vec<G1Affine>> {
+    (0..n).map(|_| read_g1(reader, encoding)).collect()
+}
+
+fn read_g2_vec<R: Read>(
+    reader: &mut R,
+    n: usize,
+    encoding: PointEncoding,
+) -> io::Result<Vec<G2Affine>> {
+    (0..n).map(|_| read_g2(reader, encoding)).collect()
+}
+
+fn write_g1<W: Write>(writer: &mut W, p: &G1Affine, encoding: PointEncoding) -> io::Result<()> {
+    match encoding {
+        PointEncoding::Compressed => writer.write_all(&p.to_compressed()),
+        PointEncoding::Uncompressed => writer.write_all(&p.to_uncompressed()),
+    }
+}
+
+fn write_g2<W: Write>(writer: &mut W, p: &G2Affine, encoding: PointEncoding) -> io::Result<()> {
+    match encoding {
+        PointEncoding::Compressed => writer.write_all(&p.to_compressed()),
+        PointEncoding::Uncompressed => writer.write_all(&p.to_uncompressed()),
+    }
+}
+
+/// Serde for vectors of points as `0x` prefixed hexadecimal compressed points,
+/// which are only checked to be on the curve.
+macro_rules! hex_points_serde {
+    ($name:ident, $affine:ident, $size:expr) => {
+        mod $name {
+            use serde::de::Error as _;
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            use crate::{hexstr, $affine};
+
+            pub fn serialize<S: Serializer>(points: &[$affine], s: S) -> Result<S::Ok, S::Error> {
+                s.collect_seq(
+                    points
+                        .iter()
+                        .map(|p| format!("0x{}", hexstr::encode(&p.to_compressed()))),
+                )
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<$affine>, D::Error> {
+                let strings = Vec::<String>::deserialize(d)?;
+                strings
+                    .iter()
+                    .map(|s| {
+                        let mut bytes = [0; $size];
+                        s.strip_prefix("0x")
+                            .and_then(|hex| hexstr::decode(hex, &mut bytes))
+                            .ok_or_else(|| D::Error::custom("invalid hexadecimal point"))?;
+                        $affine::from_compressed_unchecked(&bytes).map_err(D::Error::custom)
+                    })
+                    .collect()
+            }
+        }
+    };
+}
+
+hex_points_serde!(hex_g1, G1Affine, 48);
+hex_points_serde!(hex_g2, G2Affine, 96);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn powers_g1(base: G1Projective, tau: &Scalar, n: usize) -> Vec<G1Affine> {
+        let mut cur = base;
+        (0..n)
+            .map(|_| {
+                let p = cur.into_affine();
+                cur *= tau;
+                p
+            })
+            .collect()
+    }
+
+    fn random_accumulator<R: RngCore>(n: usize, rng: &mut R) -> Accumulator {
+        let tau = Scalar::random(rng);
+        let alpha = Scalar::random(rng);
+        let beta = Scalar::random(rng);
+
+        let mut cur = G2Projective::one();
+        let tau_powers_g2 = (0..n)
+            .map(|_| {
+                let p = cur.into_affine();
+                cur *= tau;
+                p
+            })
+            .collect();
+
+        Accumulator {
+            tau_powers_g1: powers_g1(G1Projective::one(), &tau, 2 * n - 1),
+            tau_powers_g2,
+            alpha_tau_powers_g1: powers_g1(G1Projective::one() * alpha, &tau, n),
+            beta_tau_powers_g1: powers_g1(G1Projective::one() * beta, &tau, n),
+            beta_g2: (G2Affine::one() * beta).into_affine(),
+        }
+    }
+
+    /// Returns a point of the curve outside of $\mathbb{G}_1$.
+    fn non_subgroup_g1() -> G1Affine {
+        let mut bytes = [0; 48];
+        bytes[0] = 0x80;
+        for x in 1.. {
+            bytes[47] = x;
+            if let Ok(p) = G1Affine::from_compressed_unchecked(&bytes) {
+                if !p.is_torsion_free() {
+                    return p;
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_powers_of_tau_files() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let accumulator = random_accumulator(8, &mut rng);
+        let mut hash = [0; HASH_SIZE];
+        rng.fill_bytes(&mut hash);
+
+        let mut challenge = Vec::new();
+        write_challenge(&mut challenge, &hash, &accumulator).unwrap();
+        assert_eq!(
+            challenge.len(),
+            HASH_SIZE + 15 * 96 + 8 * 192 + 16 * 96 + 192
+        );
+        assert_eq!(
+            read_challenge(&challenge[..], 8).unwrap(),
+            (hash, accumulator.clone())
+        );
+        assert!(read_challenge(&challenge[..challenge.len() - 1], 8).is_err());
+
+        let s = G1Projective::random(&mut rng);
+        let x = Scalar::random(&mut rng);
+        let public_key = PublicKey {
+            tau_g1: (s.into_affine(), (s * x).into_affine()),
+            alpha_g1: (s.into_affine(), (s * x).into_affine()),
+            beta_g1: (G1Affine::zero(), s.into_affine()),
+            tau_g2: G2Projective::random(&mut rng).into_affine(),
+            alpha_g2: G2Affine::zero(),
+            beta_g2: G2Affine::one(),
+        };
+        let mut response = Vec::new();
+        write_response(&mut response, &hash, &accumulator, &public_key).unwrap();
+        assert_eq!(
+            response.len(),
+            HASH_SIZE + 15 * 48 + 8 * 96 + 16 * 48 + 96 + 6 * 96 + 3 * 192
+        );
+        assert_eq!(
+            read_response(&response[..], 8).unwrap(),
+            (hash, accumulator.clone(), public_key)
+        );
+
+        // Points off the curve are rejected, points outside of the subgroups are not.
+        let mut bad = challenge.clone();
+        bad[HASH_SIZE + 96 + 95] ^= 1;
+        assert!(read_challenge(&bad[..], 8).is_err());
+
+        let mut outside = accumulator.clone();
+        outside.beta_tau_powers_g1[3] = non_subgroup_g1();
+        let mut challenge = Vec::new();
+        write_challenge(&mut challenge, &hash, &outside).unwrap();
+        let (_, read) = read_challenge(&challenge[..], 8).unwrap();
+        assert_eq!(read, outside);
+        assert_eq!(accumulator.check_subgroups(), Ok(()));
+        assert_eq!(read.check_subgroups(), Err(Error::PointNotInGroup));
+        #[cfg(feature = "parallel")]
+        {
+            assert_eq!(accumulator.par_check_subgroups(), Ok(()));
+            assert_eq!(read.par_check_subgroups(), Err(Error::PointNotInGroup));
+        }
+    }
+
+    #[test]
+    fn test_accumulator_consistency() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert_eq!(Accumulator::new(4).verify_consistency(&mut rng), Ok(()));
+
+        let accumulator = random_accumulator(8, &mut rng);
+        assert_eq!(accumulator.len(), 8);
+        assert_eq!(accumulator.verify_consistency(&mut rng), Ok(()));
+
+        let random = G1Projective::random(&mut rng).into_affine();
+        let mut bad = accumulator.clone();
+        bad.tau_powers_g1[11] = random;
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::VerifyFailed));
+        let mut bad = accumulator.clone();
+        bad.tau_powers_g2.swap(2, 3);
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::VerifyFailed));
+        let mut bad = accumulator.clone();
+        bad.alpha_tau_powers_g1[7] = random;
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::VerifyFailed));
+        let mut bad = accumulator.clone();
+        bad.beta_g2 = G2Affine::one();
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::VerifyFailed));
+        let mut bad = accumulator.clone();
+        bad.tau_powers_g1[0] = random;
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::VerifyFailed));
+
+        let mut bad = accumulator.clone();
+        bad.beta_tau_powers_g1.pop();
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::LengthMismatch));
+        assert_eq!(
+            Accumulator::new(1).verify_consistency(&mut rng),
+            Err(Error::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn test_ceremony_transcript() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // The generators, as in the transcript before any contribution.
+        let json = r#"{
+            "transcripts": [{
+                "numG1Powers": 2,
+                "numG2Powers": 2,
+                "powersOfTau": {
+                    "G1Powers": [
+                        "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+                        "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+                    ],
+                    "G2Powers": [
+                        "0x93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+                        "0x93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+                    ]
+                },
+                "witness": {
+                    "runningProducts": ["0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"],
+                    "potPubkeys": ["0x93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"],
+                    "blsSignatures": [""]
+                }
+            }],
+            "participantIds": [""],
+            "participantEcdsaSignatures": [""]
+        }"#;
+        let ceremony: CeremonyTranscript = serde_json::from_str(json).unwrap();
+        let transcript = &ceremony.transcripts[0];
+        assert_eq!(transcript.powers_of_tau.g1_powers, vec![G1Affine::one(); 2]);
+        assert_eq!(transcript.powers_of_tau.g2_powers, vec![G2Affine::one(); 2]);
+        assert_eq!(transcript.witness.running_products, vec![G1Affine::one()]);
+        assert_eq!(transcript.witness.bls_signatures, vec![String::new()]);
+        assert_eq!(transcript.check_subgroups(), Ok(()));
+        assert_eq!(transcript.verify_consistency(&mut rng), Ok(()));
+
+        // A setup after a contribution, written and read back.
+        let tau = Scalar::random(&mut rng);
+        let mut transcript = transcript.clone();
+        transcript.num_g1_powers = 16;
+        transcript.num_g2_powers = 4;
+        transcript.powers_of_tau.g1_powers = powers_g1(G1Projective::one(), &tau, 16);
+        transcript.powers_of_tau.g2_powers = (0..4)
+            .map(|i| (G2Affine::one() * tau.pow([i])).into_affine())
+            .collect();
+        transcript.witness.running_products = vec![transcript.powers_of_tau.g1_powers[1]];
+        transcript.witness.pot_pubkeys = vec![transcript.powers_of_tau.g2_powers[1]];
+        assert_eq!(transcript.verify_consistency(&mut rng), Ok(()));
+        #[cfg(feature = "parallel")]
+        assert_eq!(transcript.par_check_subgroups(), Ok(()));
+
+        let ceremony = CeremonyTranscript {
+            transcripts: vec![transcript.clone()],
+            participant_ids: vec!["eth|0x0".to_string()],
+            participant_ecdsa_signatures: vec![String::new()],
+        };
+        let json = serde_json::to_string(&ceremony).unwrap();
+        assert!(json.contains(r#""numG1Powers":16"#));
+        assert!(json.contains(r#""G2Powers":["0x93e02b6052719f607dacd3a0"#));
+        assert!(json.contains(r#""participantEcdsaSignatures":[""]"#));
+        assert_eq!(
+            serde_json::from_str::<CeremonyTranscript>(&json).unwrap(),
+            ceremony
+        );
+
+        let mut bad = transcript.clone();
+        bad.powers_of_tau.g1_powers.swap(3, 4);
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::VerifyFailed));
+        let mut bad = transcript.clone();
+        bad.num_g2_powers = 5;
+        assert_eq!(bad.verify_consistency(&mut rng), Err(Error::LengthMismatch));
+        let mut bad = transcript;
+        bad.powers_of_tau.g1_powers[2] = non_subgroup_g1();
+        assert_eq!(bad.check_subgroups(), Err(Error::PointNotInGroup));
+
+        assert!(
+            serde_json::from_str::<PowersOfTau>(r#"{"G1Powers": ["97f1"], "G2Powers": []}"#)
+                .is_err()
+        );
+        assert!(
+            serde_json::from_str::<PowersOfTau>(r#"{"G1Powers": ["0x97f1"], "G2Powers": []}"#)
+                .is_err()
+        );
+    }
+}
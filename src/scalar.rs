@@ -10,6 +10,10 @@ use core::{
 
 use blst::*;
 use fff::{Field, PrimeField};
+use rand_core::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+use zeroize::{Zeroize, Zeroizing};
 
 /// Represents an element of the scalar field $\mathbb{F}_q$ of the BLS12-381 elliptic
 /// curve construction.
@@ -267,6 +271,29 @@ impl PartialEq for Scalar {
 }
 impl Eq for Scalar {}
 
+impl ConstantTimeEq for Scalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.l[..].ct_eq(&other.0.l[..])
+    }
+}
+
+impl ConditionallySelectable for Scalar {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut out = blst_fr::default();
+        for (out, (a, b)) in out.l.iter_mut().zip(a.0.l.iter().zip(b.0.l.iter())) {
+            *out = u64::conditional_select(a, b, choice);
+        }
+
+        Scalar(out)
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.l.zeroize();
+    }
+}
+
 impl From<Scalar> for ScalarRepr {
     fn from(val: Scalar) -> Self {
         let mut out = [0u64; 4];
@@ -293,7 +320,7 @@ impl From<u64> for Scalar {
     }
 }
 
-impl<'a> Neg for &'a Scalar {
+impl Neg for &Scalar {
     type Output = Scalar;
 
     #[inline]
@@ -314,29 +341,29 @@ impl Neg for Scalar {
     }
 }
 
-impl<'a, 'b> Sub<&'b Scalar> for &'a Scalar {
+impl Sub<&Scalar> for &Scalar {
     type Output = Scalar;
 
     #[inline]
-    fn sub(self, rhs: &'b Scalar) -> Scalar {
+    fn sub(self, rhs: &Scalar) -> Scalar {
         self.sub(rhs)
     }
 }
 
-impl<'a, 'b> Add<&'b Scalar> for &'a Scalar {
+impl Add<&Scalar> for &Scalar {
     type Output = Scalar;
 
     #[inline]
-    fn add(self, rhs: &'b Scalar) -> Scalar {
+    fn add(self, rhs: &Scalar) -> Scalar {
         self.add(rhs)
     }
 }
 
-impl<'a, 'b> Mul<&'b Scalar> for &'a Scalar {
+impl Mul<&Scalar> for &Scalar {
     type Output = Scalar;
 
     #[inline]
-    fn mul(self, rhs: &'b Scalar) -> Scalar {
+    fn mul(self, rhs: &Scalar) -> Scalar {
         self.mul(rhs)
     }
 }
@@ -401,15 +428,7 @@ impl fff::Field for Scalar {
     }
 
     fn inverse(&self) -> Option<Self> {
-        let mut t0 = blst_fr::default();
-        unsafe {
-            blst_fr_eucl_inverse(&mut t0, &self.0);
-        }
-        if self.is_zero() {
-            None
-        } else {
-            Some(Scalar(t0))
-        }
+        self.invert_vartime()
     }
 
     fn frobenius_map(&mut self, _: usize) {
@@ -603,6 +622,159 @@ impl Scalar {
         raw.try_into().ok()
     }
 
+    /// Converts a little-endian byte representation into a `Scalar` in constant
+    /// time, failing if the input is not canonical.
+    pub fn from_bytes_le_ct(bytes: &[u8; 32]) -> CtOption<Scalar> {
+        let mut raw = blst_scalar::default();
+        unsafe { blst_scalar_from_lendian(&mut raw, bytes.as_ptr()) };
+
+        Scalar::from_blst_scalar_ct(&raw)
+    }
+
+    /// Converts a big-endian byte representation into a `Scalar` in constant
+    /// time, failing if the input is not canonical.
+    pub fn from_bytes_be_ct(bytes: &[u8; 32]) -> CtOption<Scalar> {
+        let mut raw = blst_scalar::default();
+        unsafe { blst_scalar_from_bendian(&mut raw, bytes.as_ptr()) };
+
+        Scalar::from_blst_scalar_ct(&raw)
+    }
+
+    /// Reduces a little-endian integer of 512 bits modulo the group order. Uniformly
+    /// random bytes give a scalar whose distribution is statistically close to
+    /// uniform.
+    pub fn from_bytes_wide(bytes: &[u8; 64]) -> Scalar {
+        let mut shift = Scalar::from(1 << 32);
+        shift.square();
+
+        let mut out = Scalar::zero();
+        for limb in bytes.chunks_exact(8).rev() {
+            out *= shift;
+            out += Scalar::from(u64::from_le_bytes(limb.try_into().unwrap()));
+        }
+
+        out
+    }
+
+    /// Hashes a message to a scalar with `hash_to_field` of RFC 9380, expanding it
+    /// to 48 bytes with `expand_message_xmd` over SHA-256 and reducing them as a
+    /// big-endian integer.
+    pub fn hash_to_scalar(msg: &[u8], dst: &[u8]) -> Scalar {
+        let uniform = expand_message_xmd(msg, dst, 48);
+        let mut wide = [0u8; 64];
+        for (out, byte) in wide.iter_mut().zip(uniform.iter().rev()) {
+            *out = *byte;
+        }
+        Scalar::from_bytes_wide(&wide)
+    }
+
+    fn from_blst_scalar_ct(raw: &blst_scalar) -> CtOption<Scalar> {
+        let is_canonical = unsafe { blst_scalar_fr_check(raw) };
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_from_scalar(&mut out, raw) };
+
+        CtOption::new(Scalar(out), Choice::from(is_canonical as u8))
+    }
+
+    /// Computes the multiplicative inverse with blst's Euclidean algorithm, failing
+    /// for zero. The running time depends on the value, see `inverse_ct` for secrets.
+    pub fn invert_vartime(&self) -> Option<Scalar> {
+        if self.is_zero() {
+            return None;
+        }
+
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_eucl_inverse(&mut out, &self.0) };
+        Some(Scalar(out))
+    }
+
+    /// Returns whether this element is a square, zero included, by computing its
+    /// Legendre symbol with the binary Jacobi algorithm. The running time depends on
+    /// the value.
+    pub fn is_square_vartime(&self) -> bool {
+        crate::vartime::legendre_vartime(self.into_repr(), MODULUS) >= 0
+    }
+
+    /// Computes the multiplicative inverse in constant time, failing for zero.
+    pub fn inverse_ct(&self) -> CtOption<Scalar> {
+        // self^(q - 2), blst only has a variable time inversion for this field.
+        let inv = self.pow([
+            0xfffffffeffffffff,
+            0x53bda402fffe5bfe,
+            0x3339d80809a1d805,
+            0x73eda753299d7d48,
+        ]);
+
+        CtOption::new(inv, !self.ct_eq(&Scalar::zero()))
+    }
+
+    /// Computes a square root in constant time, failing if there is none.
+    pub fn sqrt_ct(&self) -> CtOption<Scalar> {
+        // Tonelli-Shank's algorithm for q mod 16 = 1, running every loop for its
+        // maximal number of iterations and selecting the results.
+        // https://eprint.iacr.org/2012/685.pdf (page 12, algorithm 5)
+
+        // w = self^((t - 1) / 2), where q - 1 = t * 2^S
+        let w = self.pow([
+            0x7fff2dff7fffffff,
+            0x04d0ec02a9ded201,
+            0x94cebea4199cec04,
+            0x0000000039f6d3a9,
+        ]);
+
+        let mut v = S;
+        let mut x = *self * w;
+        let mut b = x * w;
+        let mut z = Scalar::root_of_unity();
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b;
+            tmp.square();
+            let mut j_less_than_v = Choice::from(1);
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Scalar::one());
+                let mut squared = Scalar::conditional_select(&tmp, &z, tmp_is_one);
+                squared.square();
+                tmp = Scalar::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Scalar::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Scalar::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Scalar::conditional_select(&result, &x, b.ct_eq(&Scalar::one()));
+            z.square();
+            b *= z;
+            v = k;
+        }
+
+        let mut x2 = x;
+        x2.square();
+        CtOption::new(x, x2.ct_eq(self))
+    }
+
+    /// Returns the 320-bit little-endian encoding of `self + m * q` for a random
+    /// 64-bit `m`. This multiplies points of order `q` like `self`, but its bits change
+    /// on every call.
+    pub(crate) fn randomized_bytes_le<R: RngCore>(&self, rng: &mut R) -> Zeroizing<[u8; 40]> {
+        let k = Zeroizing::new(ScalarRepr::from(*self).0);
+        let m = rng.next_u64() as u128;
+
+        let mut out = Zeroizing::new([0u8; 40]);
+        let mut carry = 0u128;
+        for (i, chunk) in out.chunks_exact_mut(8).enumerate() {
+            let (k, q) = if i < 4 { (k[i], MODULUS.0[i]) } else { (0, 0) };
+            let t = k as u128 + m * q as u128 + carry;
+            chunk.copy_from_slice(&(t as u64).to_le_bytes());
+            carry = t >> 64;
+        }
+
+        out
+    }
+
     /// Converts an element of `Scalar` into a byte representation in
     /// little-endian byte order.
     pub fn to_bytes_le(&self) -> [u8; 32] {
@@ -694,13 +866,65 @@ impl Scalar {
     }
 }
 
+/// Expands a message to `len` uniform bytes with `expand_message_xmd` of RFC 9380
+/// over SHA-256. Tags longer than 255 bytes are hashed first, as specified.
+///
+/// # Panics
+///
+/// Panics if `len` is larger than `255 * 32`.
+pub(crate) fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    assert!(len <= 255 * 32, "expand_message_xmd output too long");
+
+    let oversize;
+    let dst = if dst.len() > 255 {
+        oversize = Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(dst)
+            .finalize();
+        &oversize[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    let b_0 = Sha256::new()
+        .chain([0u8; 64])
+        .chain(msg)
+        .chain((len as u16).to_be_bytes())
+        .chain([0u8])
+        .chain(dst)
+        .chain(dst_len)
+        .finalize();
+
+    let mut out = Vec::with_capacity(len);
+    let mut b_i = [0u8; 32];
+    for i in 1..=(len + 31) / 32 {
+        // b_i = H((b_0 xor b_(i - 1)) || i || DST_prime), with b_0 xor 0 = b_0 for b_1.
+        for (b, b0) in b_i.iter_mut().zip(b_0.iter()) {
+            *b ^= b0;
+        }
+        let hash = Sha256::new()
+            .chain(b_i)
+            .chain([i as u8])
+            .chain(dst)
+            .chain(dst_len)
+            .finalize();
+        b_i.copy_from_slice(&hash[..]);
+        out.extend_from_slice(&b_i);
+    }
+    out.truncate(len);
+
+    out
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Scalar, ScalarRepr, MODULUS, R};
+    use super::{expand_message_xmd, Scalar, ScalarRepr, MODULUS, R};
 
     use fff::{Field, PrimeField, PrimeFieldRepr, SqrtField};
     use rand_core::SeedableRng;
     use rand_xorshift::XorShiftRng;
+    use zeroize::Zeroize;
 
     /// INV = -(q^{-1} mod 2^64) mod 2^64
     const INV: u64 = 0xfffffffeffffffff;
@@ -1032,7 +1256,7 @@ mod tests {
 
         for _ in 0..100 {
             r1 = r1.inverse().unwrap();
-            r2 = r2.pow(&q_minus_2);
+            r2 = r2.pow(q_minus_2);
 
             assert_eq!(r1, r2);
             // Add R so we check something different next time around
@@ -1059,11 +1283,9 @@ mod tests {
         let mut none_count = 0;
 
         for _ in 0..100 {
-            let square_root = square.sqrt();
-            if square_root.is_none() {
-                none_count += 1;
-            } else {
-                assert_eq!(square_root.unwrap() * square_root.unwrap(), square);
+            match square.sqrt() {
+                Some(square_root) => assert_eq!(square_root * square_root, square),
+                None => none_count += 1,
             }
             square -= Scalar::one();
         }
@@ -1895,7 +2117,7 @@ mod tests {
             // Exponentiate by various small numbers and ensure it consists with repeated
             // multiplication.
             let a = Scalar::random(&mut rng);
-            let target = a.pow(&[i]);
+            let target = a.pow([i]);
             let mut c = Scalar::one();
             for _ in 0..i {
                 c.mul_assign(&a);
@@ -2109,6 +2331,79 @@ mod tests {
         assert!(Scalar::multiplicative_generator().sqrt().is_none());
     }
 
+    #[test]
+    fn test_scalar_vartime() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Scalar::random(&mut rng);
+            assert_eq!(a.invert_vartime().unwrap(), a.inverse().unwrap());
+            assert_eq!(
+                a.is_square_vartime(),
+                a.legendre() != fff::LegendreSymbol::QuadraticNonResidue
+            );
+            let mut square = a;
+            square.square();
+            assert!(square.is_square_vartime());
+        }
+
+        assert!(Scalar::zero().invert_vartime().is_none());
+        assert_eq!(Scalar::one().invert_vartime().unwrap(), Scalar::one());
+        assert_eq!((-Scalar::one()).invert_vartime().unwrap(), -Scalar::one());
+        assert!(Scalar::zero().is_square_vartime());
+        assert!(!Scalar::root_of_unity().is_square_vartime());
+    }
+
+    #[test]
+    fn test_scalar_ct() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = Scalar::random(&mut rng);
+            assert_eq!(a.inverse_ct().unwrap(), a.inverse().unwrap());
+
+            let mut square = a;
+            square.square();
+            let root = square.sqrt_ct().unwrap();
+            assert!(root == a || root == -a);
+
+            let b = Scalar::random(&mut rng);
+            assert_eq!(bool::from(b.sqrt_ct().is_some()), b.sqrt().is_some());
+
+            assert_eq!(Scalar::from_bytes_le_ct(&a.to_bytes_le()).unwrap(), a);
+            assert_eq!(Scalar::from_bytes_be_ct(&a.to_bytes_be()).unwrap(), a);
+        }
+
+        assert!(bool::from(Scalar::zero().inverse_ct().is_none()));
+        assert_eq!(Scalar::zero().sqrt_ct().unwrap(), Scalar::zero());
+        assert_eq!(Scalar::one().sqrt_ct().unwrap().pow([2]), Scalar::one());
+
+        let mut modulus = [0u8; 32];
+        for (chunk, limb) in modulus.chunks_exact_mut(8).zip(MODULUS.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        assert!(bool::from(Scalar::from_bytes_le_ct(&modulus).is_none()));
+        modulus.reverse();
+        assert!(bool::from(Scalar::from_bytes_be_ct(&modulus).is_none()));
+
+        crate::tests::field::random_ct_tests::<Scalar>();
+
+        let mut a = Scalar::random(&mut rng);
+        a.zeroize();
+        assert!(a.is_zero());
+    }
+
+    #[test]
+    fn test_scalar_batch_invert() {
+        crate::tests::field::random_batch_invert_tests::<Scalar>();
+    }
+
     #[test]
     fn scalar_field_tests() {
         crate::tests::field::random_field_tests::<Scalar>();
@@ -2122,6 +2417,64 @@ mod tests {
         crate::tests::repr::random_repr_tests::<Scalar>();
     }
 
+    /// Vectors of RFC 9380, appendix K.1.
+    #[test]
+    fn test_expand_message_xmd() {
+        const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+        let vectors: [(&[u8], usize, &str); 3] = [
+            (
+                b"",
+                0x20,
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                b"abc",
+                0x20,
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+            (
+                b"abcdef0123456789",
+                0x20,
+                "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+            ),
+        ];
+        for (msg, len, expected) in vectors.iter() {
+            assert_eq!(hex::encode(expand_message_xmd(msg, DST, *len)), *expected);
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_wide() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut bytes = [0u8; 64];
+        assert_eq!(Scalar::from_bytes_wide(&bytes), Scalar::zero());
+
+        // The modulus plus five reduces to five.
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(MODULUS.as_ref()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes[0] += 5;
+        assert_eq!(Scalar::from_bytes_wide(&bytes), Scalar::from(5));
+
+        for _ in 0..100 {
+            let a = Scalar::random(&mut rng);
+            let b = Scalar::random(&mut rng);
+            bytes[..32].copy_from_slice(&a.to_bytes_le());
+            bytes[32..].copy_from_slice(&b.to_bytes_le());
+
+            // a + b 2^256
+            let mut expected = Scalar::from(2).pow([256]);
+            expected.mul_assign(&b);
+            expected.add_assign(&a);
+            assert_eq!(Scalar::from_bytes_wide(&bytes), expected);
+        }
+    }
+
     #[test]
     fn test_modulus() {
         assert_eq!(
@@ -0,0 +1,310 @@
+//! A wrapper for secret scalars, such as signing keys, which is wiped from memory
+//! when dropped.
+
+use core::fmt;
+
+use blst::*;
+use fff::Field;
+use rand_core::RngCore;
+use subtle::{Choice, ConstantTimeEq, CtOption};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{G1Projective, G2Projective, Scalar};
+
+/// A secret element of the scalar field, typically a signing key.
+///
+/// Unlike `Scalar`, it is not `Copy`, its `Debug` output is redacted and it is zeroed
+/// when dropped. It only exposes the operations needed for signing, and is serialized
+/// explicitly with `to_bytes_le` or the `secret_scalar_serde` module.
+pub struct SecretScalar(Scalar);
+
+impl fmt::Debug for SecretScalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretScalar(<redacted>)")
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+impl ConstantTimeEq for SecretScalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl SecretScalar {
+    /// Wraps `sk`. Copies of `sk` held by the caller are not wiped.
+    pub fn new(sk: Scalar) -> Self {
+        SecretScalar(sk)
+    }
+
+    /// Samples a uniformly random secret.
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        SecretScalar(Scalar::random(rng))
+    }
+
+    /// Converts a little-endian byte representation in constant time, failing if
+    /// the input is not canonical.
+    pub fn from_bytes_le(bytes: &[u8; 32]) -> CtOption<Self> {
+        Scalar::from_bytes_le_ct(bytes).map(SecretScalar)
+    }
+
+    /// Exports the secret in little-endian byte order. The bytes are wiped when the
+    /// returned buffer is dropped.
+    pub fn to_bytes_le(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0.to_bytes_le())
+    }
+
+    /// Computes the public key in $\mathbb{G}_1$, the generator multiplied by the secret.
+    pub fn public_key_g1(&self) -> G1Projective {
+        let mut out = blst_p1::default();
+        self.with_blst_scalar(|sk| unsafe { blst_sk_to_pk_in_g1(&mut out, sk) });
+
+        G1Projective(out)
+    }
+
+    /// Computes the public key in $\mathbb{G}_2$, the generator multiplied by the secret.
+    pub fn public_key_g2(&self) -> G2Projective {
+        let mut out = blst_p2::default();
+        self.with_blst_scalar(|sk| unsafe { blst_sk_to_pk_in_g2(&mut out, sk) });
+
+        G2Projective(out)
+    }
+
+    /// Signs `msg` with a signature in $\mathbb{G}_2$, for a public key in $\mathbb{G}_1$.
+    pub fn sign_g2(&self, msg: &[u8], dst: &[u8], aug: &[u8]) -> G2Projective {
+        let hash = G2Projective::hash_to_curve(msg, dst, aug);
+        let mut out = blst_p2::default();
+        self.with_blst_scalar(|sk| unsafe { blst_sign_pk_in_g1(&mut out, &hash.0, sk) });
+
+        G2Projective(out)
+    }
+
+    /// Signs `msg` with a signature in $\mathbb{G}_1$, for a public key in $\mathbb{G}_2$.
+    pub fn sign_g1(&self, msg: &[u8], dst: &[u8], aug: &[u8]) -> G1Projective {
+        let hash = G1Projective::hash_to_curve(msg, dst, aug);
+        let mut out = blst_p1::default();
+        self.with_blst_scalar(|sk| unsafe { blst_sign_pk_in_g2(&mut out, &hash.0, sk) });
+
+        G1Projective(out)
+    }
+
+    /// Splits the secret into `count` Shamir shares, any `threshold` of which
+    /// determine it. The share at index `i` is the evaluation at `i + 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero or greater than `count`.
+    pub fn share<R: RngCore>(&self, threshold: usize, count: usize, rng: &mut R) -> Vec<Self> {
+        assert!(
+            threshold > 0 && threshold <= count,
+            "the threshold must be between 1 and the number of shares"
+        );
+
+        let coeffs: Vec<SecretScalar> = (1..threshold).map(|_| Self::random(rng)).collect();
+        (1..=count as u64)
+            .map(|x| {
+                let x = Scalar::from(x);
+                let mut share = Scalar::zero();
+                for coeff in coeffs.iter().rev() {
+                    share.add_assign(&coeff.0);
+                    share.mul_assign(&x);
+                }
+                share.add_assign(&self.0);
+                SecretScalar(share)
+            })
+            .collect()
+    }
+
+    /// Returns the secret, for computations elsewhere in the crate, which must wipe
+    /// any copies.
+    pub(crate) fn expose(&self) -> &Scalar {
+        &self.0
+    }
+
+    /// Calls `f` with the secret as a `blst_scalar`, which is wiped afterwards.
+    fn with_blst_scalar<T>(&self, f: impl FnOnce(&blst_scalar) -> T) -> T {
+        let mut sk = blst_scalar::default();
+        unsafe { blst_scalar_from_fr(&mut sk, &(self.0).0) };
+        let out = f(&sk);
+        sk.b.zeroize();
+
+        out
+    }
+}
+
+/// Opt-in serde support for `SecretScalar`, as 32 little-endian bytes.
+///
+/// Use it with `#[serde(with = "blstrs::secret_scalar_serde")]`.
+pub mod secret_scalar_serde {
+    use serde::{de::Error as DeserializeError, Deserializer, Serializer};
+    use zeroize::Zeroizing;
+
+    use super::SecretScalar;
+
+    /// Serializes the secret as a byte string.
+    pub fn serialize<S: Serializer>(sk: &SecretScalar, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(&sk.to_bytes_le()[..])
+    }
+
+    /// Deserializes a canonical secret from a byte string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<SecretScalar, D::Error> {
+        let bytes = Zeroizing::new(crate::serde_impl::deserialize_bytes(d, "a secret scalar")?);
+        let mut buf = Zeroizing::new([0u8; 32]);
+        if bytes.len() != buf.len() {
+            return Err(D::Error::invalid_length(bytes.len(), &"32 bytes"));
+        }
+        buf.copy_from_slice(&bytes);
+
+        Option::from(SecretScalar::from_bytes_le(&buf))
+            .ok_or_else(|| D::Error::custom("secret scalar is not canonical"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pairing, G1Affine, G2Affine};
+
+    use groupy::{CurveAffine, CurveProjective};
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use serde::{Deserialize, Serialize};
+
+    const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    #[test]
+    fn test_secret_scalar_sign() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let scalar = Scalar::random(&mut rng);
+        let sk = SecretScalar::new(scalar);
+        assert_eq!(sk.public_key_g1(), G1Projective::one() * scalar);
+        assert_eq!(sk.public_key_g2(), G2Projective::one() * scalar);
+
+        let sig = sk.sign_g2(b"hello", DST, &[]);
+        assert_eq!(
+            sig,
+            G2Projective::hash_to_curve(b"hello", DST, &[]) * scalar
+        );
+        assert_eq!(
+            pairing(
+                sk.public_key_g1().into_affine(),
+                G2Projective::hash_to_curve(b"hello", DST, &[]).into_affine()
+            ),
+            pairing(G1Affine::one(), sig.into_affine())
+        );
+
+        let sig = sk.sign_g1(b"hello", DST, &[]);
+        assert_eq!(
+            sig,
+            G1Projective::hash_to_curve(b"hello", DST, &[]) * scalar
+        );
+        assert_eq!(
+            pairing(sig.into_affine(), G2Affine::one()),
+            pairing(
+                G1Projective::hash_to_curve(b"hello", DST, &[]).into_affine(),
+                sk.public_key_g2().into_affine()
+            )
+        );
+    }
+
+    #[test]
+    fn test_secret_scalar_redacted() {
+        let mut sk = SecretScalar::new(Scalar::from(42));
+        assert_eq!(format!("{:?}", sk), "SecretScalar(<redacted>)");
+
+        sk.zeroize();
+        assert!(sk.0.is_zero());
+    }
+
+    #[test]
+    fn test_secret_scalar_bytes() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let sk = SecretScalar::random(&mut rng);
+        let bytes = sk.to_bytes_le();
+        let decoded = SecretScalar::from_bytes_le(&bytes).unwrap();
+        assert!(bool::from(sk.ct_eq(&decoded)));
+        assert!(bool::from(
+            SecretScalar::from_bytes_le(&[0xff; 32]).is_none()
+        ));
+
+        #[derive(Serialize, Deserialize)]
+        struct Keystore {
+            #[serde(with = "secret_scalar_serde")]
+            sk: SecretScalar,
+        }
+
+        let json = serde_json::to_string(&Keystore { sk }).unwrap();
+        let keystore: Keystore = serde_json::from_str(&json).unwrap();
+        assert!(bool::from(keystore.sk.ct_eq(&decoded)));
+        assert!(serde_json::from_str::<Keystore>(r#"{"sk":[1,2,3]}"#).is_err());
+    }
+
+    #[test]
+    fn test_secret_scalar_share() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let sk = SecretScalar::random(&mut rng);
+        let shares = sk.share(3, 5, &mut rng);
+        assert_eq!(shares.len(), 5);
+
+        // Any 3 partial signatures combine to the signature, in the exponent.
+        for subset in &[[0usize, 1, 2], [1, 3, 4], [0, 2, 4]] {
+            let mut sig = G2Projective::zero();
+            for &i in subset {
+                let xi = Scalar::from(i as u64 + 1);
+                let mut lambda = Scalar::one();
+                for &j in subset {
+                    if i != j {
+                        let xj = Scalar::from(j as u64 + 1);
+                        let mut den = xj;
+                        den.sub_assign(&xi);
+                        lambda.mul_assign(&xj);
+                        lambda.mul_assign(&den.inverse().unwrap());
+                    }
+                }
+                sig.add_assign(&(shares[i].sign_g2(b"hello", DST, &[]) * lambda));
+            }
+            assert_eq!(sig, sk.sign_g2(b"hello", DST, &[]));
+        }
+
+        // Fewer shares do not.
+        let mut sig = shares[0].sign_g2(b"hello", DST, &[]);
+        sig.add_assign(&shares[1].sign_g2(b"hello", DST, &[]));
+        assert_ne!(sig, sk.sign_g2(b"hello", DST, &[]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_secret_scalar_share_threshold() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        SecretScalar::random(&mut rng).share(4, 3, &mut rng);
+    }
+}
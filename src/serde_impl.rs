@@ -5,11 +5,12 @@ use fff::PrimeField;
 use groupy::{CurveAffine, CurveProjective, EncodedPoint};
 
 use serde::de::{Error as DeserializeError, SeqAccess, Visitor};
-use serde::ser::SerializeTuple;
+use serde::ser::{Error as SerializeError, SerializeTuple};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
-    Fp, Fp12, Fp2, Fp6, FpRepr, G1Affine, G1Projective, G2Affine, G2Projective, Scalar, ScalarRepr,
+    Fp, Fp12, Fp2, Fp6, FpRepr, G1Affine, G1Projective, G2Affine, G2Projective,
+    PairingG1G2Snapshot, PairingG2G1Snapshot, Scalar, ScalarRepr,
 };
 
 const ERR_CODE: &str = "deserialized bytes don't encode a group element";
@@ -62,6 +63,64 @@ impl<'de> Deserialize<'de> for G2Affine {
     }
 }
 
+macro_rules! serde_snapshot {
+    ($snapshot:ident) => {
+        /// Serializes the versioned binary encoding of `write_raw`.
+        impl Serialize for $snapshot {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                let mut buf = Vec::with_capacity(self.raw_fmt_size());
+                self.write_raw(&mut buf).map_err(S::Error::custom)?;
+                s.serialize_bytes(&buf)
+            }
+        }
+
+        /// Deserializes with `read_raw`.
+        impl<'de> Deserialize<'de> for $snapshot {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes(d, concat!("an encoded ", stringify!($snapshot)))?;
+                $snapshot::read_raw(&bytes[..]).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+serde_snapshot!(PairingG1G2Snapshot);
+serde_snapshot!(PairingG2G1Snapshot);
+
+/// Deserializes a byte string, also accepted as a sequence of bytes.
+pub(crate) fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    d: D,
+    expecting: &'static str,
+) -> Result<Vec<u8>, D::Error> {
+    struct BytesVisitor(&'static str);
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+
+        fn visit_bytes<E: DeserializeError>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: DeserializeError>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                v.push(byte);
+            }
+            Ok(v)
+        }
+    }
+
+    d.deserialize_bytes(BytesVisitor(expecting))
+}
+
 /// Serializes a group element using its compressed representation.
 fn serialize_affine<S: Serializer, C: CurveAffine>(c: &C, s: S) -> Result<S::Ok, S::Error> {
     let len = C::Compressed::size();
@@ -314,6 +373,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serde_g2_prepared() {
+        use crate::{g2_prepared_serde_checked, g2_prepared_serde_unchecked};
+        use crate::{G2Prepared, PairingCurveAffine};
+
+        #[derive(Serialize, Deserialize)]
+        struct Checked(#[serde(with = "g2_prepared_serde_checked")] G2Prepared);
+
+        #[derive(Serialize, Deserialize)]
+        struct Unchecked(#[serde(with = "g2_prepared_serde_unchecked")] G2Prepared);
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let g = G2Projective::random(&mut rng).into_affine();
+            let prepared = g.prepare();
+            let ser = serde_json::to_vec(&Checked(prepared.clone())).unwrap();
+            assert_eq!(
+                ser,
+                serde_json::to_vec(&Unchecked(prepared.clone())).unwrap()
+            );
+
+            let de: Checked = serde_json::from_slice(&ser).unwrap();
+            assert_eq!(de.0, prepared);
+            let de: Unchecked = serde_json::from_slice(&ser).unwrap();
+            assert_eq!(de.0, prepared);
+
+            // Lines that are not a preparation only load unchecked.
+            let mut buf = Vec::new();
+            prepared.write_raw(&mut buf).unwrap();
+            buf[2] ^= 1;
+            let ser = serde_json::to_vec(&buf).unwrap();
+            assert!(serde_json::from_slice::<Checked>(&ser).is_err());
+            assert!(serde_json::from_slice::<Unchecked>(&ser).is_ok());
+        }
+
+        let zero = G2Affine::zero().prepare();
+        let ser = serde_json::to_vec(&Checked(zero.clone())).unwrap();
+        let de: Checked = serde_json::from_slice(&ser).unwrap();
+        assert!(de.0.is_zero());
+    }
+
+    #[test]
+    fn serde_pairing_snapshot() {
+        use crate::{PairingG1G2, PairingG1G2Snapshot};
+
+        let dst = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let sk = Scalar::random(&mut rng);
+        let pk = (G1Projective::one() * sk).into_affine();
+        let sig = (G2Projective::hash_to_curve(b"hello", dst, &[]) * sk).into_affine();
+
+        let mut ctx = PairingG1G2::builder(dst).build().unwrap();
+        ctx.aggregate(&pk, Some(&sig), b"hello", &[]).unwrap();
+        let snapshot = ctx.commit().snapshot();
+        let ser = serde_json::to_vec(&snapshot).unwrap();
+        let de: PairingG1G2Snapshot = serde_json::from_slice(&ser).unwrap();
+        assert_eq!(snapshot, de);
+
+        test_roundtrip(
+            &PairingG1G2::builder(dst)
+                .build()
+                .unwrap()
+                .commit()
+                .snapshot(),
+        );
+    }
+
     #[test]
     fn serde_scalar() {
         let mut rng = XorShiftRng::from_seed([
@@ -0,0 +1,903 @@
+//! Non-interactive sigma protocols: proofs of knowledge of discrete logarithms,
+//! of their equality across groups, and of any linear relation between secret
+//! scalars and public group elements.
+//!
+//! A `LinearRelation` is a system of equations `X_i = sum_j w_j B_ij` over a group,
+//! for public images `X_i` and bases `B_ij` and secret scalars `w_j`, which covers
+//! Schnorr proofs, openings of Pedersen commitments and equalities of logarithms
+//! in the same group (Maurer, "Unifying zero-knowledge proofs of knowledge"). A
+//! `DleqProof` proves that `X = x G` and `Y = x H` for the same `x` with `G` and `H`
+//! in any two groups of order `r`, among $\mathbb{G}_1$, $\mathbb{G}_2$ and
+//! $\mathbb{G}_T$.
+//!
+//! The protocols are made non-interactive with a `Transcript`, to which the caller
+//! appends the message and any context the proof is bound to. The nonces are
+//! derived deterministically from the transcript and the secrets, as in RFC 6979,
+//! so that a nonce is never reused for two different statements. The random number
+//! generator given to the provers only blinds the multiplications by the nonces.
+
+use core::fmt;
+use std::io::{self, Read, Write};
+
+use fff::{Field, PrimeField};
+use groupy::CurveProjective;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable};
+use zeroize::Zeroize;
+
+use crate::transcript::Transcript;
+use crate::{Compress, Error, Fp12, G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+
+/// A group of prime order `r` in which sigma protocols are run, written
+/// additively.
+///
+/// It is implemented by `G1Projective` and `G2Projective`, and by `Fp12` for
+/// $\mathbb{G}_T$, whose group law is the multiplication of `Fp12`.
+pub trait SigmaGroup: Copy + PartialEq + fmt::Debug {
+    /// Length of the encoding written by `write`.
+    const ENCODED_SIZE: usize;
+
+    /// The neutral element.
+    fn identity() -> Self;
+
+    /// Returns whether this is the neutral element.
+    fn is_identity(&self) -> bool;
+
+    /// The group law.
+    fn combine(&self, other: &Self) -> Self;
+
+    /// Multiplies by a secret scalar, in constant time. The curve groups blind the
+    /// scalar with `rng`, see `G1Projective::mul_secret`.
+    fn scale<R: RngCore>(&self, s: &Scalar, rng: &mut R) -> Self;
+
+    /// Computes `sum_i scalars[i] bases[i]`, in time depending on the scalars.
+    fn multi_exp_vartime(bases: &[Self], scalars: &[Scalar]) -> Self;
+
+    /// Absorbs the element into a transcript.
+    fn append_to(&self, transcript: &mut Transcript, label: &[u8]);
+
+    /// Writes the element in `ENCODED_SIZE` bytes.
+    fn write<W: Write>(&self, writer: W) -> io::Result<()>;
+
+    /// Reads an element written by `write`, checking that it belongs to the group.
+    fn read<R: Read>(reader: R) -> io::Result<Self>;
+}
+
+macro_rules! impl_sigma_group {
+    ($projective:ident, $affine:ident, $size:expr, $append:ident) => {
+        impl SigmaGroup for $projective {
+            const ENCODED_SIZE: usize = $size;
+
+            fn identity() -> Self {
+                $projective::zero()
+            }
+
+            fn is_identity(&self) -> bool {
+                self.is_zero()
+            }
+
+            fn combine(&self, other: &Self) -> Self {
+                let mut out = *self;
+                CurveProjective::add_assign(&mut out, other);
+                out
+            }
+
+            fn scale<R: RngCore>(&self, s: &Scalar, rng: &mut R) -> Self {
+                self.mul_secret(s, rng)
+            }
+
+            fn multi_exp_vartime(bases: &[Self], scalars: &[Scalar]) -> Self {
+                let mut bases = bases.to_vec();
+                $projective::batch_normalization(&mut bases);
+                let affine: Vec<$affine> = bases.iter().map(|p| p.into_affine()).collect();
+                $projective::multi_exp_vartime(&affine, scalars)
+            }
+
+            fn append_to(&self, transcript: &mut Transcript, label: &[u8]) {
+                transcript.$append(label, &self.into_affine());
+            }
+
+            fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+                writer.write_all(&self.into_affine().to_compressed())
+            }
+
+            fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+                let mut bytes = [0u8; $size];
+                reader.read_exact(&mut bytes)?;
+                Ok($affine::from_compressed(&bytes)?.into())
+            }
+        }
+    };
+}
+
+impl_sigma_group!(G1Projective, G1Affine, 48, append_g1);
+impl_sigma_group!(G2Projective, G2Affine, 96, append_g2);
+
+impl SigmaGroup for Fp12 {
+    /// The torus compression, which exists for all elements of $\mathbb{G}_T$ but
+    /// one.
+    const ENCODED_SIZE: usize = 288;
+
+    fn identity() -> Self {
+        Fp12::one()
+    }
+
+    fn is_identity(&self) -> bool {
+        *self == Fp12::one()
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        self * other
+    }
+
+    fn scale<R: RngCore>(&self, s: &Scalar, _rng: &mut R) -> Self {
+        // Square and multiply, always computing the product.
+        let mut out = Fp12::one();
+        let repr = s.into_repr();
+        for limb in repr.as_ref().iter().rev() {
+            for i in (0..64).rev() {
+                out = out * out;
+                let product = out * self;
+                let bit = Choice::from(((limb >> i) & 1) as u8);
+                out = Fp12::conditional_select(&out, &product, bit);
+            }
+        }
+        out
+    }
+
+    fn multi_exp_vartime(bases: &[Self], scalars: &[Scalar]) -> Self {
+        assert_eq!(bases.len(), scalars.len());
+        bases
+            .iter()
+            .zip(scalars)
+            .fold(Fp12::one(), |acc, (base, s)| acc * base.pow(s.into_repr()))
+    }
+
+    fn append_to(&self, transcript: &mut Transcript, label: &[u8]) {
+        transcript.append_gt(label, self);
+    }
+
+    /// Fails for the elements without a compression, such as one.
+    fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        if self.c1().is_zero() || self.compress().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "element has no torus compression",
+            ));
+        }
+        self.write_compressed(writer)
+    }
+
+    fn read<R: Read>(reader: R) -> io::Result<Self> {
+        let gt = Fp12::read_compressed(reader)?;
+        if gt.pow(Scalar::char()) != Fp12::one() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "element is not in the subgroup of order r",
+            ));
+        }
+        Ok(gt)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Equation<G> {
+    image: G,
+    terms: Vec<(usize, G)>,
+}
+
+/// A system of equations `X_i = sum_j w_j B_ij`, where the scalars `w_j` are the
+/// secret witness.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearRelation<G> {
+    num_scalars: usize,
+    equations: Vec<Equation<G>>,
+}
+
+/// A proof of a `LinearRelation` made of the commitments of the prover, which can
+/// be verified in a batch with `verify_batch`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proof<G> {
+    pub commitments: Vec<G>,
+    pub responses: Vec<Scalar>,
+}
+
+/// A proof of a `LinearRelation` made of the challenge, from which the verifier
+/// recomputes the commitments. It takes a scalar per secret, plus one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactProof {
+    pub challenge: Scalar,
+    pub responses: Vec<Scalar>,
+}
+
+impl<G: SigmaGroup> LinearRelation<G> {
+    /// Creates a relation without equations over `num_scalars` secrets.
+    pub fn new(num_scalars: usize) -> Self {
+        LinearRelation {
+            num_scalars,
+            equations: Vec::new(),
+        }
+    }
+
+    /// The relation `X = x G` of a Schnorr proof.
+    pub fn schnorr(base: G, image: G) -> Self {
+        let mut relation = LinearRelation::new(1);
+        relation.add_equation(image, &[(0, base)]);
+        relation
+    }
+
+    /// Adds the equation `image = sum w_j B` over the terms `(j, B)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a term refers to a secret beyond `num_scalars`.
+    pub fn add_equation(&mut self, image: G, terms: &[(usize, G)]) {
+        assert!(
+            terms.iter().all(|(j, _)| *j < self.num_scalars),
+            "secret index out of range"
+        );
+        self.equations.push(Equation {
+            image,
+            terms: terms.to_vec(),
+        });
+    }
+
+    /// Number of secrets.
+    pub fn num_scalars(&self) -> usize {
+        self.num_scalars
+    }
+
+    /// Number of equations.
+    pub fn num_equations(&self) -> usize {
+        self.equations.len()
+    }
+
+    /// Proves knowledge of a `witness` satisfying the relation, bound to everything
+    /// absorbed by `transcript`.
+    ///
+    /// Fails with `LengthMismatch` if the witness does not have `num_scalars`
+    /// scalars. A witness that does not satisfy the relation yields a proof that
+    /// fails verification.
+    pub fn prove<R: RngCore>(
+        &self,
+        witness: &[Scalar],
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Result<Proof<G>, Error> {
+        let (commitments, _, responses) = self.prove_inner(witness, transcript, rng)?;
+        Ok(Proof {
+            commitments,
+            responses,
+        })
+    }
+
+    /// Proves knowledge of a `witness` as `prove`, with a compact proof.
+    pub fn prove_compact<R: RngCore>(
+        &self,
+        witness: &[Scalar],
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Result<CompactProof, Error> {
+        let (_, challenge, responses) = self.prove_inner(witness, transcript, rng)?;
+        Ok(CompactProof {
+            challenge,
+            responses,
+        })
+    }
+
+    fn prove_inner<R: RngCore>(
+        &self,
+        witness: &[Scalar],
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Result<(Vec<G>, Scalar, Vec<Scalar>), Error> {
+        if witness.len() != self.num_scalars {
+            return Err(Error::LengthMismatch);
+        }
+        self.append_to(transcript);
+
+        let mut nonces = derive_nonces(transcript, witness, self.num_scalars);
+        let commitments: Vec<G> = self
+            .equations
+            .iter()
+            .map(|eq| {
+                eq.terms.iter().fold(G::identity(), |acc, (j, base)| {
+                    acc.combine(&base.scale(&nonces[*j], rng))
+                })
+            })
+            .collect();
+
+        let challenge = challenge(transcript, &commitments);
+        let responses = nonces
+            .iter()
+            .zip(witness)
+            .map(|(k, w)| *k + challenge * w)
+            .collect();
+        for k in nonces.iter_mut() {
+            k.zeroize();
+        }
+
+        Ok((commitments, challenge, responses))
+    }
+
+    /// Verifies a proof bound to everything absorbed by `transcript`.
+    ///
+    /// Fails with `LengthMismatch` if the proof does not have the shape of the
+    /// relation, and with `VerifyFailed` if it is invalid.
+    pub fn verify(&self, proof: &Proof<G>, transcript: &mut Transcript) -> Result<(), Error> {
+        self.check_shape(proof)?;
+        self.append_to(transcript);
+        let challenge = challenge(transcript, &proof.commitments);
+
+        // sum_j s_j B_ij - c X_i - R_i = 0
+        for (eq, commitment) in self.equations.iter().zip(&proof.commitments) {
+            let (mut bases, mut scalars) = eq.verification_terms(&proof.responses, &challenge);
+            bases.push(*commitment);
+            scalars.push(-Scalar::one());
+            if !G::multi_exp_vartime(&bases, &scalars).is_identity() {
+                return Err(Error::VerifyFailed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a compact proof bound to everything absorbed by `transcript`.
+    ///
+    /// Fails with `LengthMismatch` if the proof does not have a response per
+    /// secret, and with `VerifyFailed` if it is invalid.
+    pub fn verify_compact(
+        &self,
+        proof: &CompactProof,
+        transcript: &mut Transcript,
+    ) -> Result<(), Error> {
+        if proof.responses.len() != self.num_scalars {
+            return Err(Error::LengthMismatch);
+        }
+        self.append_to(transcript);
+
+        // R_i = sum_j s_j B_ij - c X_i
+        let commitments: Vec<G> = self
+            .equations
+            .iter()
+            .map(|eq| {
+                let (bases, scalars) = eq.verification_terms(&proof.responses, &proof.challenge);
+                G::multi_exp_vartime(&bases, &scalars)
+            })
+            .collect();
+
+        if challenge(transcript, &commitments) != proof.challenge {
+            return Err(Error::VerifyFailed);
+        }
+
+        Ok(())
+    }
+
+    fn check_shape(&self, proof: &Proof<G>) -> Result<(), Error> {
+        if proof.commitments.len() != self.equations.len()
+            || proof.responses.len() != self.num_scalars
+        {
+            return Err(Error::LengthMismatch);
+        }
+        Ok(())
+    }
+
+    fn append_to(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"num-scalars", &(self.num_scalars as u64).to_le_bytes());
+        transcript.append_message(
+            b"num-equations",
+            &(self.equations.len() as u64).to_le_bytes(),
+        );
+        for eq in &self.equations {
+            eq.image.append_to(transcript, b"image");
+            transcript.append_message(b"num-terms", &(eq.terms.len() as u64).to_le_bytes());
+            for (j, base) in &eq.terms {
+                transcript.append_message(b"index", &(*j as u64).to_le_bytes());
+                base.append_to(transcript, b"base");
+            }
+        }
+    }
+}
+
+impl<G: SigmaGroup> Equation<G> {
+    /// The bases and scalars of `sum_j s_j B_ij - c X_i`.
+    fn verification_terms(
+        &self,
+        responses: &[Scalar],
+        challenge: &Scalar,
+    ) -> (Vec<G>, Vec<Scalar>) {
+        let mut bases = Vec::with_capacity(self.terms.len() + 2);
+        let mut scalars = Vec::with_capacity(self.terms.len() + 2);
+        for (j, base) in &self.terms {
+            bases.push(*base);
+            scalars.push(responses[*j]);
+        }
+        bases.push(self.image);
+        scalars.push(-challenge);
+        (bases, scalars)
+    }
+}
+
+impl<G: SigmaGroup> Proof<G> {
+    /// Reads a proof of a relation with `num_equations` equations over
+    /// `num_scalars` secrets, as written by `write`.
+    pub fn read<R: Read>(
+        mut reader: R,
+        num_equations: usize,
+        num_scalars: usize,
+    ) -> io::Result<Self> {
+        let commitments = (0..num_equations)
+            .map(|_| G::read(&mut reader))
+            .collect::<io::Result<_>>()?;
+        let responses = (0..num_scalars)
+            .map(|_| read_scalar(&mut reader))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Proof {
+            commitments,
+            responses,
+        })
+    }
+
+    /// Writes the commitments followed by the responses, without lengths.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for commitment in &self.commitments {
+            commitment.write(&mut writer)?;
+        }
+        for response in &self.responses {
+            writer.write_all(&response.to_bytes_le())?;
+        }
+        Ok(())
+    }
+}
+
+impl CompactProof {
+    /// Reads a proof of a relation over `num_scalars` secrets, as written by
+    /// `write`.
+    pub fn read<R: Read>(mut reader: R, num_scalars: usize) -> io::Result<Self> {
+        let challenge = read_scalar(&mut reader)?;
+        let responses = (0..num_scalars)
+            .map(|_| read_scalar(&mut reader))
+            .collect::<io::Result<_>>()?;
+
+        Ok(CompactProof {
+            challenge,
+            responses,
+        })
+    }
+
+    /// Writes the challenge followed by the responses, as little-endian scalars.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.challenge.to_bytes_le())?;
+        for response in &self.responses {
+            writer.write_all(&response.to_bytes_le())?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies many proofs over the same group with a single multi-scalar
+/// multiplication, checking a random linear combination of all their equations.
+///
+/// Each proof is checked against the transcript at the same position, as it would
+/// be by `LinearRelation::verify`. Fails with `LengthMismatch` if there are not as
+/// many transcripts as proofs, or if a proof does not have the shape of its
+/// relation, and with `VerifyFailed` if any proof is invalid.
+pub fn verify_batch<G: SigmaGroup, R: RngCore>(
+    proofs: &[(&LinearRelation<G>, &Proof<G>)],
+    transcripts: &mut [Transcript],
+    rng: &mut R,
+) -> Result<(), Error> {
+    if proofs.len() != transcripts.len() {
+        return Err(Error::LengthMismatch);
+    }
+
+    let mut bases = Vec::new();
+    let mut scalars = Vec::new();
+    for ((relation, proof), transcript) in proofs.iter().zip(transcripts.iter_mut()) {
+        relation.check_shape(proof)?;
+        relation.append_to(transcript);
+        let challenge = challenge(transcript, &proof.commitments);
+
+        // rho (sum_j s_j B_ij - c X_i - R_i)
+        for (eq, commitment) in relation.equations.iter().zip(&proof.commitments) {
+            let rho = Scalar::random(rng);
+            for (j, base) in &eq.terms {
+                bases.push(*base);
+                scalars.push(rho * proof.responses[*j]);
+            }
+            bases.push(eq.image);
+            scalars.push(-(rho * challenge));
+            bases.push(*commitment);
+            scalars.push(-rho);
+        }
+    }
+
+    if !G::multi_exp_vartime(&bases, &scalars).is_identity() {
+        return Err(Error::VerifyFailed);
+    }
+
+    Ok(())
+}
+
+/// A proof that `X = x G` and `Y = x H` for the same secret `x`, with `G` and `H`
+/// in possibly different groups (Chaum and Pedersen).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DleqProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+impl DleqProof {
+    /// Length of the encoding written by `write`.
+    pub const SIZE: usize = 64;
+
+    /// Proves that `x = secret g` and `y = secret h`, bound to everything absorbed
+    /// by `transcript`.
+    pub fn prove<G: SigmaGroup, H: SigmaGroup, R: RngCore>(
+        g: &G,
+        x: &G,
+        h: &H,
+        y: &H,
+        secret: &Scalar,
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Self {
+        append_dleq(transcript, g, x, h, y);
+
+        let mut nonce = derive_nonces(transcript, &[*secret], 1)[0];
+        g.scale(&nonce, rng).append_to(transcript, b"commitment");
+        h.scale(&nonce, rng).append_to(transcript, b"commitment");
+        let challenge = transcript.challenge_scalar(b"challenge");
+        let response = nonce + challenge * secret;
+        nonce.zeroize();
+
+        DleqProof {
+            challenge,
+            response,
+        }
+    }
+
+    /// Verifies a proof that `x` and `y` have the same logarithm to the bases `g` and
+    /// `h`, bound to everything absorbed by `transcript`.
+    pub fn verify<G: SigmaGroup, H: SigmaGroup>(
+        &self,
+        g: &G,
+        x: &G,
+        h: &H,
+        y: &H,
+        transcript: &mut Transcript,
+    ) -> Result<(), Error> {
+        append_dleq(transcript, g, x, h, y);
+
+        let scalars = [self.response, -self.challenge];
+        G::multi_exp_vartime(&[*g, *x], &scalars).append_to(transcript, b"commitment");
+        H::multi_exp_vartime(&[*h, *y], &scalars).append_to(transcript, b"commitment");
+
+        if transcript.challenge_scalar(b"challenge") != self.challenge {
+            return Err(Error::VerifyFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Reads a proof written by `write`.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let challenge = read_scalar(&mut reader)?;
+        let response = read_scalar(&mut reader)?;
+        Ok(DleqProof {
+            challenge,
+            response,
+        })
+    }
+
+    /// Writes the challenge and the response as little-endian scalars.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.challenge.to_bytes_le())?;
+        writer.write_all(&self.response.to_bytes_le())
+    }
+}
+
+fn append_dleq<G: SigmaGroup, H: SigmaGroup>(
+    transcript: &mut Transcript,
+    g: &G,
+    x: &G,
+    h: &H,
+    y: &H,
+) {
+    transcript.append_message(b"dleq", &[]);
+    g.append_to(transcript, b"base");
+    x.append_to(transcript, b"image");
+    h.append_to(transcript, b"base");
+    y.append_to(transcript, b"image");
+}
+
+/// Derives the nonces from a copy of the transcript to which the secrets are
+/// appended, so that they depend on both the statement and the witness.
+fn derive_nonces(transcript: &Transcript, witness: &[Scalar], n: usize) -> Vec<Scalar> {
+    let mut rng = transcript.clone();
+    for w in witness {
+        rng.append_scalar(b"witness", w);
+    }
+    (0..n).map(|_| rng.challenge_scalar(b"nonce")).collect()
+}
+
+fn challenge<G: SigmaGroup>(transcript: &mut Transcript, commitments: &[G]) -> Scalar {
+    for commitment in commitments {
+        commitment.append_to(transcript, b"commitment");
+    }
+    transcript.challenge_scalar(b"challenge")
+}
+
+fn read_scalar<R: Read>(reader: &mut R) -> io::Result<Scalar> {
+    let mut bytes = [0u8; 32];
+    reader.read_exact(&mut bytes)?;
+    Scalar::from_bytes_le(&bytes).ok_or_else(|| Error::InvalidScalar.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use groupy::CurveAffine;
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use crate::pairing;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ])
+    }
+
+    fn transcript(message: &[u8]) -> Transcript {
+        let mut transcript = Transcript::new(b"sigma-test");
+        transcript.append_message(b"message", message);
+        transcript
+    }
+
+    fn schnorr_roundtrip<G: SigmaGroup>(base: G, rng: &mut XorShiftRng) {
+        let x = Scalar::random(&mut *rng);
+        let relation = LinearRelation::schnorr(base, base.scale(&x, rng));
+
+        let proof = relation
+            .prove(&[x], &mut transcript(b"hello"), rng)
+            .unwrap();
+        assert_eq!(relation.verify(&proof, &mut transcript(b"hello")), Ok(()));
+        assert_eq!(
+            relation.verify(&proof, &mut transcript(b"other")),
+            Err(Error::VerifyFailed)
+        );
+
+        let mut bytes = Vec::new();
+        proof.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), G::ENCODED_SIZE + 32);
+        assert_eq!(Proof::<G>::read(&bytes[..], 1, 1).unwrap(), proof);
+
+        let compact = relation
+            .prove_compact(&[x], &mut transcript(b"hello"), rng)
+            .unwrap();
+        assert_eq!(compact.responses, proof.responses);
+        assert_eq!(
+            relation.verify_compact(&compact, &mut transcript(b"hello")),
+            Ok(())
+        );
+        assert_eq!(
+            relation.verify_compact(&compact, &mut transcript(b"other")),
+            Err(Error::VerifyFailed)
+        );
+
+        let mut bytes = Vec::new();
+        compact.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(CompactProof::read(&bytes[..], 1).unwrap(), compact);
+
+        // A wrong secret does not yield a valid proof.
+        let wrong = Scalar::random(&mut *rng);
+        let proof = relation
+            .prove(&[wrong], &mut transcript(b"hello"), rng)
+            .unwrap();
+        assert_eq!(
+            relation.verify(&proof, &mut transcript(b"hello")),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_schnorr() {
+        let mut rng = rng();
+        schnorr_roundtrip(G1Projective::one(), &mut rng);
+        schnorr_roundtrip(G2Projective::one(), &mut rng);
+        schnorr_roundtrip(pairing(G1Affine::one(), G2Affine::one()), &mut rng);
+    }
+
+    #[test]
+    fn test_deterministic_nonces() {
+        let mut rng = rng();
+        let x = Scalar::random(&mut rng);
+        let g = G1Projective::one();
+        let relation = LinearRelation::schnorr(g, g.scale(&x, &mut rng));
+
+        // The generator only blinds the multiplications, and does not change the proof.
+        let prove = |message: &[u8], rng: &mut XorShiftRng| {
+            relation.prove(&[x], &mut transcript(message), rng).unwrap()
+        };
+        assert_eq!(prove(b"hello", &mut rng), prove(b"hello", &mut rng));
+        assert_ne!(
+            prove(b"hello", &mut rng).commitments,
+            prove(b"other", &mut rng).commitments
+        );
+
+        // The nonces depend on the secret, not only on the statement.
+        let other = Scalar::random(&mut rng);
+        let proof = relation
+            .prove(&[other], &mut transcript(b"hello"), &mut rng)
+            .unwrap();
+        assert_ne!(proof.commitments, prove(b"hello", &mut rng).commitments);
+    }
+
+    #[test]
+    fn test_linear_relation() {
+        let mut rng = rng();
+        let g = G1Projective::random(&mut rng);
+        let h = G1Projective::random(&mut rng);
+        let k = G1Projective::random(&mut rng);
+        let (a, b) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let c = g.scale(&a, &mut rng).combine(&h.scale(&b, &mut rng));
+
+        // Opening of a Pedersen commitment C = a G + b H, with D = a K.
+        let mut relation = LinearRelation::new(2);
+        relation.add_equation(c, &[(0, g), (1, h)]);
+        relation.add_equation(k.scale(&a, &mut rng), &[(0, k)]);
+        assert_eq!(relation.num_equations(), 2);
+
+        let proof = relation
+            .prove(&[a, b], &mut transcript(b""), &mut rng)
+            .unwrap();
+        assert_eq!(relation.verify(&proof, &mut transcript(b"")), Ok(()));
+        let compact = relation
+            .prove_compact(&[a, b], &mut transcript(b""), &mut rng)
+            .unwrap();
+        assert_eq!(
+            relation.verify_compact(&compact, &mut transcript(b"")),
+            Ok(())
+        );
+
+        let mut tampered = proof.clone();
+        tampered.responses[1] += Scalar::one();
+        assert_eq!(
+            relation.verify(&tampered, &mut transcript(b"")),
+            Err(Error::VerifyFailed)
+        );
+
+        // D is not a multiple of K by the secret of C.
+        let mut wrong = LinearRelation::new(2);
+        wrong.add_equation(c, &[(0, g), (1, h)]);
+        wrong.add_equation(k.scale(&b, &mut rng), &[(0, k)]);
+        let proof = wrong
+            .prove(&[a, b], &mut transcript(b""), &mut rng)
+            .unwrap();
+        assert_eq!(
+            wrong.verify(&proof, &mut transcript(b"")),
+            Err(Error::VerifyFailed)
+        );
+
+        assert_eq!(
+            relation.prove(&[a], &mut transcript(b""), &mut rng),
+            Err(Error::LengthMismatch)
+        );
+        let mut short = compact;
+        short.responses.pop();
+        assert_eq!(
+            relation.verify_compact(&short, &mut transcript(b"")),
+            Err(Error::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn test_dleq() {
+        let mut rng = rng();
+        let x = Scalar::random(&mut rng);
+        let g1 = G1Projective::random(&mut rng);
+        let g2 = G2Projective::random(&mut rng);
+        let gt = pairing(G1Affine::one(), G2Affine::one());
+        let x1 = g1.scale(&x, &mut rng);
+        let x2 = g2.scale(&x, &mut rng);
+        let xt = gt.scale(&x, &mut rng);
+
+        let proof = DleqProof::prove(&g1, &x1, &g2, &x2, &x, &mut transcript(b""), &mut rng);
+        assert_eq!(
+            proof.verify(&g1, &x1, &g2, &x2, &mut transcript(b"")),
+            Ok(())
+        );
+        assert_eq!(
+            proof.verify(&g1, &x1, &g2, &x2, &mut transcript(b"other")),
+            Err(Error::VerifyFailed)
+        );
+
+        let proof = DleqProof::prove(&g2, &x2, &gt, &xt, &x, &mut transcript(b""), &mut rng);
+        assert_eq!(
+            proof.verify(&g2, &x2, &gt, &xt, &mut transcript(b"")),
+            Ok(())
+        );
+
+        let mut bytes = Vec::new();
+        proof.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), DleqProof::SIZE);
+        assert_eq!(DleqProof::read(&bytes[..]).unwrap(), proof);
+
+        // The logarithms differ.
+        let y = Scalar::random(&mut rng);
+        let yt = gt.scale(&y, &mut rng);
+        let proof = DleqProof::prove(&g1, &x1, &gt, &yt, &x, &mut transcript(b""), &mut rng);
+        assert_eq!(
+            proof.verify(&g1, &x1, &gt, &yt, &mut transcript(b"")),
+            Err(Error::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn test_gt_scale() {
+        let mut rng = rng();
+        let gt = pairing(G1Affine::one(), G2Affine::one());
+        let s = Scalar::random(&mut rng);
+        assert_eq!(gt.scale(&s, &mut rng), gt.pow(s.into_repr()));
+        assert_eq!(gt.scale(&Scalar::zero(), &mut rng), Fp12::one());
+
+        let mut bytes = Vec::new();
+        SigmaGroup::write(&gt, &mut bytes).unwrap();
+        assert_eq!(<Fp12 as SigmaGroup>::read(&bytes[..]).unwrap(), gt);
+        assert!(SigmaGroup::write(&Fp12::one(), &mut Vec::new()).is_err());
+
+        // Elements of the cyclotomic subgroup have a compression, but are not all
+        // in the subgroup of order r: f^((p^6 - 1)(p^2 + 1)) for a random f.
+        let f = Fp12::random(&mut rng);
+        let mut outside = f;
+        outside.conjugate();
+        outside *= f.inverse().unwrap();
+        let mut frobenius = outside;
+        frobenius.frobenius_map(2);
+        outside *= frobenius;
+        let mut bytes = Vec::new();
+        outside.write_compressed(&mut bytes).unwrap();
+        assert!(<Fp12 as SigmaGroup>::read(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let mut rng = rng();
+        let g = G1Projective::one();
+
+        let mut relations = Vec::new();
+        let mut proofs = Vec::new();
+        for i in 0..4u8 {
+            let x = Scalar::random(&mut rng);
+            let relation = LinearRelation::schnorr(g, g.scale(&x, &mut rng));
+            proofs.push(
+                relation
+                    .prove(&[x], &mut transcript(&[i]), &mut rng)
+                    .unwrap(),
+            );
+            relations.push(relation);
+        }
+        let transcripts = || (0..4u8).map(|i| transcript(&[i])).collect::<Vec<_>>();
+        let batch = |proofs: &[Proof<G1Projective>], rng: &mut XorShiftRng| {
+            let pairs: Vec<_> = relations.iter().zip(proofs).collect();
+            verify_batch(&pairs, &mut transcripts(), rng)
+        };
+
+        assert_eq!(batch(&proofs, &mut rng), Ok(()));
+
+        let mut tampered = proofs.clone();
+        tampered[2].commitments[0] = tampered[2].commitments[0].combine(&g);
+        assert_eq!(batch(&tampered, &mut rng), Err(Error::VerifyFailed));
+
+        let pairs: Vec<_> = relations.iter().zip(&proofs).collect();
+        assert_eq!(
+            verify_batch(&pairs, &mut transcripts()[..3], &mut rng),
+            Err(Error::LengthMismatch)
+        );
+    }
+}
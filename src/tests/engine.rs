@@ -48,10 +48,49 @@ pub fn engine_tests<E: Engine>() {
         );
     }
 
+    degenerate_tests::<E>();
     random_bilinearity_tests::<E>();
     random_miller_loop_tests::<E>();
 }
 
+fn degenerate_tests<E: Engine>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // Zero has no image under the final exponentiation.
+    assert!(E::final_exponentiation(&E::Fqk::zero()).is_none());
+    assert_eq!(
+        E::final_exponentiation(&E::Fqk::one()).unwrap(),
+        E::Fqk::one()
+    );
+
+    // The empty Miller loop is one.
+    assert_eq!(E::miller_loop(std::iter::empty()), E::Fqk::one());
+
+    // Pairing with the identity is one, and never fails.
+    let a = E::G1::random(&mut rng);
+    let b = E::G2::random(&mut rng);
+    assert_eq!(
+        E::try_pairing(E::G1Affine::zero(), b).unwrap(),
+        E::Fqk::one()
+    );
+    assert_eq!(
+        E::try_pairing(a, E::G2Affine::zero()).unwrap(),
+        E::Fqk::one()
+    );
+    assert_eq!(
+        E::try_pairing(E::G1Affine::zero(), E::G2Affine::zero()).unwrap(),
+        E::Fqk::one()
+    );
+
+    let ab = E::try_pairing(a, b).unwrap();
+    assert_eq!(ab, E::pairing(a, b));
+    assert!(!ab.is_zero());
+    assert_ne!(ab, E::Fqk::one());
+}
+
 fn random_miller_loop_tests<E: Engine>() {
     let mut rng = XorShiftRng::from_seed([
         0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
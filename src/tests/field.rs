@@ -1,6 +1,9 @@
 use fff::{Field, LegendreSymbol, PrimeField, SqrtField};
 use rand_core::{RngCore, SeedableRng};
 use rand_xorshift::XorShiftRng;
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+use crate::BatchInvert;
 
 pub fn random_frobenius_tests<F: Field, C: AsRef<[u64]>>(characteristic: C, maxpower: usize) {
     let mut rng = XorShiftRng::from_seed([
@@ -100,6 +103,101 @@ pub fn random_field_tests<F: Field>() {
     }
 }
 
+pub fn random_ct_tests<F>()
+where
+    F: Field + ConstantTimeEq + ConditionallySelectable + ConditionallyNegatable,
+{
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let a = F::random(&mut rng);
+        let b = F::random(&mut rng);
+
+        assert!(bool::from(a.ct_eq(&a)));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(bool::from(F::zero().ct_eq(&F::zero())));
+
+        assert_eq!(F::conditional_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(F::conditional_select(&a, &b, Choice::from(1)), b);
+
+        let (mut c, mut d) = (a, b);
+        F::conditional_swap(&mut c, &mut d, Choice::from(1));
+        assert_eq!((c, d), (b, a));
+
+        let mut neg_a = a;
+        neg_a.negate();
+        let mut c = a;
+        c.conditional_negate(Choice::from(0));
+        assert_eq!(c, a);
+        c.conditional_negate(Choice::from(1));
+        assert_eq!(c, neg_a);
+    }
+}
+
+pub fn random_batch_invert_tests<F: BatchInvert>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for len in &[0, 1, 2, 7, 100] {
+        // Every third element is zero.
+        let v: Vec<F> = (0..*len)
+            .map(|i| {
+                if i % 3 == 1 {
+                    F::zero()
+                } else {
+                    F::random(&mut rng)
+                }
+            })
+            .collect();
+        let expected: Vec<F> = v
+            .iter()
+            .map(|a| a.inverse().unwrap_or_else(F::zero))
+            .collect();
+        let zeros = v.iter().filter(|a| a.is_zero()).count();
+
+        let mut w = v.clone();
+        assert_eq!(F::batch_invert(&mut w), zeros);
+        assert_eq!(w, expected);
+
+        let mut w = v.clone();
+        assert_eq!(bool::from(F::batch_invert_ct(&mut w)), zeros == 0);
+        assert_eq!(w, expected);
+    }
+
+    let mut v: Vec<F> = (0..10).map(|_| F::random(&mut rng)).collect();
+    let expected: Vec<F> = v.iter().map(|a| a.inverse().unwrap()).collect();
+    assert!(bool::from(F::batch_invert_ct(&mut v)));
+    assert_eq!(v, expected);
+
+    #[cfg(feature = "parallel")]
+    {
+        let mut v: Vec<F> = (0..3000)
+            .map(|i| {
+                if i % 1000 == 0 {
+                    F::zero()
+                } else {
+                    F::random(&mut rng)
+                }
+            })
+            .collect();
+        let mut w = v.clone();
+        assert_eq!(F::par_batch_invert(&mut v), 3);
+        F::batch_invert(&mut w);
+        assert_eq!(v, w);
+
+        let mut v: Vec<F> = (0..3000).map(|_| F::random(&mut rng)).collect();
+        let mut w = v.clone();
+        assert!(bool::from(F::par_batch_invert_ct(&mut v)));
+        F::batch_invert(&mut w);
+        assert_eq!(v, w);
+    }
+}
+
 pub fn from_str_tests<F: PrimeField>() {
     {
         let a = "84395729384759238745923745892374598234705297301958723458712394587103249587213984572934750213947582345792304758273458972349582734958273495872304598234";
@@ -49,6 +49,9 @@ pub trait Engine: ScalarEngine {
     type Fqk: Field + Compress;
 
     /// Perform a miller loop with some number of (G1, G2) pairs.
+    ///
+    /// Pairs where either element is the point at infinity contribute `Fqk::one()`,
+    /// so the result is never zero for valid inputs.
     fn miller_loop<'a, I>(i: I) -> Self::Fqk
     where
         I: IntoIterator<
@@ -59,10 +62,14 @@ pub trait Engine: ScalarEngine {
         >;
 
     /// Perform final exponentiation of the result of a miller loop.
+    ///
+    /// Returns `None` if the input is zero, which is never the output of `miller_loop`
+    /// and has no image in the target group.
     fn final_exponentiation(_: &Self::Fqk) -> Option<Self::Fqk>;
 
-    /// Performs a complete pairing operation `(p, q)`.
-    fn pairing<G1, G2>(p: G1, q: G2) -> Self::Fqk
+    /// Performs a complete pairing operation `(p, q)`, returning `None` if the
+    /// final exponentiation is undefined for the Miller loop output.
+    fn try_pairing<G1, G2>(p: G1, q: G2) -> Option<Self::Fqk>
     where
         G1: Into<Self::G1Affine>,
         G2: Into<Self::G2Affine>,
@@ -70,7 +77,20 @@ pub trait Engine: ScalarEngine {
         Self::final_exponentiation(&Self::miller_loop(
             [(&(p.into().prepare()), &(q.into().prepare()))].iter(),
         ))
-        .unwrap()
+    }
+
+    /// Performs a complete pairing operation `(p, q)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `try_pairing` returns `None`, which does not happen for any
+    /// points of $\mathbb{G}_1$ and $\mathbb{G}_2$.
+    fn pairing<G1, G2>(p: G1, q: G2) -> Self::Fqk
+    where
+        G1: Into<Self::G1Affine>,
+        G2: Into<Self::G2Affine>,
+    {
+        Self::try_pairing(p, q).expect("the miller loop output is never zero")
     }
 }
 
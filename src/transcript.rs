@@ -0,0 +1,174 @@
+//! Fiat-Shamir transcripts hashing the canonical encodings of the prover messages
+//! with SHA-256.
+//!
+//! Every message is absorbed with a label, and the lengths of both, so that the
+//! hashed bytes determine the sequence of messages. Points are absorbed in their
+//! compressed encodings, scalars as 32 little-endian bytes and elements of
+//! $\mathbb{G}_T$ in their torus compression.
+
+use sha2::{Digest, Sha256};
+
+use fff::Field;
+
+use crate::pairing::write_fp12;
+use crate::{Compress, Fp12, G1Affine, G2Affine, Scalar};
+
+/// A Fiat-Shamir transcript, from which challenges are derived as a 512-bit hash
+/// of everything absorbed so far.
+///
+/// The label given to `new` separates the transcripts of different protocols, and
+/// the labels of the messages those of different messages with the same encoding.
+#[derive(Clone)]
+pub struct Transcript(Sha256);
+
+impl Transcript {
+    /// Starts a transcript separated from those of other protocols by `label`.
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript(Sha256::new());
+        transcript.append_message(b"dom-sep", label);
+        transcript
+    }
+
+    /// Absorbs arbitrary bytes.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.0.update((label.len() as u64).to_le_bytes());
+        self.0.update(label);
+        self.0.update((message.len() as u64).to_le_bytes());
+        self.0.update(message);
+    }
+
+    /// Absorbs the compressed encoding of a point of $\mathbb{G}_1$.
+    pub fn append_g1(&mut self, label: &[u8], p: &G1Affine) {
+        self.append_message(label, &p.to_compressed());
+    }
+
+    /// Absorbs the compressed encoding of a point of $\mathbb{G}_2$.
+    pub fn append_g2(&mut self, label: &[u8], p: &G2Affine) {
+        self.append_message(label, &p.to_compressed());
+    }
+
+    /// Absorbs the little-endian encoding of a scalar.
+    pub fn append_scalar(&mut self, label: &[u8], s: &Scalar) {
+        self.append_message(label, &s.to_bytes_le());
+    }
+
+    /// Absorbs the torus compression of an element of $\mathbb{G}_T$, as written by
+    /// `Compress::write_compressed`.
+    ///
+    /// The elements of `Fp12` without a compression, one among them, are absorbed
+    /// as their twelve coefficients instead, which are never confused with a
+    /// compression as they are longer.
+    pub fn append_gt(&mut self, label: &[u8], gt: &Fp12) {
+        let mut bytes = Vec::with_capacity(12 * 48);
+        if gt.c1().is_zero() || gt.compress().is_none() {
+            write_fp12(gt, &mut bytes)
+        } else {
+            gt.write_compressed(&mut bytes)
+        }
+        .expect("writing to a vector does not fail");
+        self.append_message(label, &bytes);
+    }
+
+    /// Derives a challenge from a 512-bit hash of the transcript, reduced modulo the
+    /// group order. The challenge is absorbed, and is never zero.
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> Scalar {
+        loop {
+            self.append_message(label, &[]);
+            let mut wide = [0u8; 64];
+            wide[..32].copy_from_slice(&self.0.clone().chain([0u8]).finalize()[..]);
+            wide[32..].copy_from_slice(&self.0.clone().chain([1u8]).finalize()[..]);
+            let challenge = Scalar::from_bytes_wide(&wide);
+            self.append_scalar(b"challenge", &challenge);
+
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use groupy::CurveAffine;
+
+    use crate::{pairing, PrimeField};
+
+    /// Pins the encodings of all message types, which must not change.
+    #[test]
+    fn test_challenge_vector() {
+        let mut transcript = Transcript::new(b"test");
+        transcript.append_g1(b"g1", &G1Affine::one());
+        transcript.append_g2(b"g2", &G2Affine::one());
+        transcript.append_scalar(b"scalar", &Scalar::from(42));
+        transcript.append_gt(b"gt", &pairing(G1Affine::one(), G2Affine::one()));
+        transcript.append_gt(b"one", &Fp12::one());
+
+        assert_eq!(
+            format!("{}", transcript.challenge_scalar(b"challenge").into_repr()),
+            "0x1d68281cf99ea74b08e45b6f4e60e9d42a43261f193a6868880a8515b6df1889"
+        );
+    }
+
+    #[test]
+    fn test_separation() {
+        let challenge = |label: &[u8], messages: &[(&[u8], &[u8])]| {
+            let mut transcript = Transcript::new(label);
+            for (label, message) in messages {
+                transcript.append_message(label, message);
+            }
+            transcript.challenge_scalar(b"challenge")
+        };
+
+        let base = challenge(b"protocol", &[(b"a", b"message")]);
+        assert_eq!(base, challenge(b"protocol", &[(b"a", b"message")]));
+        assert_ne!(base, challenge(b"other", &[(b"a", b"message")]));
+        assert_ne!(base, challenge(b"protocol", &[(b"b", b"message")]));
+        assert_ne!(base, challenge(b"protocol", &[(b"am", b"essage")]));
+        assert_ne!(
+            base,
+            challenge(b"protocol", &[(b"a", b"mess"), (b"a", b"age")])
+        );
+
+        // Challenges are absorbed, so that successive ones differ.
+        let mut transcript = Transcript::new(b"protocol");
+        let first = transcript.challenge_scalar(b"challenge");
+        assert_ne!(first, transcript.challenge_scalar(b"challenge"));
+    }
+
+    #[test]
+    fn test_typed_messages() {
+        let p = G1Affine::one();
+        let mut typed = Transcript::new(b"protocol");
+        typed.append_g1(b"p", &p);
+        let mut raw = Transcript::new(b"protocol");
+        raw.append_message(b"p", &p.to_compressed());
+        assert_eq!(
+            typed.challenge_scalar(b"challenge"),
+            raw.challenge_scalar(b"challenge")
+        );
+
+        let gt = pairing(G1Affine::one(), G2Affine::one());
+        let mut compressed = Vec::new();
+        gt.write_compressed(&mut compressed).unwrap();
+        let mut typed = Transcript::new(b"protocol");
+        typed.append_gt(b"gt", &gt);
+        let mut raw = Transcript::new(b"protocol");
+        raw.append_message(b"gt", &compressed);
+        assert_eq!(
+            typed.challenge_scalar(b"challenge"),
+            raw.challenge_scalar(b"challenge")
+        );
+
+        // One has no compression, and is not confused with other elements.
+        let mut one = Transcript::new(b"protocol");
+        one.append_gt(b"gt", &Fp12::one());
+        let mut typed = Transcript::new(b"protocol");
+        typed.append_gt(b"gt", &gt);
+        assert_ne!(
+            one.challenge_scalar(b"challenge"),
+            typed.challenge_scalar(b"challenge")
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Variable time algorithms, for use with public data only.
+
+use fff::{Field, PrimeField, PrimeFieldRepr};
+use groupy::CurveProjective;
+
+/// Multiplies `base` by `scalar` using its w-NAF form, in time depending on the scalar.
+pub(crate) fn wnaf_mul<G: CurveProjective>(base: &G, scalar: <G::Scalar as PrimeField>::Repr) -> G {
+    let window = G::recommended_wnaf_for_scalar(scalar);
+
+    // Odd multiples base, 3 base, ..., (2^window - 1) base.
+    let mut table = Vec::with_capacity(1 << (window - 1));
+    let mut dbl = *base;
+    dbl.double();
+    let mut acc = *base;
+    for _ in 0..(1 << (window - 1)) {
+        table.push(acc);
+        acc.add_assign(&dbl);
+    }
+
+    let mut result = G::zero();
+    let mut found_one = false;
+    for digit in wnaf_form(scalar, window).iter().rev() {
+        if found_one {
+            result.double();
+        }
+
+        if *digit > 0 {
+            found_one = true;
+            result.add_assign(&table[(digit / 2) as usize]);
+        } else if *digit < 0 {
+            found_one = true;
+            result.sub_assign(&table[(-digit / 2) as usize]);
+        }
+    }
+
+    result
+}
+
+/// Computes the w-NAF form of `c`, with odd digits in `(-2^window, 2^window)` and at
+/// least `window` zeros after each non-zero digit.
+fn wnaf_form<R: PrimeFieldRepr>(mut c: R, window: usize) -> Vec<i64> {
+    let mut wnaf = Vec::with_capacity(c.num_bits() as usize + 1);
+    let modulus = 1u64 << (window + 1);
+
+    while !c.is_zero() {
+        let mut digit = 0;
+        if c.is_odd() {
+            digit = (c.as_ref()[0] % modulus) as i64;
+            if digit > 1 << window {
+                digit -= modulus as i64;
+            }
+
+            if digit > 0 {
+                c.sub_noborrow(&R::from(digit as u64));
+            } else {
+                c.add_nocarry(&R::from((-digit) as u64));
+            }
+        }
+
+        wnaf.push(digit);
+        c.div2();
+    }
+
+    wnaf
+}
+
+/// Computes `sum_i scalars[i] * bases[i]` with Pippenger's bucket method, in time
+/// depending on the scalars.
+pub(crate) fn multi_exp<G: CurveProjective>(
+    bases: &[G::Affine],
+    scalars: &[<G::Scalar as PrimeField>::Repr],
+) -> G {
+    assert_eq!(bases.len(), scalars.len(), "lengths must match");
+
+    let num_bits = G::Scalar::NUM_BITS as usize;
+    let window = match bases.len() {
+        0..=3 => 1,
+        n => (n as f64).ln().ceil() as usize,
+    };
+    let digit = |s: &<G::Scalar as PrimeField>::Repr, start: usize| -> usize {
+        let limbs = s.as_ref();
+        let (limb, shift) = (start / 64, start % 64);
+        let mut bits = limbs[limb] >> shift;
+        if shift + window > 64 && limb + 1 < limbs.len() {
+            bits |= limbs[limb + 1] << (64 - shift);
+        }
+        (bits & ((1 << window) - 1)) as usize
+    };
+
+    let mut result = G::zero();
+    let mut buckets = vec![G::zero(); (1 << window) - 1];
+    for start in (0..num_bits).step_by(window).rev() {
+        for _ in 0..window {
+            result.double();
+        }
+
+        for bucket in buckets.iter_mut() {
+            *bucket = G::zero();
+        }
+        for (base, scalar) in bases.iter().zip(scalars) {
+            let d = digit(scalar, start);
+            if d != 0 {
+                buckets[d - 1].add_assign_mixed(base);
+            }
+        }
+
+        // sum_d d * buckets[d - 1], as a sum of running sums from the top.
+        let mut running = G::zero();
+        for bucket in buckets.iter().rev() {
+            running.add_assign(bucket);
+            result.add_assign(&running);
+        }
+    }
+
+    result
+}
+
+/// Computes `base^exp` with a sliding window of four bits, in time depending on the
+/// exponent.
+pub(crate) fn pow_vartime<F: Field>(base: &F, exp: &[u64]) -> F {
+    const WINDOW: usize = 4;
+
+    // Odd powers base, base^3, ..., base^15.
+    let mut table = [*base; 1 << (WINDOW - 1)];
+    let mut sqr = *base;
+    sqr.square();
+    for i in 1..table.len() {
+        table[i] = table[i - 1];
+        table[i].mul_assign(&sqr);
+    }
+
+    let bit = |i: usize| (exp[i / 64] >> (i % 64)) & 1;
+    let num_bits = exp.len() * 64;
+
+    let mut result = F::one();
+    let mut i = num_bits;
+    while i > 0 {
+        if bit(i - 1) == 0 {
+            result.square();
+            i -= 1;
+            continue;
+        }
+
+        // Take at most four bits, from the set bit i - 1 down to the lowest set bit.
+        let low = i.saturating_sub(WINDOW);
+        let mut j = low;
+        while bit(j) == 0 {
+            j += 1;
+        }
+        let mut value = 0usize;
+        for k in (j..i).rev() {
+            result.square();
+            value = (value << 1) | bit(k) as usize;
+        }
+        result.mul_assign(&table[value / 2]);
+        i = j;
+    }
+
+    result
+}
+
+/// Computes the Legendre symbol of `a` modulo the odd prime `modulus` with the binary
+/// Jacobi symbol algorithm: 0 if `a` is zero, 1 if it is a non-zero square and -1
+/// otherwise. `a` must be reduced.
+pub(crate) fn legendre_vartime<R: PrimeFieldRepr>(mut a: R, mut n: R) -> i8 {
+    let mut t = 1;
+    while !a.is_zero() {
+        while a.is_even() {
+            a.div2();
+            let r = n.as_ref()[0] & 7;
+            if r == 3 || r == 5 {
+                t = -t;
+            }
+        }
+
+        if a < n {
+            core::mem::swap(&mut a, &mut n);
+            if a.as_ref()[0] & 3 == 3 && n.as_ref()[0] & 3 == 3 {
+                t = -t;
+            }
+        }
+
+        a.sub_noborrow(&n);
+    }
+
+    if n == R::from(1) {
+        t
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{G1Affine, G1Projective, G2Projective, Scalar};
+
+    use groupy::CurveAffine;
+
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_wnaf_mul() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..20 {
+            let k = Scalar::random(&mut rng);
+            let p = G1Projective::random(&mut rng);
+            assert_eq!(wnaf_mul(&p, k.into_repr()), p * k);
+            let q = G2Projective::random(&mut rng);
+            assert_eq!(wnaf_mul(&q, k.into_repr()), q * k);
+        }
+    }
+
+    #[test]
+    fn test_multi_exp() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for &n in &[0, 1, 2, 5, 64, 300] {
+            let mut scalars: Vec<Scalar> = (0..n).map(|_| Scalar::random(&mut rng)).collect();
+            if n > 2 {
+                scalars[1] = Scalar::zero();
+                scalars[2] = -Scalar::one();
+            }
+            let p: Vec<G1Projective> = (0..n).map(|_| G1Projective::random(&mut rng)).collect();
+            let q: Vec<G2Projective> = (0..n).map(|_| G2Projective::random(&mut rng)).collect();
+
+            let mut expected_p = G1Projective::zero();
+            let mut expected_q = G2Projective::zero();
+            for ((p, q), s) in p.iter().zip(&q).zip(&scalars) {
+                expected_p.add_assign(&(*p * s));
+                expected_q.add_assign(&(*q * s));
+            }
+
+            let p: Vec<_> = p.iter().map(|p| p.into_affine()).collect();
+            let q: Vec<_> = q.iter().map(|q| q.into_affine()).collect();
+            assert_eq!(G1Projective::multi_exp_vartime(&p, &scalars), expected_p);
+            assert_eq!(G2Projective::multi_exp_vartime(&q, &scalars), expected_q);
+        }
+
+        let p = [G1Affine::zero(), G1Affine::one()];
+        let scalars = [Scalar::from(3), Scalar::from(5)];
+        assert_eq!(
+            G1Projective::multi_exp_vartime(&p, &scalars),
+            G1Projective::one() * Scalar::from(5)
+        );
+    }
+
+    #[test]
+    fn test_wnaf_form() {
+        use crate::ScalarRepr;
+
+        for window in 2..6 {
+            for c in 0..1000u64 {
+                let wnaf = wnaf_form(ScalarRepr::from(c), window);
+                let value = wnaf.iter().rev().fold(0i64, |acc, d| 2 * acc + d);
+                assert_eq!(value, c as i64);
+                for (i, d) in wnaf.iter().enumerate() {
+                    assert!(*d == 0 || (d % 2 != 0 && d.abs() < 1 << window));
+                    if *d != 0 {
+                        assert!(wnaf[i + 1..].iter().take(window).all(|d| *d == 0));
+                    }
+                }
+            }
+        }
+    }
+}